@@ -0,0 +1,1027 @@
+//! A command-line interface to the icon catalog, for looking up icons from terminals and
+//! scripts without launching the browser.
+
+use iced_material_icon_browser::diff::{DiffKind, SnapshotDiff};
+use iced_material_icon_browser::export::{RustModuleOptions, RustModuleStyle};
+use iced_material_icon_browser::raster::{self, DensitySet, RasterOptions, Rgba};
+use iced_material_icon_browser::resources::ICONS_FONT_BYTES;
+use iced_material_icon_browser::subset;
+use iced_material_icon_browser::usage::{IconReference, ProjectUsage};
+use iced_material_icon_browser::validation::{self, Severity, ValidationReport};
+use iced_material_icon_browser::{
+    export, Collections, GlyphOutline, LoadError, MaterialFontMeta, MaterialFontMetaList, Query,
+    SearchQuery,
+};
+
+const USAGE: &str = "Usage: material-icons [--meta <metadata.json>] [--json] <command>
+
+Commands:
+  search <query>           Icons matching <query>, most relevant first (typos allowed, filters
+                           like `tag:arrow -cat:av pop:>1000 cp:e5c4..e5ff name:/^arrow_/`)
+  info <name|codepoint>    Details of a single icon (codepoint: 58820, e5c4, 0xe5c4 or U+E5C4)
+  categories               All categories, with their icon count
+  list [--category <name>] All icons, optionally restricted to a category
+  svg <name|codepoint>     The icon as an SVG document (24x24), drawn from the font's glyph
+  png <name|codepoint>...  Renders icons to PNG files, printing their paths
+  rust [<name|codepoint>...]
+                           A Rust module declaring the codepoints of icons (of all icons by
+                           default)
+  subset <name|codepoint>...
+                           Writes MaterialIcons-Subset.ttf, a font with only the glyphs of the
+                           icons (with their codepoints), and the matching metadata file
+                           (failing if either file exists)
+  usage <directory>        The icons referenced by the source files of a project (e.g.
+                           `char::from_u32(0xe5c4)`, `Icons.home`), and the unknown ones
+  validate                 Checks the metadata against the font (icons without glyphs, glyphs
+                           without icons, duplicate codepoints and names), failing on errors
+  diff <old.json> [<new.json>]
+                           The icons added, removed, renamed, re-coded or changed (tags,
+                           categories, version) between two metadata files (the new one being
+                           `--meta`, or the bundled one, by default)
+
+Options:
+  -h, --help               Print this help (when given before the command)
+  --                       Take the remaining arguments as they are (e.g. `search -- --help`)
+  --meta <metadata.json>   Use another metadata file instead of the bundled one
+  --font <font.ttf>        Use another icon font instead of the bundled one (for `svg`, `png`,
+                           `subset` and `validate`)
+  --json                   Print JSON instead of human-readable output
+  --category <name>        Add the icons of a category (for `png`, `rust` and `subset`)
+  --collection <name>      Add the icons of one of the browser's collections (likewise)
+  --out <directory>        Where `png` and `subset` write files [.]
+
+PNG options:
+  --size <px>              The icon size (18, 24, 36, 48 or any other), before scaling [24]
+  --color <#rrggbb[aa]>    The icon color [#000000]
+  --background <color>     The background color, or `transparent` [transparent]
+  --padding <px>           The space around the icon, before scaling [0]
+  --density <set>          `single` (<name>.png), `android` (drawable-mdpi..xxxhdpi/ic_<name>.png)
+                           or `ios` (<name>.png, <name>@2x.png, <name>@3x.png) [single]
+
+Rust options:
+  --enum                   Declare a `MaterialIcon` enum instead of `CODEPOINT_<NAME>` constants
+  --iced                   Add an `icon(...)` function returning an Iced `text` widget";
+
+/// The name (without extension) of the font and metadata files written by `subset`.
+const SUBSET_FILE_NAME: &str = "MaterialIcons-Subset";
+
+const EXIT_SUCCESS: i32 = 0;
+const EXIT_FAILURE: i32 = 1;
+const EXIT_USAGE: i32 = 2;
+
+enum Command {
+    Search(String),
+    Info(String),
+    Categories,
+    List(Option<String>),
+    Svg(String),
+    Png(IconArgs),
+    Rust(IconArgs),
+    Subset(IconArgs),
+    Usage(std::path::PathBuf),
+    Validate,
+    Diff(std::path::PathBuf, Option<std::path::PathBuf>),
+}
+
+/// The icons a command applies to: those named (or with the codepoints) `keys`, then those of
+/// `category` and `collection`.
+struct IconArgs {
+    keys: Vec<String>,
+    category: Option<String>,
+    collection: Option<String>,
+}
+
+impl IconArgs {
+    fn is_empty(&self) -> bool {
+        self.keys.is_empty() && self.category.is_none() && self.collection.is_none()
+    }
+}
+
+#[derive(Default)]
+struct PngArgs {
+    options: RasterOptions,
+    densities: DensitySet,
+}
+
+struct Args {
+    meta: Option<std::path::PathBuf>,
+    font: Option<std::path::PathBuf>,
+    json: bool,
+    out: std::path::PathBuf,
+    png: PngArgs,
+    rust: RustModuleOptions,
+    command: Command,
+}
+
+fn parse_pixels(option: &str, value: Option<String>) -> Result<u32, String> {
+    let value = value.ok_or_else(|| format!("Missing value after `{}`", option))?;
+    value
+        .parse::<u32>()
+        .map_err(|_| format!("Invalid number of pixels `{}` for `{}`", value, option))
+}
+
+fn parse_color(option: &str, value: Option<String>) -> Result<Rgba, String> {
+    let value = value.ok_or_else(|| format!("Missing color after `{}`", option))?;
+    Rgba::parse(&value).ok_or_else(|| format!("Invalid color `{}` for `{}`", value, option))
+}
+
+impl Args {
+    /// Parses the arguments (without the program name), returning `None` when help is requested
+    /// before the command.
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut meta = None;
+        let mut font = None;
+        let mut json = false;
+        let mut category = None;
+        let mut png = PngArgs::default();
+        let mut png_option = None;
+        let mut out = None;
+        let mut collection = None;
+        let mut rust = RustModuleOptions::default();
+        let mut rust_option = None;
+        let mut positional = vec![];
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                // NOTE: After the command, `--help` may be an argument of the command (e.g. a
+                // search query given after `--`).
+                "-h" | "--help" if positional.is_empty() => return Ok(None),
+                "--" => {
+                    positional.extend(args.by_ref());
+                    break;
+                }
+                "--meta" => meta = Some(args.next().ok_or("Missing path after `--meta`")?.into()),
+                "--font" => font = Some(args.next().ok_or("Missing path after `--font`")?.into()),
+                "--category" => {
+                    category = Some(args.next().ok_or("Missing name after `--category`")?)
+                }
+                "--json" => json = true,
+                "--size" => png.options.size = parse_pixels(&arg, args.next())?,
+                "--padding" => png.options.padding = parse_pixels(&arg, args.next())?,
+                "--color" => png.options.foreground = parse_color(&arg, args.next())?,
+                "--background" => {
+                    png.options.background = match args.next().as_deref() {
+                        Some("transparent") => None,
+                        value => Some(parse_color(&arg, value.map(String::from))?),
+                    }
+                }
+                "--density" => {
+                    let value = args.next().ok_or("Missing set after `--density`")?;
+                    png.densities = DensitySet::parse(&value)
+                        .ok_or_else(|| format!("Unknown density set `{}`", value))?;
+                }
+                "--out" => out = Some(args.next().ok_or("Missing path after `--out`")?.into()),
+                "--collection" => {
+                    collection = Some(args.next().ok_or("Missing name after `--collection`")?)
+                }
+                "--enum" => {
+                    rust.style = RustModuleStyle::Enum;
+                    rust_option = Some(arg);
+                    continue;
+                }
+                "--iced" => {
+                    rust.iced_helper = true;
+                    rust_option = Some(arg);
+                    continue;
+                }
+                _ if arg.starts_with("--") => return Err(format!("Unknown option `{}`", arg)),
+                _ => {
+                    positional.push(arg);
+                    continue;
+                }
+            }
+            if [
+                "--size",
+                "--padding",
+                "--color",
+                "--background",
+                "--density",
+            ]
+            .contains(&arg.as_str())
+            {
+                png_option = Some(arg);
+            }
+        }
+
+        let mut positional = positional.into_iter();
+        let command = match positional.next().as_deref() {
+            Some("search") => Command::Search(positional.next().ok_or("Missing search query")?),
+            Some("info") => {
+                Command::Info(positional.next().ok_or("Missing icon name or codepoint")?)
+            }
+            Some("categories") => Command::Categories,
+            Some("list") => Command::List(category.take()),
+            Some("svg") => Command::Svg(positional.next().ok_or("Missing icon name or codepoint")?),
+            Some(command @ ("png" | "subset")) => {
+                let icons = IconArgs {
+                    keys: positional.by_ref().collect(),
+                    category: category.take(),
+                    collection: collection.take(),
+                };
+                if icons.is_empty() {
+                    return Err(
+                        "Missing icon names, codepoints, `--category` or `--collection`".into(),
+                    );
+                }
+                match command {
+                    "png" => Command::Png(icons),
+                    _ => Command::Subset(icons),
+                }
+            }
+            Some("rust") => Command::Rust(IconArgs {
+                keys: positional.by_ref().collect(),
+                category: category.take(),
+                collection: collection.take(),
+            }),
+            Some("usage") => Command::Usage(positional.next().ok_or("Missing directory")?.into()),
+            Some("validate") => Command::Validate,
+            Some("diff") => Command::Diff(
+                positional.next().ok_or("Missing metadata file")?.into(),
+                positional.next().map(std::path::PathBuf::from),
+            ),
+            Some(command) => return Err(format!("Unknown command `{}`", command)),
+            None => return Err("Missing command".into()),
+        };
+        if let Some(arg) = positional.next() {
+            return Err(format!("Unexpected argument `{}`", arg));
+        }
+        if category.is_some() {
+            return Err(
+                "`--category` is only supported by `list`, `png`, `rust` and `subset`".into(),
+            );
+        }
+        if collection.is_some() {
+            return Err("`--collection` is only supported by `png`, `rust` and `subset`".into());
+        }
+        if font.is_some()
+            && !matches!(
+                command,
+                Command::Svg(_) | Command::Png(..) | Command::Subset(..) | Command::Validate
+            )
+        {
+            return Err(
+                "`--font` is only supported by `svg`, `png`, `subset` and `validate`".into(),
+            );
+        }
+        if out.is_some() && !matches!(command, Command::Png(..) | Command::Subset(..)) {
+            return Err("`--out` is only supported by `png` and `subset`".into());
+        }
+        if let Some(option) = png_option {
+            if !matches!(command, Command::Png(..)) {
+                return Err(format!("`{}` is only supported by `png`", option));
+            }
+        }
+        if let Some(option) = rust_option {
+            if !matches!(command, Command::Rust(..)) {
+                return Err(format!("`{}` is only supported by `rust`", option));
+            }
+        }
+
+        Ok(Some(Self {
+            meta,
+            font,
+            json,
+            out: out.unwrap_or_else(|| ".".into()),
+            png,
+            rust,
+            command,
+        }))
+    }
+}
+
+fn load_font(path: &Option<std::path::PathBuf>) -> Result<std::borrow::Cow<'static, [u8]>, String> {
+    match path {
+        Some(path) => std::fs::read(path)
+            .map(std::borrow::Cow::from)
+            .map_err(|e| format!("The font could not be read: {}", e)),
+        None => Ok(std::borrow::Cow::from(ICONS_FONT_BYTES)),
+    }
+}
+
+fn load_meta_list(path: &Option<std::path::PathBuf>) -> Result<MaterialFontMetaList, LoadError> {
+    match path {
+        Some(path) => MaterialFontMetaList::load_from_path_blocking(path),
+        None => MaterialFontMetaList::bundled(),
+    }
+}
+
+#[derive(serde::Serialize)]
+struct ItemInfo<'a> {
+    #[serde(flatten)]
+    item: &'a MaterialFontMeta,
+    codepoint_hex: String,
+    aliases: Vec<&'a String>,
+    missing_families: Vec<String>,
+}
+
+fn to_json(value: &impl serde::Serialize) -> String {
+    serde_json::to_string_pretty(value).expect("the output should be serializable")
+}
+
+fn format_items(items: &[&MaterialFontMeta], json: bool) -> String {
+    if json {
+        return to_json(&items);
+    }
+    items
+        .iter()
+        .map(|item| {
+            format!(
+                "{}  {:<8} {}",
+                item.to_hex_codepoint(),
+                item.codepoint(),
+                item.name()
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn format_item_info(
+    item: &MaterialFontMeta,
+    meta_list: &MaterialFontMetaList,
+    json: bool,
+) -> String {
+    let missing_families = meta_list.missing_families(item);
+    let aliases = meta_list
+        .aliases(item)
+        .into_iter()
+        .map(|alias| alias.name())
+        .collect::<Vec<&String>>();
+    if json {
+        return to_json(&ItemInfo {
+            item,
+            codepoint_hex: item.to_hex_codepoint(),
+            aliases,
+            missing_families,
+        });
+    }
+    let aliases = if aliases.is_empty() {
+        String::from("(none)")
+    } else {
+        aliases
+            .iter()
+            .map(|alias| alias.as_str())
+            .collect::<Vec<&str>>()
+            .join(", ")
+    };
+    let missing_families = if missing_families.is_empty() {
+        String::from("(available in all families)")
+    } else {
+        missing_families.join(", ")
+    };
+    let sizes = item
+        .sizes_px()
+        .iter()
+        .map(|size| size.to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
+    [
+        ("Name", item.name().clone()),
+        ("Aliases", aliases),
+        ("Codepoint (hex)", item.to_hex_codepoint()),
+        ("Codepoint (u32)", item.codepoint().to_string()),
+        ("Version", item.version().to_string()),
+        ("Popularity", item.popularity().to_string()),
+        ("Categories", item.categories().join(", ")),
+        ("Tags", item.tags().join(", ")),
+        ("Sizes (px)", sizes),
+        ("Missing from", missing_families),
+    ]
+    .iter()
+    .map(|(label, value)| format!("{:<17} {}", format!("{}:", label), value))
+    .collect::<Vec<String>>()
+    .join("\n")
+}
+
+fn format_categories(meta_list: &MaterialFontMetaList, json: bool) -> String {
+    let counts = meta_list
+        .categories()
+        .iter()
+        .map(|category| {
+            let count = meta_list
+                .items()
+                .iter()
+                .filter(|item| item.contains_category(category))
+                .count();
+            (category, count)
+        })
+        .collect::<Vec<(&String, usize)>>();
+    if json {
+        let value = counts
+            .iter()
+            .map(|(name, count)| serde_json::json!({"name": name, "count": count}))
+            .collect::<Vec<serde_json::Value>>();
+        return to_json(&value);
+    }
+    counts
+        .iter()
+        .map(|(name, count)| format!("{:<16} {}", name, count))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// The icons of `icons`, in the order of the arguments.
+fn lookup_items<'a>(
+    meta_list: &'a MaterialFontMetaList,
+    icons: &IconArgs,
+) -> Result<Vec<&'a MaterialFontMeta>, String> {
+    let mut items = icons
+        .keys
+        .iter()
+        .map(|key| {
+            meta_list
+                .lookup(key)
+                .ok_or_else(|| format!("No icon named or with codepoint `{}`", key))
+        })
+        .collect::<Result<Vec<&MaterialFontMeta>, String>>()?;
+    if let Some(category) = &icons.category {
+        if !meta_list.categories().contains(category) {
+            return Err(format!("Unknown category `{}`", category));
+        }
+        items.extend(Query::new().category(Some(category.clone())).run(meta_list));
+    }
+    if let Some(name) = &icons.collection {
+        let collections = load_collections()?;
+        let collection = collections
+            .get(name)
+            .ok_or_else(|| format!("Unknown collection `{}`", name))?;
+        items.extend(
+            collection
+                .icons()
+                .iter()
+                .filter_map(|icon| meta_list.get_item_by_name(icon)),
+        );
+    }
+    Ok(items)
+}
+
+/// The collections saved by the browser.
+fn load_collections() -> Result<Collections, String> {
+    let path = Collections::default_path().ok_or("No configuration directory")?;
+    match std::fs::read(&path) {
+        Ok(bytes) => {
+            Collections::from_json(&bytes).map_err(|e| format!("{}: {}", path.display(), e))
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Collections::default()),
+        Err(e) => Err(format!("{}: {}", path.display(), e)),
+    }
+}
+
+/// Renders `items` to PNG files in the output directory, returning their paths.
+fn write_pngs(
+    items: &[&MaterialFontMeta],
+    font: &[u8],
+    png: &PngArgs,
+    out: &std::path::Path,
+) -> Result<Vec<std::path::PathBuf>, String> {
+    let mut paths = vec![];
+    for item in items {
+        let files = raster::render_icon(font, item, &png.options, png.densities)
+            .map_err(|e| format!("{}: {}", item.name(), e))?;
+        for (path, bytes) in files {
+            paths.push(write_file(&out.join(path), bytes)?);
+        }
+    }
+    Ok(paths)
+}
+
+/// Writes `contents` to `path`, creating its parent directories if needed.
+fn write_file(
+    path: &std::path::Path,
+    contents: impl AsRef<[u8]>,
+) -> Result<std::path::PathBuf, String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("{}: {}", parent.display(), e))?;
+    }
+    std::fs::write(path, contents).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(path.into())
+}
+
+fn format_paths(paths: &[std::path::PathBuf], json: bool) -> String {
+    if json {
+        return to_json(&paths);
+    }
+    paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn format_usage(usage: &ProjectUsage, meta_list: &MaterialFontMetaList, json: bool) -> String {
+    let used_icons = usage.used_icons(meta_list);
+    let unknown = usage.unknown_references(meta_list);
+    if json {
+        let reference_json = |reference: &IconReference| {
+            serde_json::json!({
+                "path": reference.path,
+                "line": reference.line,
+                "kind": reference.kind.to_string(),
+            })
+        };
+        let icons = used_icons
+            .iter()
+            .map(|(item, references)| {
+                serde_json::json!({
+                    "name": item.name(),
+                    "codepoint_hex": item.to_hex_codepoint(),
+                    "references": references.iter().map(|r| reference_json(r)).collect::<Vec<_>>(),
+                })
+            })
+            .collect::<Vec<serde_json::Value>>();
+        let unknown = unknown
+            .iter()
+            .map(|reference| {
+                let mut value = reference_json(reference);
+                value["key"] = reference.key.to_string().into();
+                value
+            })
+            .collect::<Vec<serde_json::Value>>();
+        let skipped = usage
+            .skipped()
+            .iter()
+            .map(|(path, error)| serde_json::json!({ "path": path, "error": error }))
+            .collect::<Vec<serde_json::Value>>();
+        return to_json(&serde_json::json!({
+            "root": usage.root(),
+            "icons": icons,
+            "unknown": unknown,
+            "skipped": skipped,
+        }));
+    }
+    let mut lines = vec![];
+    for (item, references) in &used_icons {
+        lines.push(format!("{}  {}", item.to_hex_codepoint(), item.name()));
+        lines.extend(
+            references
+                .iter()
+                .map(|reference| format!("      {}  ({})", reference.location(), reference.kind)),
+        );
+    }
+    if !unknown.is_empty() {
+        lines.push(format!("Unknown icons ({}):", unknown.len()));
+        lines.extend(unknown.iter().map(|reference| {
+            format!(
+                "{}  {}  ({})",
+                reference.key,
+                reference.location(),
+                reference.kind
+            )
+        }));
+    }
+    if !usage.skipped().is_empty() {
+        lines.push(format!("Skipped ({}):", usage.skipped().len()));
+        lines.extend(
+            usage
+                .skipped()
+                .iter()
+                .map(|(path, error)| format!("{}  ({})", path.display(), error)),
+        );
+    }
+    lines.join("\n")
+}
+
+fn format_validation(report: &ValidationReport, json: bool) -> String {
+    let errors = report.count(Severity::Error);
+    let warnings = report.count(Severity::Warning);
+    if json {
+        let problems = report
+            .problems()
+            .iter()
+            .map(|problem| {
+                let mut value = serde_json::to_value(problem).expect("problems are serializable");
+                value["severity"] = problem.severity().to_string().into();
+                value["message"] = problem.to_string().into();
+                value
+            })
+            .collect::<Vec<serde_json::Value>>();
+        return to_json(&serde_json::json!({
+            "valid": report.is_valid(),
+            "errors": errors,
+            "warnings": warnings,
+            "problems": problems,
+        }));
+    }
+    let mut lines = report
+        .problems()
+        .iter()
+        .map(|problem| format!("{:<8} {}", problem.severity(), problem))
+        .collect::<Vec<String>>();
+    lines.push(format!("{} error(s), {} warning(s)", errors, warnings));
+    lines.join("\n")
+}
+
+fn format_diff(diff: &SnapshotDiff, json: bool) -> String {
+    if json {
+        let buckets = DiffKind::ALL
+            .iter()
+            .map(|kind| {
+                let key = serde_json::to_value(kind).expect("buckets are serializable");
+                let key = key.as_str().unwrap_or_default().to_string();
+                (key, serde_json::json!(diff.bucket(*kind)))
+            })
+            .collect::<serde_json::Map<String, serde_json::Value>>();
+        return to_json(&buckets);
+    }
+    if diff.is_empty() {
+        return String::from("No differences");
+    }
+    let mut lines = vec![];
+    for kind in DiffKind::ALL {
+        let entries = diff.bucket(kind);
+        if entries.is_empty() {
+            continue;
+        }
+        lines.push(format!("{} ({}):", kind, entries.len()));
+        lines.extend(entries.iter().map(|entry| format!("  {}", entry)));
+    }
+    lines.join("\n")
+}
+
+/// Runs the command, returning its output and whether it succeeded (`validate` prints its report
+/// even when it finds errors).
+fn run(args: Args) -> Result<(String, bool), String> {
+    let meta_list = load_meta_list(&args.meta).map_err(|e| e.to_string())?;
+    let output = match args.command {
+        Command::Search(text) => {
+            let search =
+                SearchQuery::parse(&text).map_err(|e| format!("Invalid search query: {}", e))?;
+            let items = Query::new().search(search).run(&meta_list);
+            format_items(&items, args.json)
+        }
+        Command::Info(key) => match meta_list.lookup(&key) {
+            Some(item) => format_item_info(item, &meta_list, args.json),
+            None => return Err(format!("No icon named or with codepoint `{}`", key)),
+        },
+        Command::Categories => format_categories(&meta_list, args.json),
+        Command::List(category) => {
+            if let Some(category) = &category {
+                if !meta_list.categories().contains(category) {
+                    return Err(format!("Unknown category `{}`", category));
+                }
+            }
+            let items = Query::new().category(category).run(&meta_list);
+            format_items(&items, args.json)
+        }
+        Command::Svg(key) => {
+            let item = meta_list
+                .lookup(&key)
+                .ok_or_else(|| format!("No icon named or with codepoint `{}`", key))?;
+            let font = load_font(&args.font)?;
+            let outline =
+                GlyphOutline::from_font(&font, item.codepoint()).map_err(|e| e.to_string())?;
+            let svg = export::to_svg(&outline);
+            if args.json {
+                to_json(&serde_json::json!({"name": item.name(), "svg": svg}))
+            } else {
+                svg.trim_end().into()
+            }
+        }
+        Command::Png(icons) => {
+            let items = lookup_items(&meta_list, &icons)?;
+            let font = load_font(&args.font)?;
+            let paths = write_pngs(&items, &font, &args.png, &args.out)?;
+            format_paths(&paths, args.json)
+        }
+        Command::Rust(icons) => {
+            let items = if icons.is_empty() {
+                meta_list.items().iter().collect()
+            } else {
+                lookup_items(&meta_list, &icons)?
+            };
+            let module = export::to_rust_module(&items, &args.rust);
+            if args.json {
+                to_json(&serde_json::json!({"icons": items.len(), "module": module}))
+            } else {
+                module.trim_end().into()
+            }
+        }
+        Command::Subset(icons) => {
+            let items = lookup_items(&meta_list, &icons)?;
+            let font = load_font(&args.font)?;
+            let (font, meta_json) = subset::subset_icons(&font, meta_list.header(), &items)
+                .map_err(|e| e.to_string())?;
+            let (font_path, meta_path) = subset::output_paths(&args.out.join(SUBSET_FILE_NAME));
+            // NOTE: Neither file is written if either exists, so that a previous subset isn't
+            // left half replaced.
+            for path in [&font_path, &meta_path] {
+                if path.exists() {
+                    return Err(format!(
+                        "{}: already exists (remove it, or pick another `--out` directory)",
+                        path.display()
+                    ));
+                }
+            }
+            let paths = [
+                write_file(&font_path, font)?,
+                write_file(&meta_path, meta_json)?,
+            ];
+            format_paths(&paths, args.json)
+        }
+        Command::Usage(root) => {
+            let usage =
+                ProjectUsage::scan(&root).map_err(|e| format!("{}: {}", root.display(), e))?;
+            format_usage(&usage, &meta_list, args.json)
+        }
+        Command::Validate => {
+            let font = load_font(&args.font)?;
+            let report = validation::validate(&meta_list, &font).map_err(|e| e.to_string())?;
+            return Ok((format_validation(&report, args.json), report.is_valid()));
+        }
+        Command::Diff(old_path, new_path) => {
+            let old = MaterialFontMetaList::load_from_path_blocking(&old_path)
+                .map_err(|e| format!("{}: {}", old_path.display(), e))?;
+            let diff = match new_path {
+                Some(new_path) => {
+                    let new = MaterialFontMetaList::load_from_path_blocking(&new_path)
+                        .map_err(|e| format!("{}: {}", new_path.display(), e))?;
+                    SnapshotDiff::new(&old, &new)
+                }
+                None => SnapshotDiff::new(&old, &meta_list),
+            };
+            format_diff(&diff, args.json)
+        }
+    };
+    Ok((output, true))
+}
+
+fn write_output(output: &str) {
+    use std::io::Write;
+
+    let mut stdout = std::io::stdout().lock();
+    let result = writeln!(stdout, "{}", output).and_then(|_| stdout.flush());
+    // NOTE: A closed pipe (e.g. `material-icons list | head`) is not an error for a command-line
+    // tool.
+    if let Err(e) = result {
+        if e.kind() != std::io::ErrorKind::BrokenPipe {
+            eprintln!("{}", e);
+            std::process::exit(EXIT_FAILURE);
+        }
+    }
+}
+
+/// Runs the command line `args` (without the program name), returning what to print (to stdout
+/// when `Ok`, to stderr otherwise) and the exit code.
+fn execute(args: Vec<String>) -> (Result<String, String>, i32) {
+    if args.is_empty() {
+        return (Ok(USAGE.into()), EXIT_SUCCESS);
+    }
+    let args = match Args::parse(args.into_iter()) {
+        Ok(Some(args)) => args,
+        Ok(None) => return (Ok(USAGE.into()), EXIT_SUCCESS),
+        Err(e) => return (Err(format!("{}\n\n{}", e, USAGE)), EXIT_USAGE),
+    };
+    match run(args) {
+        Ok((output, true)) => (Ok(output), EXIT_SUCCESS),
+        Ok((output, false)) => (Ok(output), EXIT_FAILURE),
+        Err(e) => (Err(e), EXIT_FAILURE),
+    }
+}
+
+fn main() {
+    let (output, code) = execute(std::env::args().skip(1).collect());
+    match output {
+        Ok(output) => write_output(&output),
+        Err(e) => eprintln!("{}", e),
+    }
+    if code != EXIT_SUCCESS {
+        std::process::exit(code);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<Args>, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn parse_error(args: &[&str]) -> String {
+        match parse(args) {
+            Err(e) => e,
+            Ok(_) => panic!("{:?} should be rejected", args),
+        }
+    }
+
+    fn execute(args: &[&str]) -> (Result<String, String>, i32) {
+        super::execute(args.iter().map(|arg| arg.to_string()).collect())
+    }
+
+    fn execute_json(args: &[&str]) -> serde_json::Value {
+        let (output, code) = execute(args);
+        assert_eq!(code, EXIT_SUCCESS);
+        serde_json::from_str(&output.unwrap()).unwrap()
+    }
+
+    #[test]
+    fn options_and_commands() {
+        let args = parse(&["--meta", "meta.json", "--json", "search", "arrow"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(args.meta, Some("meta.json".into()));
+        assert!(args.json);
+        assert_eq!(args.out, std::path::PathBuf::from("."));
+        assert!(matches!(args.command, Command::Search(query) if query == "arrow"));
+
+        let args = parse(&[
+            "png",
+            "home",
+            "--size",
+            "48",
+            "--padding",
+            "4",
+            "--out",
+            "res",
+        ])
+        .unwrap()
+        .unwrap();
+        assert_eq!(args.png.options.size, 48);
+        assert_eq!(args.png.options.padding, 4);
+        assert_eq!(args.out, std::path::PathBuf::from("res"));
+        assert!(matches!(args.command, Command::Png(icons) if icons.keys == ["home"]));
+
+        let args = parse(&["rust", "--enum", "--iced"]).unwrap().unwrap();
+        assert_eq!(args.rust.style, RustModuleStyle::Enum);
+        assert!(args.rust.iced_helper);
+        assert!(matches!(args.command, Command::Rust(icons) if icons.is_empty()));
+
+        let args = parse(&["list", "--category", "av"]).unwrap().unwrap();
+        assert!(matches!(args.command, Command::List(Some(category)) if category == "av"));
+    }
+
+    #[test]
+    fn help_only_before_the_command() {
+        assert!(parse(&["--help"]).unwrap().is_none());
+        assert!(parse(&["--json", "-h", "search"]).unwrap().is_none());
+        let args = parse(&["search", "--", "--help"]).unwrap().unwrap();
+        assert!(matches!(args.command, Command::Search(query) if query == "--help"));
+        let args = parse(&["search", "-h"]).unwrap().unwrap();
+        assert!(matches!(args.command, Command::Search(query) if query == "-h"));
+        assert_eq!(
+            parse_error(&["search", "--help"]),
+            "Unknown option `--help`"
+        );
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        assert_eq!(parse_error(&["--json"]), "Missing command");
+        assert_eq!(parse_error(&["find"]), "Unknown command `find`");
+        assert_eq!(
+            parse_error(&["--verbose", "list"]),
+            "Unknown option `--verbose`"
+        );
+        assert_eq!(parse_error(&["search"]), "Missing search query");
+        assert_eq!(
+            parse_error(&["info", "home", "search"]),
+            "Unexpected argument `search`"
+        );
+        assert_eq!(
+            parse_error(&["list", "--meta"]),
+            "Missing path after `--meta`"
+        );
+        assert_eq!(
+            parse_error(&["png", "home", "--size", "big"]),
+            "Invalid number of pixels `big` for `--size`"
+        );
+        assert_eq!(
+            parse_error(&["png", "home", "--color", "blue"]),
+            "Invalid color `blue` for `--color`"
+        );
+        assert_eq!(
+            parse_error(&["png", "home", "--density", "web"]),
+            "Unknown density set `web`"
+        );
+        assert_eq!(
+            parse_error(&["png"]),
+            "Missing icon names, codepoints, `--category` or `--collection`"
+        );
+        assert_eq!(
+            parse_error(&["search", "home", "--category", "av"]),
+            "`--category` is only supported by `list`, `png`, `rust` and `subset`"
+        );
+        assert_eq!(
+            parse_error(&["info", "home", "--font", "icons.ttf"]),
+            "`--font` is only supported by `svg`, `png`, `subset` and `validate`"
+        );
+        assert_eq!(
+            parse_error(&["rust", "--out", "src"]),
+            "`--out` is only supported by `png` and `subset`"
+        );
+        assert_eq!(
+            parse_error(&["svg", "home", "--size", "48"]),
+            "`--size` is only supported by `png`"
+        );
+        assert_eq!(
+            parse_error(&["list", "--enum"]),
+            "`--enum` is only supported by `rust`"
+        );
+    }
+
+    #[test]
+    fn exit_codes() {
+        let (output, code) = execute(&[]);
+        assert_eq!((output, code), (Ok(USAGE.into()), EXIT_SUCCESS));
+        let (output, code) = execute(&["--help"]);
+        assert_eq!((output, code), (Ok(USAGE.into()), EXIT_SUCCESS));
+
+        let (output, code) = execute(&["find", "home"]);
+        assert_eq!(code, EXIT_USAGE);
+        assert!(output
+            .unwrap_err()
+            .starts_with("Unknown command `find`\n\nUsage:"));
+
+        let (output, code) = execute(&["info", "no_such_icon"]);
+        assert_eq!(code, EXIT_FAILURE);
+        assert_eq!(
+            output.unwrap_err(),
+            "No icon named or with codepoint `no_such_icon`"
+        );
+        let (output, code) = execute(&["search", "cat:"]);
+        assert_eq!(code, EXIT_FAILURE);
+        assert!(output.unwrap_err().starts_with("Invalid search query: "));
+        let (_, code) = execute(&["--meta", "/nonexistent/meta.json", "list"]);
+        assert_eq!(code, EXIT_FAILURE);
+
+        let (output, code) = execute(&["info", "e88a"]);
+        assert_eq!(code, EXIT_SUCCESS);
+        assert!(output.unwrap().starts_with("Name:             home\n"));
+        // NOTE: The bundled font has deprecated glyphs, which are warnings, not errors.
+        let (output, code) = execute(&["validate"]);
+        assert_eq!(code, EXIT_SUCCESS);
+        assert!(output.unwrap().ends_with(" warning(s)"));
+    }
+
+    #[test]
+    fn json_output() {
+        let info = execute_json(&["--json", "info", "home"]);
+        assert_eq!(info["name"], "home");
+        assert_eq!(info["codepoint"], 0xe88a);
+        assert_eq!(info["codepoint_hex"], "e88a");
+        assert!(info["aliases"].is_array());
+
+        let items = execute_json(&["--json", "search", "arrow_back"]);
+        assert_eq!(items[0]["name"], "arrow_back");
+
+        let categories = execute_json(&["--json", "categories"]);
+        let categories = categories.as_array().unwrap();
+        assert!(!categories.is_empty());
+        assert!(categories
+            .iter()
+            .all(|category| category["name"].is_string() && category["count"].as_u64() > Some(0)));
+
+        let items = execute_json(&["--json", "list", "--category", "av"]);
+        let count = categories
+            .iter()
+            .find(|category| category["name"] == "av")
+            .unwrap()["count"]
+            .as_u64()
+            .unwrap();
+        assert_eq!(items.as_array().unwrap().len() as u64, count);
+
+        let svg = execute_json(&["--json", "svg", "home"]);
+        assert_eq!(svg["name"], "home");
+        assert!(svg["svg"].as_str().unwrap().starts_with("<svg"));
+
+        let report = execute_json(&["--json", "validate"]);
+        assert_eq!(report["valid"], true);
+        assert_eq!(report["errors"], 0);
+
+        let module = execute_json(&["--json", "rust", "home", "settings"]);
+        assert_eq!(module["icons"], 2);
+        assert!(module["module"]
+            .as_str()
+            .unwrap()
+            .contains("pub const CODEPOINT_HOME: u32 = 0xe88a;"));
+    }
+
+    #[test]
+    fn subsets_dont_replace_files() {
+        let out = std::env::temp_dir().join(format!(
+            "iced-material-icon-browser-subset-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&out);
+        let out_arg = out.display().to_string();
+        let font_path = out.join("MaterialIcons-Subset.ttf");
+        let meta_path = out.join("MaterialIcons-Subset.json");
+
+        let (output, code) = execute(&["subset", "home", "--out", &out_arg]);
+        assert_eq!(code, EXIT_SUCCESS);
+        assert_eq!(
+            output.unwrap(),
+            format!("{}\n{}", font_path.display(), meta_path.display())
+        );
+
+        std::fs::remove_file(&font_path).unwrap();
+        std::fs::write(&meta_path, "{}").unwrap();
+        let (output, code) = execute(&["subset", "home", "--out", &out_arg]);
+        let meta = std::fs::read_to_string(&meta_path);
+        let font_written = font_path.exists();
+        std::fs::remove_dir_all(&out).unwrap();
+        assert_eq!(code, EXIT_FAILURE);
+        assert!(output.unwrap_err().contains("already exists"));
+        assert_eq!(meta.unwrap(), "{}");
+        assert!(!font_written);
+    }
+}
@@ -0,0 +1,298 @@
+//! Named, user-defined sets of icons (e.g. "Dashboard app"), persisted in the configuration
+//! directory and exchangeable as JSON or TOML files.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::LoadError;
+
+/// The version of the collections file written by this crate.
+const FILE_VERSION: u32 = 1;
+
+/// The upgrades of collections files written by older versions (see [`crate::config::Migration`]).
+///
+/// NOTE: When the schema changes, `FILE_VERSION` gets incremented and a function is appended
+/// here, so that files written by older versions keep loading.
+const MIGRATIONS: &[crate::config::Migration] = &[];
+
+// NOTE: Fails the build when `FILE_VERSION` and `MIGRATIONS` get out of sync.
+const _: () = assert!(FILE_VERSION as usize == MIGRATIONS.len() + 1);
+
+/// The format of an exported collection, chosen from the file extension.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CollectionFormat {
+    /// `.json` files.
+    Json,
+    /// `.toml` files.
+    Toml,
+}
+
+impl CollectionFormat {
+    /// The format of the file at `path`, if its extension is a supported one.
+    pub fn from_path(path: impl AsRef<std::path::Path>) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "json" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+}
+
+/// A named list of icon names, in the order they were added.
+///
+/// Exported as `{"name": "Dashboard app", "icons": ["home", "settings"]}` or, in TOML:
+///
+/// ```toml
+/// name = "Dashboard app"
+/// icons = ["home", "settings"]
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Collection {
+    name: String,
+    #[serde(default)]
+    icons: Vec<String>,
+}
+
+impl Collection {
+    /// An empty collection.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            icons: vec![],
+        }
+    }
+
+    /// The name of the collection.
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    /// The names of the icons of the collection.
+    pub fn icons(&self) -> &Vec<String> {
+        &self.icons
+    }
+
+    /// Whether the icon named `icon` is in the collection.
+    pub fn contains(&self, icon: &str) -> bool {
+        self.icons.iter().any(|value| value == icon)
+    }
+
+    /// Adds the icon named `icon`, returning `false` if it was already in the collection.
+    pub fn add(&mut self, icon: impl Into<String>) -> bool {
+        let icon = icon.into();
+        if self.contains(&icon) {
+            return false;
+        }
+        self.icons.push(icon);
+        true
+    }
+
+    /// Removes the icon named `icon`, returning `false` if it wasn't in the collection.
+    pub fn remove(&mut self, icon: &str) -> bool {
+        let length = self.icons.len();
+        self.icons.retain(|value| value != icon);
+        self.icons.len() != length
+    }
+
+    /// Parses an exported collection.
+    pub fn parse(bytes: &[u8], format: CollectionFormat) -> Result<Self, LoadError> {
+        let mut collection = match format {
+            CollectionFormat::Json => {
+                serde_json::from_slice::<Self>(bytes).map_err(LoadError::from_config_json)?
+            }
+            CollectionFormat::Toml => {
+                let text = std::str::from_utf8(bytes)
+                    .map_err(|e| LoadError::Toml(format!("invalid UTF-8: {}", e)))?;
+                toml::from_str::<Self>(text).map_err(|e| LoadError::Toml(e.to_string()))?
+            }
+        };
+        // NOTE: Names are trimmed, as by `Collections::create`, so that importing `"Favs "` merges
+        // into "Favs".
+        collection.name = collection.name.trim().to_string();
+        if collection.name.is_empty() {
+            return Err(LoadError::Invalid("the collection has no name".into()));
+        }
+        Ok(collection)
+    }
+
+    /// The collection as an exported file.
+    pub fn to_file_contents(&self, format: CollectionFormat) -> String {
+        match format {
+            CollectionFormat::Json => {
+                serde_json::to_string_pretty(self).expect("collections should be serializable")
+            }
+            CollectionFormat::Toml => {
+                toml::to_string(self).expect("collections should be serializable")
+            }
+        }
+    }
+
+    /// Imports the collection exported to `path`, whose extension (`.json` or `.toml`) gives the
+    /// format.
+    pub async fn import(path: impl AsRef<std::path::Path>) -> Result<Self, LoadError> {
+        let path = path.as_ref();
+        let format = CollectionFormat::from_path(path).ok_or_else(|| {
+            LoadError::Invalid(String::from("expected a `.json` or `.toml` file"))
+        })?;
+        let bytes = tokio::fs::read(path).await?;
+        Self::parse(&bytes, format)
+    }
+
+    /// Exports the collection to `path`, whose extension (`.json` or `.toml`, the former being the
+    /// default) gives the format.
+    pub async fn export(self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        let path = path.as_ref();
+        let format = CollectionFormat::from_path(path).unwrap_or(CollectionFormat::Json);
+        crate::config::write(path, self.to_file_contents(format)).await
+    }
+}
+
+/// All collections of the user, in creation order.
+///
+/// The file looks like `{"version": 1, "collections": [{"name": "...", "icons": [...]}]}`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Collections {
+    collections: Vec<Collection>,
+}
+
+impl Collections {
+    /// The name of the collections file in the
+    /// [configuration directory](crate::config::config_dir).
+    pub const FILE_NAME: &'static str = "collections.json";
+
+    /// The default location of the collections file.
+    pub fn default_path() -> Option<std::path::PathBuf> {
+        crate::config::config_file(Self::FILE_NAME)
+    }
+
+    /// Loads the collections from `path`, a missing file meaning no collections.
+    pub async fn load(path: impl AsRef<std::path::Path>) -> Result<Self, LoadError> {
+        match crate::config::read_if_exists(path).await? {
+            Some(bytes) => Self::from_json(&bytes),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Parses the contents of a collections file.
+    pub fn from_json(bytes: &[u8]) -> Result<Self, LoadError> {
+        crate::config::from_versioned_json(bytes, "collections", MIGRATIONS)
+    }
+
+    /// The contents of a collections file.
+    pub fn to_json(&self) -> String {
+        crate::config::to_versioned_json(self, FILE_VERSION)
+    }
+
+    /// Writes the collections to `path`.
+    pub async fn save(self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        crate::config::write(path, self.to_json()).await
+    }
+
+    /// All collections.
+    pub fn collections(&self) -> &Vec<Collection> {
+        &self.collections
+    }
+
+    /// The collection named `name`.
+    pub fn get(&self, name: &str) -> Option<&Collection> {
+        self.collections
+            .iter()
+            .find(|collection| collection.name == name)
+    }
+
+    /// The collection named `name`, for modification.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Collection> {
+        self.collections
+            .iter_mut()
+            .find(|collection| collection.name == name)
+    }
+
+    /// Creates an empty collection, returning `false` if the name is blank or already taken.
+    pub fn create(&mut self, name: &str) -> bool {
+        let name = name.trim();
+        if name.is_empty() || self.get(name).is_some() {
+            return false;
+        }
+        self.collections.push(Collection::new(name));
+        true
+    }
+
+    /// Adds an imported collection, merging its icons into the existing collection of the same
+    /// name, if any.
+    pub fn import(&mut self, collection: Collection) {
+        match self.get_mut(&collection.name) {
+            Some(existing) => {
+                for icon in collection.icons {
+                    existing.add(icon);
+                }
+            }
+            None => self.collections.push(collection),
+        }
+    }
+
+    /// Deletes the collection named `name`.
+    pub fn remove(&mut self, name: &str) -> Option<Collection> {
+        let index = self
+            .collections
+            .iter()
+            .position(|collection| collection.name == name)?;
+        Some(self.collections.remove(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_trims_the_name() {
+        let collection = Collection::parse(
+            br#"{"name": " Favs ", "icons": ["home"]}"#,
+            CollectionFormat::Json,
+        )
+        .unwrap();
+        assert_eq!(collection.name(), "Favs");
+
+        let mut collections = Collections::default();
+        assert!(collections.create("Favs"));
+        collections.import(collection);
+        assert_eq!(collections.collections().len(), 1);
+        assert_eq!(collections.get("Favs").unwrap().icons(), &["home"]);
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut collections = Collections::default();
+        assert!(collections.create("Favs"));
+        let json = collections.to_json();
+        assert!(json.contains(r#""version": 1"#));
+        assert_eq!(
+            Collections::from_json(json.as_bytes()).unwrap(),
+            collections
+        );
+    }
+
+    #[test]
+    fn versions() {
+        let collections =
+            Collections::from_json(br#"{"collections": [{"name": "Favs", "icons": []}]}"#).unwrap();
+        assert!(collections.get("Favs").is_some());
+        for json in [r#"{"version": 0}"#, r#"{"version": 2}"#] {
+            assert!(
+                matches!(
+                    Collections::from_json(json.as_bytes()),
+                    Err(LoadError::Invalid(_))
+                ),
+                "{}",
+                json
+            );
+        }
+    }
+
+    #[test]
+    fn parse_rejects_blank_names() {
+        let result = Collection::parse(b"name = \"  \"\nicons = []", CollectionFormat::Toml);
+        assert!(matches!(result, Err(LoadError::Invalid(_))));
+    }
+}
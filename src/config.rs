@@ -0,0 +1,162 @@
+//! Files kept in the user's configuration directory (e.g. `~/.config/iced-material-icon-browser`
+//! on Linux, following the XDG Base Directory specification), such as the favorites.
+
+use crate::models::LoadError;
+
+/// Upgrades the JSON object of a configuration file from one version to the next. Each file has
+/// a list of them, the one at index `i` taking a version `i + 1` file to version `i + 2`.
+pub(crate) type Migration = fn(&mut serde_json::Map<String, serde_json::Value>);
+
+/// The name of the application's directory inside the user's configuration directory.
+pub const APP_DIR_NAME: &str = "iced-material-icon-browser";
+
+/// The application's configuration directory, if the platform has one.
+pub fn config_dir() -> Option<std::path::PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(APP_DIR_NAME))
+}
+
+/// The path of the configuration file named `file_name`.
+pub fn config_file(file_name: &str) -> Option<std::path::PathBuf> {
+    config_dir().map(|dir| dir.join(file_name))
+}
+
+/// Reads the file at `path`, or returns `None` if it doesn't exist (yet).
+pub async fn read_if_exists(path: impl AsRef<std::path::Path>) -> std::io::Result<Option<Vec<u8>>> {
+    match tokio::fs::read(path).await {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Parses the contents of a configuration file whose current version is `migrations.len() + 1`,
+/// migrating it from older versions first. `kind` names the file in errors (e.g. `"state"`).
+///
+/// The file's `version` isn't deserialized, since it is only meaningful before the migrations.
+pub(crate) fn from_versioned_json<T: serde::de::DeserializeOwned>(
+    bytes: &[u8],
+    kind: &str,
+    migrations: &[Migration],
+) -> Result<T, LoadError> {
+    let file_version = migrations.len() + 1;
+    let mut value =
+        serde_json::from_slice::<serde_json::Value>(bytes).map_err(LoadError::from_config_json)?;
+    let object = value
+        .as_object_mut()
+        .ok_or_else(|| LoadError::Invalid(String::from("expected a JSON object")))?;
+    // NOTE: Files are always written with a version, so one without is of the oldest schema.
+    let version = match object.remove("version") {
+        None => 1,
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| usize::try_from(version).ok())
+            .filter(|version| *version > 0)
+            .ok_or_else(|| {
+                LoadError::Invalid(format!("invalid {} file version {}", kind, version))
+            })?,
+    };
+    if version > file_version {
+        return Err(LoadError::Invalid(format!(
+            "unsupported {} file version {} (expected at most {})",
+            kind, version, file_version
+        )));
+    }
+    for migration in &migrations[version - 1..] {
+        migration(object);
+    }
+    serde_json::from_value(value).map_err(LoadError::from_config_json)
+}
+
+/// The contents of a configuration file: `value` (a struct), along with the file's `version`.
+pub(crate) fn to_versioned_json(value: &impl serde::Serialize, version: u32) -> String {
+    let mut value = serde_json::to_value(value).expect("configuration files are serializable");
+    if let Some(object) = value.as_object_mut() {
+        object.insert("version".into(), version.into());
+    }
+    serde_json::to_string_pretty(&value).expect("configuration files are serializable")
+}
+
+/// Writes `contents` to `path`, creating its parent directories if needed. The contents are
+/// written to a temporary file first, so that a crash can't leave a truncated file behind.
+pub async fn write(path: impl AsRef<std::path::Path>, contents: String) -> std::io::Result<()> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    // NOTE: Each write gets its own temporary file, so that two saves in quick succession don't
+    // race on it, and exporting `foo.json` doesn't clobber an unrelated `foo.tmp`.
+    static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    let counter = COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let temporary_path = path.with_file_name(format!(
+        ".{}.{}.{}.tmp",
+        file_name,
+        std::process::id(),
+        counter
+    ));
+    tokio::fs::write(&temporary_path, contents).await?;
+    tokio::fs::rename(&temporary_path, path).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+    struct File {
+        name: String,
+    }
+
+    /// Version 2 renamed `title` to `name`.
+    const MIGRATIONS: &[Migration] = &[|object| {
+        if let Some(title) = object.remove("title") {
+            object.insert("name".into(), title);
+        }
+    }];
+
+    fn parse(json: &str) -> Result<File, LoadError> {
+        from_versioned_json(json.as_bytes(), "test", MIGRATIONS)
+    }
+
+    fn error(json: &str) -> String {
+        match parse(json) {
+            Err(LoadError::Invalid(message)) => message,
+            result => panic!("{} gave {:?}", json, result),
+        }
+    }
+
+    #[test]
+    fn older_versions_are_migrated() {
+        let file = File { name: "a".into() };
+        assert_eq!(parse(r#"{"version": 1, "title": "a"}"#).unwrap(), file);
+        assert_eq!(parse(r#"{"title": "a"}"#).unwrap(), file);
+        assert_eq!(parse(r#"{"version": 2, "name": "a"}"#).unwrap(), file);
+    }
+
+    #[test]
+    fn versions_are_checked() {
+        assert_eq!(
+            error(r#"{"version": 3, "name": "a"}"#),
+            "unsupported test file version 3 (expected at most 2)"
+        );
+        for version in ["0", "-1", "\"1\"", "1.5", "null"] {
+            assert_eq!(
+                error(&format!(r#"{{"version": {}, "name": "a"}}"#, version)),
+                format!("invalid test file version {}", version)
+            );
+        }
+        assert_eq!(error(r#"["a"]"#), "expected a JSON object");
+    }
+
+    #[test]
+    fn round_trip() {
+        let file = File { name: "a".into() };
+        let json = to_versioned_json(&file, 2);
+        let value = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+        assert_eq!(value, serde_json::json!({"version": 2, "name": "a"}));
+        assert_eq!(parse(&json).unwrap(), file);
+    }
+}
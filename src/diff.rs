@@ -0,0 +1,513 @@
+//! Comparison of two metadata snapshots (e.g. `2023-09-12-material-icons-meta.json` and a newer
+//! one), to audit font upgrades before rolling them out.
+
+use crate::models::{MaterialFontMeta, MaterialFontMetaList};
+
+/// The bucket an icon falls into when comparing two snapshots.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffKind {
+    /// Only in the new snapshot.
+    Added,
+    /// Only in the old snapshot.
+    Removed,
+    /// Same codepoint, new name.
+    Renamed,
+    /// Same name, new codepoint.
+    Recoded,
+    /// Same name and codepoint, but other tags, categories or version.
+    Changed,
+}
+
+impl DiffKind {
+    /// All buckets, in display order.
+    pub const ALL: [Self; 5] = [
+        Self::Added,
+        Self::Removed,
+        Self::Renamed,
+        Self::Recoded,
+        Self::Changed,
+    ];
+}
+
+impl std::fmt::Display for DiffKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Added => write!(f, "Added"),
+            Self::Removed => write!(f, "Removed"),
+            Self::Renamed => write!(f, "Renamed"),
+            Self::Recoded => write!(f, "Re-coded"),
+            Self::Changed => write!(f, "Changed"),
+        }
+    }
+}
+
+/// A change to the metadata of an icon present in both snapshots.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "field", rename_all = "snake_case")]
+pub enum FieldChange {
+    /// The version was bumped (i.e. the glyph was redrawn).
+    Version {
+        /// The old version.
+        old: u32,
+        /// The new version.
+        new: u32,
+    },
+    /// Categories were added or removed.
+    Categories {
+        /// The new categories.
+        added: Vec<String>,
+        /// The categories no longer listed.
+        removed: Vec<String>,
+    },
+    /// Tags were added or removed.
+    Tags {
+        /// The new tags.
+        added: Vec<String>,
+        /// The tags no longer listed.
+        removed: Vec<String>,
+    },
+}
+
+impl FieldChange {
+    /// The changes from `old` to `new` (popularity aside, which changes all the time).
+    fn between(old: &MaterialFontMeta, new: &MaterialFontMeta) -> Vec<Self> {
+        let mut changes = vec![];
+        if old.version() != new.version() {
+            changes.push(Self::Version {
+                old: old.version(),
+                new: new.version(),
+            });
+        }
+        let (added, removed) = set_difference(old.categories(), new.categories());
+        if !added.is_empty() || !removed.is_empty() {
+            changes.push(Self::Categories { added, removed });
+        }
+        let (added, removed) = set_difference(old.tags(), new.tags());
+        if !added.is_empty() || !removed.is_empty() {
+            changes.push(Self::Tags { added, removed });
+        }
+        changes
+    }
+}
+
+impl std::fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let list = |label: &str, added: &[String], removed: &[String]| {
+            let values = added
+                .iter()
+                .map(|value| format!("+{}", value))
+                .chain(removed.iter().map(|value| format!("-{}", value)))
+                .collect::<Vec<String>>();
+            format!("{} {}", label, values.join(" "))
+        };
+        match self {
+            Self::Version { old, new } => write!(f, "version {} → {}", old, new),
+            Self::Categories { added, removed } => {
+                write!(f, "{}", list("categories", added, removed))
+            }
+            Self::Tags { added, removed } => write!(f, "{}", list("tags", added, removed)),
+        }
+    }
+}
+
+/// The values of `new` missing from `old`, and those of `old` missing from `new`, sorted.
+fn set_difference(old: &[String], new: &[String]) -> (Vec<String>, Vec<String>) {
+    let old = old.iter().collect::<std::collections::BTreeSet<&String>>();
+    let new = new.iter().collect::<std::collections::BTreeSet<&String>>();
+    (
+        new.difference(&old).map(|value| (*value).clone()).collect(),
+        old.difference(&new).map(|value| (*value).clone()).collect(),
+    )
+}
+
+/// An icon that differs between the two snapshots.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct IconDiff {
+    /// The bucket of the icon.
+    pub kind: DiffKind,
+    /// The name in the old snapshot (`None` if added).
+    pub old_name: Option<String>,
+    /// The codepoint in the old snapshot (`None` if added).
+    pub old_codepoint: Option<u32>,
+    /// The name in the new snapshot (`None` if removed).
+    pub new_name: Option<String>,
+    /// The codepoint in the new snapshot (`None` if removed).
+    pub new_codepoint: Option<u32>,
+    /// The changes to the tags, categories and version (renamed and re-coded icons may have
+    /// some too).
+    pub changes: Vec<FieldChange>,
+}
+
+impl IconDiff {
+    fn new(kind: DiffKind, old: Option<&MaterialFontMeta>, new: Option<&MaterialFontMeta>) -> Self {
+        let changes = match (old, new) {
+            (Some(old), Some(new)) => FieldChange::between(old, new),
+            _ => vec![],
+        };
+        Self {
+            kind,
+            old_name: old.map(|item| item.name().clone()),
+            old_codepoint: old.map(|item| item.codepoint()),
+            new_name: new.map(|item| item.name().clone()),
+            new_codepoint: new.map(|item| item.codepoint()),
+            changes,
+        }
+    }
+
+    /// The name of the icon: the new one, unless it was removed.
+    pub fn name(&self) -> &String {
+        self.new_name
+            .as_ref()
+            .or(self.old_name.as_ref())
+            .expect("a diff has an old or a new icon")
+    }
+}
+
+impl std::fmt::Display for IconDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let hex = |codepoint: Option<u32>| format!("{:04x}", codepoint.unwrap_or_default());
+        match self.kind {
+            DiffKind::Added => write!(f, "{} ({})", self.name(), hex(self.new_codepoint))?,
+            DiffKind::Removed => write!(f, "{} ({})", self.name(), hex(self.old_codepoint))?,
+            DiffKind::Renamed => write!(
+                f,
+                "{} → {} ({})",
+                self.old_name.as_deref().unwrap_or_default(),
+                self.name(),
+                hex(self.new_codepoint)
+            )?,
+            DiffKind::Recoded => write!(
+                f,
+                "{} ({} → {})",
+                self.name(),
+                hex(self.old_codepoint),
+                hex(self.new_codepoint)
+            )?,
+            DiffKind::Changed => write!(f, "{} ({})", self.name(), hex(self.new_codepoint))?,
+        }
+        for change in &self.changes {
+            write!(f, "; {}", change)?;
+        }
+        Ok(())
+    }
+}
+
+/// The differences between two snapshots, by bucket (see [`DiffKind`]) and then by name.
+///
+/// Icons are matched by name first, and then, among the remaining ones, by codepoint (which makes
+/// them renamed icons). Icons sharing a name within a snapshot are paired in file order.
+///
+/// ```
+/// use iced_material_icon_browser::diff::{DiffKind, SnapshotDiff};
+/// use iced_material_icon_browser::MaterialFontMetaList;
+///
+/// let list = MaterialFontMetaList::bundled().unwrap();
+/// assert!(SnapshotDiff::new(&list, &list).is_empty());
+///
+/// let older = MaterialFontMetaList::from_items(list.header().clone(), list.items()[1..].to_vec());
+/// let diff = SnapshotDiff::new(&older, &list);
+/// assert_eq!(diff.count(DiffKind::Added), 1);
+/// assert_eq!(diff.bucket(DiffKind::Added)[0].name(), list.items()[0].name());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SnapshotDiff {
+    entries: Vec<IconDiff>,
+}
+
+impl SnapshotDiff {
+    /// Compares the `old` snapshot to the `new` one.
+    pub fn new(old: &MaterialFontMetaList, new: &MaterialFontMetaList) -> Self {
+        // NOTE: Each new icon is matched at most once, even if several old ones share its name.
+        let mut new_positions_by_name =
+            std::collections::HashMap::<&String, std::collections::VecDeque<usize>>::new();
+        for (position, item) in new.items().iter().enumerate() {
+            new_positions_by_name
+                .entry(item.name())
+                .or_default()
+                .push_back(position);
+        }
+        let mut new_matched = vec![false; new.items().len()];
+
+        let mut entries = vec![];
+        let mut old_unmatched = vec![];
+        for old_item in old.items() {
+            let position = new_positions_by_name
+                .get_mut(old_item.name())
+                .and_then(|positions| positions.pop_front());
+            match position {
+                Some(position) => {
+                    new_matched[position] = true;
+                    let new_item = &new.items()[position];
+                    let kind = if old_item.codepoint() != new_item.codepoint() {
+                        DiffKind::Recoded
+                    } else {
+                        DiffKind::Changed
+                    };
+                    let entry = IconDiff::new(kind, Some(old_item), Some(new_item));
+                    if kind == DiffKind::Recoded || !entry.changes.is_empty() {
+                        entries.push(entry);
+                    }
+                }
+                None => old_unmatched.push(old_item),
+            }
+        }
+
+        let mut new_unmatched = new
+            .items()
+            .iter()
+            .zip(new_matched)
+            .filter(|(_, matched)| !matched)
+            .map(|(item, _)| item)
+            .collect::<Vec<&MaterialFontMeta>>();
+        for old_item in old_unmatched {
+            // NOTE: With aliases, the icons sharing a codepoint are paired in file order.
+            let renamed = new_unmatched
+                .iter()
+                .position(|new_item| new_item.codepoint() == old_item.codepoint());
+            match renamed {
+                Some(index) => {
+                    let new_item = new_unmatched.remove(index);
+                    entries.push(IconDiff::new(
+                        DiffKind::Renamed,
+                        Some(old_item),
+                        Some(new_item),
+                    ));
+                }
+                None => entries.push(IconDiff::new(DiffKind::Removed, Some(old_item), None)),
+            }
+        }
+        entries.extend(
+            new_unmatched
+                .into_iter()
+                .map(|new_item| IconDiff::new(DiffKind::Added, None, Some(new_item))),
+        );
+
+        entries.sort_by(|a, b| (a.kind, a.name()).cmp(&(b.kind, b.name())));
+        Self { entries }
+    }
+
+    /// All differences, by bucket and then by name.
+    pub fn entries(&self) -> &Vec<IconDiff> {
+        &self.entries
+    }
+
+    /// The differences of the `kind` bucket, by name.
+    pub fn bucket(&self, kind: DiffKind) -> Vec<&IconDiff> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.kind == kind)
+            .collect()
+    }
+
+    /// The number of differences of the `kind` bucket.
+    pub fn count(&self, kind: DiffKind) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.kind == kind)
+            .count()
+    }
+
+    /// Whether the snapshots list the same icons (popularity aside).
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An icon of a snapshot, to be tweaked before being passed to [`snapshot`].
+    fn icon(name: &str, codepoint: u32) -> serde_json::Value {
+        serde_json::json!({
+            "name": name,
+            "version": 1,
+            "popularity": 0,
+            "codepoint": codepoint,
+            "categories": ["action"],
+            "tags": ["a", "b"],
+        })
+    }
+
+    fn snapshot(icons: Vec<serde_json::Value>) -> MaterialFontMetaList {
+        let json = serde_json::json!({ "icons": icons }).to_string();
+        MaterialFontMetaList::load_from_bytes(json.into_bytes()).unwrap()
+    }
+
+    /// The kind and names of the entries, as `(kind, old name, new name)`.
+    fn summary(diff: &SnapshotDiff) -> Vec<(DiffKind, Option<&str>, Option<&str>)> {
+        diff.entries()
+            .iter()
+            .map(|entry| {
+                (
+                    entry.kind,
+                    entry.old_name.as_deref(),
+                    entry.new_name.as_deref(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn identical_snapshots() {
+        let list = snapshot(vec![icon("home", 0xe88a), icon("search", 0xe8b6)]);
+        assert!(SnapshotDiff::new(&list, &list).is_empty());
+    }
+
+    #[test]
+    fn popularity_is_ignored() {
+        let mut popular = icon("home", 0xe88a);
+        popular["popularity"] = 1000.into();
+        let diff = SnapshotDiff::new(
+            &snapshot(vec![icon("home", 0xe88a)]),
+            &snapshot(vec![popular]),
+        );
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn added_and_removed() {
+        let diff = SnapshotDiff::new(
+            &snapshot(vec![icon("home", 0xe88a), icon("gone", 0xf000)]),
+            &snapshot(vec![icon("home", 0xe88a), icon("search", 0xe8b6)]),
+        );
+        assert_eq!(
+            summary(&diff),
+            vec![
+                (DiffKind::Added, None, Some("search")),
+                (DiffKind::Removed, Some("gone"), None),
+            ]
+        );
+        let removed = diff.bucket(DiffKind::Removed)[0];
+        assert_eq!(removed.old_codepoint, Some(0xf000));
+        assert_eq!(removed.new_codepoint, None);
+        assert_eq!(removed.to_string(), "gone (f000)");
+    }
+
+    #[test]
+    fn renamed() {
+        let mut renamed = icon("house", 0xe88a);
+        renamed["tags"] = serde_json::json!(["a", "b", "c"]);
+        let diff = SnapshotDiff::new(
+            &snapshot(vec![icon("home", 0xe88a)]),
+            &snapshot(vec![renamed]),
+        );
+        assert_eq!(
+            summary(&diff),
+            vec![(DiffKind::Renamed, Some("home"), Some("house"))]
+        );
+        assert_eq!(
+            diff.entries()[0].changes,
+            vec![FieldChange::Tags {
+                added: vec!["c".into()],
+                removed: vec![],
+            }]
+        );
+        assert_eq!(
+            diff.entries()[0].to_string(),
+            "home → house (e88a); tags +c"
+        );
+    }
+
+    #[test]
+    fn recoded() {
+        let diff = SnapshotDiff::new(
+            &snapshot(vec![icon("search", 0xe8b6)]),
+            &snapshot(vec![icon("search", 0xe005)]),
+        );
+        assert_eq!(
+            summary(&diff),
+            vec![(DiffKind::Recoded, Some("search"), Some("search"))]
+        );
+        let entry = &diff.entries()[0];
+        assert_eq!(
+            (entry.old_codepoint, entry.new_codepoint),
+            (Some(0xe8b6), Some(0xe005))
+        );
+        assert!(entry.changes.is_empty());
+        assert_eq!(entry.to_string(), "search (e8b6 → e005)");
+    }
+
+    #[test]
+    fn changed() {
+        let mut changed = icon("settings", 0xe8b8);
+        changed["version"] = 2.into();
+        changed["categories"] = serde_json::json!(["action", "device"]);
+        changed["tags"] = serde_json::json!(["b", "z"]);
+        let diff = SnapshotDiff::new(
+            &snapshot(vec![icon("settings", 0xe8b8)]),
+            &snapshot(vec![changed]),
+        );
+        assert_eq!(diff.count(DiffKind::Changed), 1);
+        assert_eq!(
+            diff.entries()[0].changes,
+            vec![
+                FieldChange::Version { old: 1, new: 2 },
+                FieldChange::Categories {
+                    added: vec!["device".into()],
+                    removed: vec![],
+                },
+                FieldChange::Tags {
+                    added: vec!["z".into()],
+                    removed: vec!["a".into()],
+                },
+            ]
+        );
+        assert_eq!(
+            diff.entries()[0].to_string(),
+            "settings (e8b8); version 1 → 2; categories +device; tags +z -a"
+        );
+    }
+
+    #[test]
+    fn aliases_are_paired_in_file_order() {
+        let diff = SnapshotDiff::new(
+            &snapshot(vec![icon("home", 0xe88a), icon("home_alias", 0xe88a)]),
+            &snapshot(vec![icon("house", 0xe88a), icon("house_alias", 0xe88a)]),
+        );
+        assert_eq!(
+            summary(&diff),
+            vec![
+                (DiffKind::Renamed, Some("home"), Some("house")),
+                (DiffKind::Renamed, Some("home_alias"), Some("house_alias")),
+            ]
+        );
+
+        // A new alias of a kept icon is added.
+        let diff = SnapshotDiff::new(
+            &snapshot(vec![icon("home", 0xe88a)]),
+            &snapshot(vec![icon("home", 0xe88a), icon("house", 0xe88a)]),
+        );
+        assert_eq!(summary(&diff), vec![(DiffKind::Added, None, Some("house"))]);
+    }
+
+    #[test]
+    fn duplicate_names() {
+        let duplicates = snapshot(vec![icon("dup", 0xe001), icon("dup", 0xe002)]);
+        assert!(SnapshotDiff::new(&duplicates, &duplicates).is_empty());
+
+        // Each new icon is matched once: the second old `dup` has no counterpart.
+        let diff = SnapshotDiff::new(&duplicates, &snapshot(vec![icon("dup", 0xe001)]));
+        assert_eq!(summary(&diff), vec![(DiffKind::Removed, Some("dup"), None)]);
+        assert_eq!(diff.entries()[0].old_codepoint, Some(0xe002));
+
+        // The second new `dup` isn't hidden by the old one.
+        let diff = SnapshotDiff::new(&snapshot(vec![icon("dup", 0xe001)]), &duplicates);
+        assert_eq!(summary(&diff), vec![(DiffKind::Added, None, Some("dup"))]);
+        assert_eq!(diff.entries()[0].new_codepoint, Some(0xe002));
+
+        // Names are paired in file order, then the leftovers by codepoint.
+        let diff = SnapshotDiff::new(
+            &duplicates,
+            &snapshot(vec![icon("dup", 0xe002), icon("other", 0xe002)]),
+        );
+        assert_eq!(
+            summary(&diff),
+            vec![
+                (DiffKind::Renamed, Some("dup"), Some("other")),
+                (DiffKind::Recoded, Some("dup"), Some("dup")),
+            ]
+        );
+    }
+}
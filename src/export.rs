@@ -0,0 +1,458 @@
+//! Serialization of (a selection of) icons to formats other tools can consume.
+
+use crate::glyph::{format_number, GlyphOutline, Segment};
+use crate::models::{MaterialFontMeta, MaterialFontMetaListHeader};
+
+#[derive(serde::Serialize)]
+struct MetaFile<'a> {
+    #[serde(flatten)]
+    header: &'a MaterialFontMetaListHeader,
+    icons: &'a [&'a MaterialFontMeta],
+}
+
+/// Serializes `items` to the metadata JSON schema, so that the output can be read back with
+/// [`crate::MaterialFontMetaList::load_from_bytes`].
+pub fn to_meta_json(header: &MaterialFontMetaListHeader, items: &[&MaterialFontMeta]) -> String {
+    // NOTE: Pretty printing with two spaces, like the files published by Google.
+    serde_json::to_string_pretty(&MetaFile {
+        header,
+        icons: items,
+    })
+    .expect("the metadata should be serializable")
+}
+
+/// Writes one `name,codepoint_hex,codepoint` line per icon, preceded by a header line.
+pub fn to_csv(items: &[&MaterialFontMeta]) -> String {
+    let mut csv = String::from("name,codepoint_hex,codepoint\n");
+    for item in items {
+        csv.push_str(&format!(
+            "{},{},{}\n",
+            item.name(),
+            item.to_hex_codepoint(),
+            item.codepoint()
+        ));
+    }
+    csv
+}
+
+/// A standalone SVG document drawing `outline` in a `0 0 24 24` view box (like the SVGs published
+/// by Google), filled with `currentColor` so that it takes the color of the surrounding text when
+/// inlined in HTML.
+pub fn to_svg(outline: &GlyphOutline) -> String {
+    format!(
+        concat!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {size} {size}\" ",
+            "width=\"{size}\" height=\"{size}\">",
+            "<path fill=\"currentColor\" d=\"{data}\"/></svg>\n"
+        ),
+        size = format_number(GlyphOutline::GRID_SIZE),
+        data = outline.to_path_data()
+    )
+}
+
+/// An Android `<vector>` drawable (e.g. `res/drawable/ic_home.xml`) drawing `outline`, in the
+/// form Android Studio's Vector Asset tool generates for Material icons: 24dp, tinted with the
+/// theme's `colorControlNormal`.
+pub fn to_vector_drawable(outline: &GlyphOutline) -> String {
+    let size = format_number(GlyphOutline::GRID_SIZE);
+    format!(
+        concat!(
+            "<vector xmlns:android=\"http://schemas.android.com/apk/res/android\"\n",
+            "    android:width=\"{size}dp\"\n",
+            "    android:height=\"{size}dp\"\n",
+            "    android:viewportWidth=\"{size}\"\n",
+            "    android:viewportHeight=\"{size}\"\n",
+            "    android:tint=\"?attr/colorControlNormal\">\n",
+            "  <path\n",
+            "      android:fillColor=\"@android:color/white\"\n",
+            "      android:pathData=\"{data}\"/>\n",
+            "</vector>\n"
+        ),
+        size = size,
+        data = outline.to_path_data()
+    )
+}
+
+/// A Jetpack Compose `ImageVector` property drawing `outline`, named after `item` (e.g. `Home`
+/// for `home`, `_3dRotation` for `3d_rotation`) and built lazily, like the icons of
+/// `material-icons-extended`.
+pub fn to_compose_image_vector(item: &MaterialFontMeta, outline: &GlyphOutline) -> String {
+    let name = leading_digit_identifier(&pascal_case(item.name()));
+    let backing_field = format!("_{}", leading_digit_identifier(&camel_case(item.name())));
+    let size = format_number(GlyphOutline::GRID_SIZE);
+    let point =
+        |p: &crate::glyph::Point| format!("{}f, {}f", format_number(p.x), format_number(p.y));
+    let mut commands = String::new();
+    for segment in outline.segments() {
+        let command = match segment {
+            Segment::MoveTo(p) => format!("moveTo({})", point(p)),
+            Segment::LineTo(p) => format!("lineTo({})", point(p)),
+            Segment::QuadTo(c, p) => format!("quadTo({}, {})", point(c), point(p)),
+            Segment::CubicTo(c1, c2, p) => {
+                format!("curveTo({}, {}, {})", point(c1), point(c2), point(p))
+            }
+            Segment::Close => String::from("close()"),
+        };
+        commands.push_str(&format!("                {}\n", command));
+    }
+    format!(
+        concat!(
+            "import androidx.compose.ui.graphics.Color\n",
+            "import androidx.compose.ui.graphics.SolidColor\n",
+            "import androidx.compose.ui.graphics.vector.ImageVector\n",
+            "import androidx.compose.ui.graphics.vector.path\n",
+            "import androidx.compose.ui.unit.dp\n",
+            "\n",
+            "val {name}: ImageVector\n",
+            "    get() {{\n",
+            "        {field}?.let {{ return it }}\n",
+            "        return ImageVector.Builder(\n",
+            "            name = \"{name}\",\n",
+            "            defaultWidth = {size}.dp,\n",
+            "            defaultHeight = {size}.dp,\n",
+            "            viewportWidth = {size}f,\n",
+            "            viewportHeight = {size}f,\n",
+            "        ).apply {{\n",
+            "            path(fill = SolidColor(Color.Black)) {{\n",
+            "{commands}",
+            "            }}\n",
+            "        }}.build().also {{ {field} = it }}\n",
+            "    }}\n",
+            "\n",
+            "private var {field}: ImageVector? = null\n"
+        ),
+        name = name,
+        field = backing_field,
+        size = size,
+        commands = commands
+    )
+}
+
+/// The vector formats an icon's glyph can be converted to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VectorFormat {
+    /// A standalone SVG document (see [`to_svg`]).
+    Svg,
+    /// An Android `<vector>` drawable (see [`to_vector_drawable`]).
+    VectorDrawable,
+    /// A Jetpack Compose `ImageVector` (see [`to_compose_image_vector`]).
+    ComposeImageVector,
+}
+
+impl VectorFormat {
+    /// Converts the glyph `outline` of `item`.
+    pub fn convert(&self, item: &MaterialFontMeta, outline: &GlyphOutline) -> String {
+        match self {
+            Self::Svg => to_svg(outline),
+            Self::VectorDrawable => to_vector_drawable(outline),
+            Self::ComposeImageVector => to_compose_image_vector(item, outline),
+        }
+    }
+}
+
+/// How the icons of a generated Rust module are declared.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RustModuleStyle {
+    /// One `pub const CODEPOINT_<NAME>: u32` per icon, like the browser's own constants.
+    #[default]
+    Constants,
+    /// A `MaterialIcon` enum with `codepoint()`, `to_char()` and `name()` methods.
+    Enum,
+}
+
+/// The options of [`to_rust_module`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RustModuleOptions {
+    /// How the icons are declared.
+    pub style: RustModuleStyle,
+    /// Whether to add a `fn icon(...) -> iced::widget::Text` helper, rendering an icon with the
+    /// Material Icons font (which the application must load).
+    pub iced_helper: bool,
+}
+
+impl RustModuleOptions {
+    /// All combinations of options, for pickers.
+    pub const ALL: [Self; 4] = [
+        Self {
+            style: RustModuleStyle::Constants,
+            iced_helper: false,
+        },
+        Self {
+            style: RustModuleStyle::Constants,
+            iced_helper: true,
+        },
+        Self {
+            style: RustModuleStyle::Enum,
+            iced_helper: false,
+        },
+        Self {
+            style: RustModuleStyle::Enum,
+            iced_helper: true,
+        },
+    ];
+}
+
+impl std::fmt::Display for RustModuleOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.style {
+            RustModuleStyle::Constants => write!(f, "Rust constants")?,
+            RustModuleStyle::Enum => write!(f, "Rust enum")?,
+        }
+        if self.iced_helper {
+            write!(f, " + Iced helper")?;
+        }
+        Ok(())
+    }
+}
+
+/// A Rust module (e.g. `src/icons.rs`) declaring the codepoints of `items`, so that applications
+/// don't have to copy them by hand. Icons that would be declared under the same name as an
+/// earlier one (e.g. an icon listed twice) are left out, so that the module always compiles.
+///
+/// ```
+/// use iced_material_icon_browser::export::{to_rust_module, RustModuleOptions, RustModuleStyle};
+/// use iced_material_icon_browser::MaterialFontMetaList;
+///
+/// let list = MaterialFontMetaList::bundled().unwrap();
+/// let items = [list.get_item_by_name("arrow_back").unwrap()];
+/// let module = to_rust_module(&items, &RustModuleOptions::default());
+/// assert!(module.contains("pub const CODEPOINT_ARROW_BACK: u32 = 0xe5c4;"));
+///
+/// let options = RustModuleOptions { style: RustModuleStyle::Enum, iced_helper: true };
+/// let module = to_rust_module(&items, &options);
+/// assert!(module.contains("Self::ArrowBack => 0xe5c4,"));
+/// assert!(module.contains("pub fn icon<'a>(icon: MaterialIcon) -> iced::widget::Text<'a>"));
+/// ```
+pub fn to_rust_module(items: &[&MaterialFontMeta], options: &RustModuleOptions) -> String {
+    let identifier = |item: &MaterialFontMeta| match options.style {
+        RustModuleStyle::Constants => format!("CODEPOINT_{}", item.name().to_uppercase()),
+        RustModuleStyle::Enum => leading_digit_identifier(&pascal_case(item.name())),
+    };
+    let mut identifiers = std::collections::HashSet::new();
+    let (items, identifiers): (Vec<&MaterialFontMeta>, Vec<String>) = items
+        .iter()
+        .map(|item| (*item, identifier(item)))
+        .filter(|(_, identifier)| identifiers.insert(identifier.clone()))
+        .unzip();
+    let mut module = String::from(concat!(
+        "//! Material Icons codepoints, generated by the Iced Material Icon Browser.\n",
+        "\n"
+    ));
+    match options.style {
+        RustModuleStyle::Constants => {
+            for (item, constant) in items.iter().zip(&identifiers) {
+                module.push_str(&format!(
+                    "pub const {}: u32 = 0x{};\n",
+                    constant,
+                    item.to_hex_codepoint()
+                ));
+            }
+        }
+        RustModuleStyle::Enum => {
+            let variants = &identifiers;
+            let arms = |value: &dyn Fn(&MaterialFontMeta) -> String| {
+                items
+                    .iter()
+                    .zip(variants)
+                    .map(|(item, variant)| {
+                        format!("            Self::{} => {},\n", variant, value(item))
+                    })
+                    .collect::<String>()
+            };
+            module.push_str(
+                "#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]\n\
+                 pub enum MaterialIcon {\n",
+            );
+            for variant in variants {
+                module.push_str(&format!("    {},\n", variant));
+            }
+            module.push_str(&format!(
+                concat!(
+                    "}}\n",
+                    "\n",
+                    "impl MaterialIcon {{\n",
+                    "    /// All icons, in declaration order.\n",
+                    "    pub const ALL: [Self; {count}] = [\n",
+                    "{all}",
+                    "    ];\n",
+                    "\n",
+                    "    /// The codepoint of the icon in the Material Icons font.\n",
+                    "    pub const fn codepoint(self) -> u32 {{\n",
+                    "        match self {{\n",
+                    "{codepoints}",
+                    "        }}\n",
+                    "    }}\n",
+                    "\n",
+                    "    /// The icon as a `char`, to be rendered with the Material Icons font.\n",
+                    "    pub fn to_char(self) -> char {{\n",
+                    "        char::from_u32(self.codepoint()).unwrap()\n",
+                    "    }}\n",
+                    "\n",
+                    "    /// The name of the icon (e.g. `arrow_back`).\n",
+                    "    pub const fn name(self) -> &'static str {{\n",
+                    "        match self {{\n",
+                    "{names}",
+                    "        }}\n",
+                    "    }}\n",
+                    "}}\n"
+                ),
+                count = items.len(),
+                all = variants
+                    .iter()
+                    .map(|variant| format!("        Self::{},\n", variant))
+                    .collect::<String>(),
+                codepoints = arms(&|item| format!("0x{}", item.to_hex_codepoint())),
+                names = arms(&|item| format!("{:?}", item.name())),
+            ));
+        }
+    }
+    if options.iced_helper {
+        let (parameter, character) = match options.style {
+            RustModuleStyle::Constants => (
+                "codepoint: u32",
+                "char::from_u32(codepoint).unwrap_or(char::REPLACEMENT_CHARACTER)",
+            ),
+            RustModuleStyle::Enum => ("icon: MaterialIcon", "icon.to_char()"),
+        };
+        module.push_str(&format!(
+            concat!(
+                "\n",
+                "/// The icon as an Iced `text` widget, in the Material Icons font (which the\n",
+                "/// application must load).\n",
+                "pub fn icon<'a>({parameter}) -> iced::widget::Text<'a> {{\n",
+                "    iced::widget::text({character})\n",
+                "        .font(iced::Font::with_name(\"Material Icons\"))\n",
+                "}}\n"
+            ),
+            parameter = parameter,
+            character = character
+        ));
+    }
+    module
+}
+
+/// `name` (e.g. `arrow_back`) in PascalCase (`ArrowBack`).
+///
+/// ```
+/// use iced_material_icon_browser::export::pascal_case;
+///
+/// assert_eq!(pascal_case("arrow_back_ios"), "ArrowBackIos");
+/// assert_eq!(pascal_case("3d_rotation"), "3dRotation");
+/// ```
+pub fn pascal_case(name: &str) -> String {
+    name.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// `name` (e.g. `arrow_back`) in camelCase (`arrowBack`).
+pub fn camel_case(name: &str) -> String {
+    let pascal = pascal_case(name);
+    let mut chars = pascal.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => pascal,
+    }
+}
+
+/// `name` prefixed with `_` if it starts with a digit (e.g. `_10k`), as identifiers (in Kotlin,
+/// Rust and most other languages) can't.
+pub(crate) fn leading_digit_identifier(name: &str) -> String {
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::tests::{bundled, list};
+
+    fn enum_options() -> RustModuleOptions {
+        RustModuleOptions {
+            style: RustModuleStyle::Enum,
+            iced_helper: false,
+        }
+    }
+
+    #[test]
+    fn leading_digit_identifiers() {
+        assert_eq!(leading_digit_identifier("10k"), "_10k");
+        assert_eq!(leading_digit_identifier("3dRotation"), "_3dRotation");
+        assert_eq!(leading_digit_identifier("Home"), "Home");
+        assert_eq!(leading_digit_identifier(""), "");
+    }
+
+    #[test]
+    fn icons_listed_twice_are_declared_once() {
+        let list = list(&[("home", 0xe88a), ("search", 0xe8b6)]);
+        let home = list.get_item_by_name("home").unwrap();
+        let search = list.get_item_by_name("search").unwrap();
+        let items = [home, search, home];
+
+        let module = to_rust_module(&items, &RustModuleOptions::default());
+        assert_eq!(module.matches("pub const CODEPOINT_HOME:").count(), 1);
+        assert_eq!(module.matches("pub const CODEPOINT_SEARCH:").count(), 1);
+
+        let module = to_rust_module(&items, &enum_options());
+        assert_eq!(module.matches("    Home,\n").count(), 1);
+        assert!(module.contains("pub const ALL: [Self; 2] = ["));
+    }
+
+    #[test]
+    fn clashing_variants_keep_the_first_icon() {
+        let list = list(&[("home", 0xe88a), ("home_", 0xe88b), ("3d_rotation", 0xe84d)]);
+        let items = list.items().iter().collect::<Vec<_>>();
+
+        let module = to_rust_module(&items, &enum_options());
+        assert_eq!(module.matches("    Home,\n").count(), 1);
+        assert!(module.contains("    _3dRotation,\n"));
+        assert!(module.contains("Self::Home => 0xe88a,"));
+        assert!(!module.contains("0xe88b"));
+        assert!(module.contains("pub const ALL: [Self; 2] = ["));
+
+        // NOTE: The constants of both are distinct, and kept.
+        let module = to_rust_module(&items, &RustModuleOptions::default());
+        assert!(module.contains("pub const CODEPOINT_HOME: u32 = 0xe88a;"));
+        assert!(module.contains("pub const CODEPOINT_HOME_: u32 = 0xe88b;"));
+    }
+
+    #[test]
+    fn bundled_icons_are_declared_once_each() {
+        let items = bundled().items().iter().collect::<Vec<_>>();
+        let distinct = |declarations: Vec<&str>| {
+            let count = declarations.len();
+            let set = declarations
+                .into_iter()
+                .collect::<std::collections::HashSet<_>>();
+            assert_eq!(set.len(), count);
+            count
+        };
+
+        let module = to_rust_module(&items, &RustModuleOptions::default());
+        let constants = module
+            .lines()
+            .filter_map(|line| line.strip_prefix("pub const "))
+            .filter_map(|line| line.split(':').next())
+            .collect();
+        assert!(distinct(constants) > 1000);
+
+        let module = to_rust_module(&items, &enum_options());
+        let variants = module
+            .lines()
+            .skip_while(|line| !line.starts_with("pub enum MaterialIcon"))
+            .skip(1)
+            .take_while(|line| *line != "}")
+            .collect::<Vec<&str>>();
+        let count = distinct(variants);
+        assert!(module.contains(&format!("pub const ALL: [Self; {}] = [", count)));
+    }
+}
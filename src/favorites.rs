@@ -0,0 +1,111 @@
+//! The icons starred by the user, persisted in the configuration directory.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::LoadError;
+
+/// The version of the favorites file written by this crate.
+const FILE_VERSION: u32 = 1;
+
+/// The upgrades of favorites files written by older versions (see [`crate::config::Migration`]).
+///
+/// NOTE: When the schema changes, `FILE_VERSION` gets incremented and a function is appended
+/// here, so that files written by older versions keep loading.
+const MIGRATIONS: &[crate::config::Migration] = &[];
+
+// NOTE: Fails the build when `FILE_VERSION` and `MIGRATIONS` get out of sync.
+const _: () = assert!(FILE_VERSION as usize == MIGRATIONS.len() + 1);
+
+/// A set of icon names. Names (rather than codepoints) are stored, since they are what users
+/// recognize when reading or editing the file.
+///
+/// The file looks like `{"version": 1, "names": ["home", "settings"]}`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Favorites {
+    names: std::collections::BTreeSet<String>,
+}
+
+impl Favorites {
+    /// The name of the favorites file in the [configuration directory](crate::config::config_dir).
+    pub const FILE_NAME: &'static str = "favorites.json";
+
+    /// The default location of the favorites file.
+    pub fn default_path() -> Option<std::path::PathBuf> {
+        crate::config::config_file(Self::FILE_NAME)
+    }
+
+    /// Loads the favorites from `path`, a missing file meaning no favorites.
+    pub async fn load(path: impl AsRef<std::path::Path>) -> Result<Self, LoadError> {
+        match crate::config::read_if_exists(path).await? {
+            Some(bytes) => Self::from_json(&bytes),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Parses the contents of a favorites file.
+    pub fn from_json(bytes: &[u8]) -> Result<Self, LoadError> {
+        crate::config::from_versioned_json(bytes, "favorites", MIGRATIONS)
+    }
+
+    /// The contents of a favorites file.
+    pub fn to_json(&self) -> String {
+        crate::config::to_versioned_json(self, FILE_VERSION)
+    }
+
+    /// Writes the favorites to `path`.
+    pub async fn save(self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        crate::config::write(path, self.to_json()).await
+    }
+
+    /// The names of the favorite icons, in alphabetical order.
+    pub fn names(&self) -> &std::collections::BTreeSet<String> {
+        &self.names
+    }
+
+    /// Whether the icon named `name` is a favorite.
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+
+    /// Adds the icon named `name` to the favorites or removes it, returning whether it is now a
+    /// favorite.
+    pub fn toggle(&mut self, name: &str) -> bool {
+        if self.names.remove(name) {
+            return false;
+        }
+        self.names.insert(name.into());
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut favorites = Favorites::default();
+        favorites.toggle("home");
+        favorites.toggle("settings");
+        let json = favorites.to_json();
+        assert!(json.contains(r#""version": 1"#));
+        assert_eq!(Favorites::from_json(json.as_bytes()).unwrap(), favorites);
+    }
+
+    #[test]
+    fn versions() {
+        let favorites = Favorites::from_json(br#"{"names": ["home"]}"#).unwrap();
+        assert!(favorites.contains("home"));
+        for json in [r#"{"version": 0}"#, r#"{"version": 2}"#] {
+            assert!(
+                matches!(
+                    Favorites::from_json(json.as_bytes()),
+                    Err(LoadError::Invalid(_))
+                ),
+                "{}",
+                json
+            );
+        }
+    }
+}
@@ -0,0 +1,296 @@
+//! Reading and writing of the tables of TrueType (and OpenType) font files.
+//!
+//! Fonts opened by the user are renamed before being loaded, so that they don't get mixed up with
+//! the bundled fonts; [`crate::subset`] builds new fonts from the tables of existing ones.
+
+/// The IDs of the `name` records holding a family name: the legacy family, and the typographic
+/// and WWS families some fonts also have (and which font databases prefer).
+const FAMILY_NAME_IDS: [u16; 3] = [1, 16, 21];
+
+/// Why a font could not be read or rewritten.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FontError {
+    /// The font could not be parsed.
+    Font(String),
+    /// A table is truncated or inconsistent (or would be, once rewritten).
+    Malformed(&'static str),
+}
+
+impl std::fmt::Display for FontError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Font(message) => write!(f, "The font was rejected: {}", message),
+            Self::Malformed(tag) => write!(f, "The font's `{}` table is malformed", tag),
+        }
+    }
+}
+
+impl std::error::Error for FontError {}
+
+/// Copies `font_bytes` with `family` as its only family name, so that it doesn't get mixed up with
+/// the fonts of its original family once loaded (e.g. a newer "Material Icons" font, next to the
+/// bundled one). The glyphs and the other tables are kept as they are.
+///
+/// ```
+/// use iced_material_icon_browser::{font::rename_font_family, resources::ICONS_FONT_BYTES};
+///
+/// let renamed = rename_font_family(ICONS_FONT_BYTES, "Material Icons (opened 1)").unwrap();
+/// let face = ttf_parser::Face::parse(&renamed, 0).unwrap();
+/// let families = face
+///     .names()
+///     .into_iter()
+///     .filter(|name| name.name_id == ttf_parser::name_id::FAMILY)
+///     .filter_map(|name| name.to_string())
+///     .collect::<Vec<String>>();
+/// assert_eq!(families, ["Material Icons (opened 1)"]);
+/// assert!(face.glyph_index('\u{e88a}').is_some());
+/// ```
+pub fn rename_font_family(font_bytes: &[u8], family: &str) -> Result<Vec<u8>, FontError> {
+    let face =
+        ttf_parser::Face::parse(font_bytes, 0).map_err(|e| FontError::Font(e.to_string()))?;
+    let raw_face = face.raw_face();
+    let mut tables = vec![];
+    for record in raw_face.table_records {
+        let tag = record.tag.to_bytes();
+        let data = raw_face
+            .table(record.tag)
+            .ok_or_else(|| FontError::Font(format!("the `{}` table is truncated", record.tag)))?;
+        match &tag {
+            b"name" => continue,
+            // NOTE: `build_font` computes the `checkSumAdjustment` with the field set to 0.
+            b"head" => {
+                let mut new_head = data.to_vec();
+                write_u32(&mut new_head, 8, 0, "head")?;
+                tables.push((tag, new_head));
+            }
+            _ => tables.push((tag, data.to_vec())),
+        }
+    }
+    let name = raw_face.table(ttf_parser::Tag::from_bytes(b"name"));
+    tables.push((*b"name", rename_name_table(name, family)?));
+    let sfnt_version = if face.tables().cff.is_some() {
+        u32::from_be_bytes(*b"OTTO")
+    } else {
+        0x0001_0000
+    };
+    Ok(build_font(sfnt_version, tables))
+}
+
+/// A `name` table (format 0) with the records of `name` (if any) that aren't family names, and
+/// `family` as the (Windows, English) family name.
+fn rename_name_table(name: Option<&[u8]>, family: &str) -> Result<Vec<u8>, FontError> {
+    let mut records = vec![];
+    if let Some(name) = name {
+        let count = read_u16(name, 2, "name")? as usize;
+        let storage = read_u16(name, 4, "name")? as usize;
+        for index in 0..count {
+            let offset = 6 + index * 12;
+            let ids = [
+                read_u16(name, offset, "name")?,
+                read_u16(name, offset + 2, "name")?,
+                read_u16(name, offset + 4, "name")?,
+                read_u16(name, offset + 6, "name")?,
+            ];
+            // NOTE: Language IDs from 0x8000 refer to the language tags of format 1 tables,
+            // which aren't kept.
+            if FAMILY_NAME_IDS.contains(&ids[3]) || ids[2] >= 0x8000 {
+                continue;
+            }
+            let length = read_u16(name, offset + 8, "name")? as usize;
+            let start = storage + read_u16(name, offset + 10, "name")? as usize;
+            let string = name
+                .get(start..start + length)
+                .ok_or(FontError::Malformed("name"))?;
+            records.push((ids, string.to_vec()));
+        }
+    }
+    let family = family
+        .encode_utf16()
+        .flat_map(u16::to_be_bytes)
+        .collect::<Vec<u8>>();
+    records.push(([3, 1, 0x0409, 1], family));
+    records.sort_by_key(|(ids, _)| *ids);
+
+    // NOTE: Lengths and offsets are 16-bit, which a long family name (or a font whose strings
+    // barely fit) can exceed.
+    let to_u16 = |value: usize| u16::try_from(value).map_err(|_| FontError::Malformed("name"));
+    let mut table = vec![];
+    for value in [0, records.len(), 6 + records.len() * 12] {
+        table.extend(to_u16(value)?.to_be_bytes());
+    }
+    let mut strings = vec![];
+    for (ids, string) in records {
+        for value in ids {
+            table.extend(value.to_be_bytes());
+        }
+        table.extend(to_u16(string.len())?.to_be_bytes());
+        table.extend(to_u16(strings.len())?.to_be_bytes());
+        strings.extend(string);
+    }
+    table.extend(strings);
+    Ok(table)
+}
+
+/// Assembles the font file (`sfnt_version` being `0x00010000` for TrueType outlines, and `OTTO`
+/// for CFF ones), computing the table checksums and the `head` table's
+/// `checkSumAdjustment`.
+pub(crate) fn build_font(sfnt_version: u32, mut tables: Vec<([u8; 4], Vec<u8>)>) -> Vec<u8> {
+    tables.sort_by_key(|(tag, _)| *tag);
+    let table_count = tables.len() as u16;
+    let (search_range, entry_selector) = binary_search_parameters(table_count, 16);
+    let mut font = vec![];
+    font.extend(sfnt_version.to_be_bytes());
+    for value in [
+        table_count,
+        search_range,
+        entry_selector,
+        table_count * 16 - search_range,
+    ] {
+        font.extend(value.to_be_bytes());
+    }
+
+    let mut offset = 12 + 16 * tables.len();
+    let mut head_offset = None;
+    for (tag, data) in &tables {
+        if tag == b"head" {
+            head_offset = Some(offset);
+        }
+        font.extend(tag);
+        font.extend(checksum(data).to_be_bytes());
+        font.extend((offset as u32).to_be_bytes());
+        font.extend((data.len() as u32).to_be_bytes());
+        offset += data.len().div_ceil(4) * 4;
+    }
+    for (_, data) in tables {
+        font.extend(data);
+        while font.len() % 4 != 0 {
+            font.push(0);
+        }
+    }
+
+    if let Some(head_offset) = head_offset {
+        let adjustment = 0xb1b0_afbau32.wrapping_sub(checksum(&font));
+        font[head_offset + 8..head_offset + 12].copy_from_slice(&adjustment.to_be_bytes());
+    }
+    font
+}
+
+/// The `searchRange` (the largest power of two not above `count`, times `size`) and
+/// `entrySelector` (its log2) of binary-searchable arrays.
+pub(crate) fn binary_search_parameters(count: u16, size: u16) -> (u16, u16) {
+    let entry_selector = 15 - count.max(1).leading_zeros() as u16;
+    ((1 << entry_selector) * size, entry_selector)
+}
+
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+pub(crate) fn read_u16(data: &[u8], offset: usize, tag: &'static str) -> Result<u16, FontError> {
+    data.get(offset..offset + 2)
+        .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+        .ok_or(FontError::Malformed(tag))
+}
+
+pub(crate) fn read_u32(data: &[u8], offset: usize, tag: &'static str) -> Result<u32, FontError> {
+    data.get(offset..offset + 4)
+        .map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .ok_or(FontError::Malformed(tag))
+}
+
+pub(crate) fn write_u16(
+    data: &mut [u8],
+    offset: usize,
+    value: u16,
+    tag: &'static str,
+) -> Result<(), FontError> {
+    data.get_mut(offset..offset + 2)
+        .ok_or(FontError::Malformed(tag))?
+        .copy_from_slice(&value.to_be_bytes());
+    Ok(())
+}
+
+pub(crate) fn write_u32(
+    data: &mut [u8],
+    offset: usize,
+    value: u32,
+    tag: &'static str,
+) -> Result<(), FontError> {
+    data.get_mut(offset..offset + 4)
+        .ok_or(FontError::Malformed(tag))?
+        .copy_from_slice(&value.to_be_bytes());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::ICONS_FONT_BYTES;
+
+    fn names(font_bytes: &[u8], name_id: u16) -> Vec<String> {
+        ttf_parser::Face::parse(font_bytes, 0)
+            .unwrap()
+            .names()
+            .into_iter()
+            .filter(|name| name.name_id == name_id && name.is_unicode())
+            .filter_map(|name| name.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn renaming_keeps_other_names() {
+        let renamed = rename_font_family(ICONS_FONT_BYTES, "Icons").unwrap();
+        for name_id in [
+            ttf_parser::name_id::COPYRIGHT_NOTICE,
+            ttf_parser::name_id::SUBFAMILY,
+        ] {
+            assert!(!names(ICONS_FONT_BYTES, name_id).is_empty());
+            assert_eq!(names(&renamed, name_id), names(ICONS_FONT_BYTES, name_id));
+        }
+        assert_eq!(names(&renamed, ttf_parser::name_id::FAMILY), ["Icons"]);
+    }
+
+    #[test]
+    fn renaming_twice_leaves_one_family() {
+        let renamed = rename_font_family(ICONS_FONT_BYTES, "Icons").unwrap();
+        let renamed = rename_font_family(&renamed, "Other icons").unwrap();
+        assert_eq!(
+            names(&renamed, ttf_parser::name_id::FAMILY),
+            ["Other icons"]
+        );
+        assert!(names(&renamed, ttf_parser::name_id::TYPOGRAPHIC_FAMILY).is_empty());
+    }
+
+    #[test]
+    fn renaming_keeps_the_glyphs() {
+        let renamed = rename_font_family(ICONS_FONT_BYTES, "Icons").unwrap();
+        let face = ttf_parser::Face::parse(&renamed, 0).unwrap();
+        let original = ttf_parser::Face::parse(ICONS_FONT_BYTES, 0).unwrap();
+        assert_eq!(face.number_of_glyphs(), original.number_of_glyphs());
+        assert_eq!(
+            face.glyph_index('\u{e88a}'),
+            original.glyph_index('\u{e88a}')
+        );
+    }
+
+    #[test]
+    fn names_too_long_for_the_name_table_are_rejected() {
+        let family = "Icons ".repeat(6000);
+        assert_eq!(
+            rename_font_family(ICONS_FONT_BYTES, &family),
+            Err(FontError::Malformed("name"))
+        );
+    }
+
+    #[test]
+    fn invalid_fonts_are_rejected() {
+        assert!(matches!(
+            rename_font_family(b"not a font", "Icons"),
+            Err(FontError::Font(_))
+        ));
+    }
+}
@@ -0,0 +1,178 @@
+//! Outlines of the glyphs of an icon font, for exporting icons to vector (and raster) formats
+//! without the font.
+
+/// A point of an outline, in the coordinates of the icon grid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Point {
+    /// The distance from the left edge of the grid.
+    pub x: f32,
+    /// The distance from the top edge of the grid.
+    pub y: f32,
+}
+
+/// A drawing command of an outline.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Segment {
+    /// Starts a new contour at the point.
+    MoveTo(Point),
+    /// A straight line to the point.
+    LineTo(Point),
+    /// A quadratic Bézier curve (the only kind of curve in TrueType fonts), through a control
+    /// point to an end point.
+    QuadTo(Point, Point),
+    /// A cubic Bézier curve (found in OpenType/CFF fonts), through two control points to an end
+    /// point.
+    CubicTo(Point, Point, Point),
+    /// Closes the current contour.
+    Close,
+}
+
+/// Why a glyph outline could not be extracted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GlyphError {
+    /// The font could not be parsed.
+    Font(String),
+    /// The font has no glyph for the codepoint.
+    MissingGlyph(u32),
+}
+
+impl std::fmt::Display for GlyphError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Font(message) => write!(f, "The font was rejected: {}", message),
+            Self::MissingGlyph(codepoint) => {
+                write!(f, "The font has no glyph for codepoint {:x}", codepoint)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GlyphError {}
+
+/// The outline of an icon, scaled to the [`GRID_SIZE`](Self::GRID_SIZE)-unit square Material
+/// Icons are designed on, with the y axis pointing down (as in SVG).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GlyphOutline {
+    segments: Vec<Segment>,
+}
+
+impl GlyphOutline {
+    /// The size of the icon grid (`24`, as in the `viewBox="0 0 24 24"` of Material SVGs).
+    pub const GRID_SIZE: f32 = 24.0;
+
+    /// Extracts the outline of the glyph of `codepoint` from a TrueType (or OpenType) font. The
+    /// font's em square (from the ascender down) is mapped to the icon grid.
+    ///
+    /// ```
+    /// use iced_material_icon_browser::{resources::ICONS_FONT_BYTES, GlyphOutline};
+    ///
+    /// let outline = GlyphOutline::from_font(ICONS_FONT_BYTES, 0xe88a).unwrap();
+    /// // The font has 512 units per em, so coordinates are multiples of 24 / 512.
+    /// assert!(outline.to_path_data().starts_with("M9.984 20.016L5.016 20.016L5.016 12"));
+    /// ```
+    pub fn from_font(font_bytes: &[u8], codepoint: u32) -> Result<Self, GlyphError> {
+        let face =
+            ttf_parser::Face::parse(font_bytes, 0).map_err(|e| GlyphError::Font(e.to_string()))?;
+        let glyph_id = char::from_u32(codepoint)
+            .and_then(|c| face.glyph_index(c))
+            .ok_or(GlyphError::MissingGlyph(codepoint))?;
+        let mut builder = OutlineBuilder {
+            scale: Self::GRID_SIZE / face.units_per_em() as f32,
+            ascender: face.ascender() as f32,
+            segments: vec![],
+        };
+        // NOTE: Glyphs without contours (e.g. spaces) have no outline, which isn't an error.
+        face.outline_glyph(glyph_id, &mut builder);
+        Ok(Self {
+            segments: builder.segments,
+        })
+    }
+
+    /// The drawing commands of the outline.
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Whether the glyph has no contours.
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// The outline in the SVG path data syntax (the `d` attribute of `<path>`), with absolute
+    /// coordinates.
+    pub fn to_path_data(&self) -> String {
+        let mut data = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::MoveTo(p) => data.push_str(&format!("M{}", format_point(p))),
+                Segment::LineTo(p) => data.push_str(&format!("L{}", format_point(p))),
+                Segment::QuadTo(c, p) => {
+                    data.push_str(&format!("Q{} {}", format_point(c), format_point(p)))
+                }
+                Segment::CubicTo(c1, c2, p) => data.push_str(&format!(
+                    "C{} {} {}",
+                    format_point(c1),
+                    format_point(c2),
+                    format_point(p)
+                )),
+                Segment::Close => data.push('Z'),
+            }
+        }
+        data
+    }
+}
+
+/// Formats a coordinate with at most three decimals, and without trailing zeros.
+pub(crate) fn format_number(value: f32) -> String {
+    let formatted = format!("{:.3}", value);
+    let formatted = formatted.trim_end_matches('0').trim_end_matches('.');
+    match formatted {
+        "-0" => String::from("0"),
+        _ => formatted.into(),
+    }
+}
+
+fn format_point(point: &Point) -> String {
+    format!("{} {}", format_number(point.x), format_number(point.y))
+}
+
+struct OutlineBuilder {
+    scale: f32,
+    ascender: f32,
+    segments: Vec<Segment>,
+}
+
+impl OutlineBuilder {
+    fn point(&self, x: f32, y: f32) -> Point {
+        Point {
+            x: x * self.scale,
+            y: (self.ascender - y) * self.scale,
+        }
+    }
+}
+
+impl ttf_parser::OutlineBuilder for OutlineBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let p = self.point(x, y);
+        self.segments.push(Segment::MoveTo(p));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let p = self.point(x, y);
+        self.segments.push(Segment::LineTo(p));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (c, p) = (self.point(x1, y1), self.point(x, y));
+        self.segments.push(Segment::QuadTo(c, p));
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (c1, c2, p) = (self.point(x1, y1), self.point(x2, y2), self.point(x, y));
+        self.segments.push(Segment::CubicTo(c1, c2, p));
+    }
+
+    fn close(&mut self) {
+        self.segments.push(Segment::Close);
+    }
+}
@@ -0,0 +1,291 @@
+//! Lookup tables built once per [`MaterialFontMetaList`](crate::MaterialFontMetaList), so that
+//! lookups and searches don't need to scan every icon.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::models::MaterialFontMeta;
+
+/// Maps codepoints, names, search tokens and categories to the positions of the icons having
+/// them, in the [items](crate::MaterialFontMetaList::items) of a list. Posting lists (the
+/// positions for a given token or category) are sorted.
+#[derive(Clone, Debug, Default)]
+pub struct SearchIndex {
+    codepoints: HashMap<u32, Vec<usize>>,
+    names: HashMap<String, usize>,
+    tokens: BTreeMap<String, Vec<usize>>,
+    categories: HashMap<String, Vec<usize>>,
+}
+
+impl SearchIndex {
+    /// Indexes `items`. The tokens are the terms [`SearchText`](crate::SearchText) matches words
+    /// against: the lowercase name, its `_`-separated parts, the lowercase tags and the
+    /// categories.
+    pub fn build(items: &[MaterialFontMeta]) -> Self {
+        let mut index = Self::default();
+        for (position, item) in items.iter().enumerate() {
+            // NOTE: Like a linear search would, lookups return the first icon in file order.
+            index
+                .codepoints
+                .entry(item.codepoint())
+                .or_default()
+                .push(position);
+            index.names.entry(item.name().clone()).or_insert(position);
+
+            let name = item.name().to_lowercase();
+            let tokens = name
+                .split('_')
+                .map(String::from)
+                .chain(std::iter::once(name.clone()))
+                .chain(item.tags().iter().map(|tag| tag.to_lowercase()))
+                .chain(item.categories().iter().cloned());
+            for token in tokens {
+                let postings = index.tokens.entry(token).or_default();
+                // NOTE: Items are visited in order, so checking the last position is enough to
+                // avoid duplicates (e.g. a tag equal to a part of the name).
+                if postings.last() != Some(&position) {
+                    postings.push(position);
+                }
+            }
+
+            for category in item.categories() {
+                let postings = index.categories.entry(category.clone()).or_default();
+                if postings.last() != Some(&position) {
+                    postings.push(position);
+                }
+            }
+        }
+        index
+    }
+
+    /// The position of the first icon having `codepoint`.
+    pub fn position_by_codepoint(&self, codepoint: u32) -> Option<usize> {
+        self.positions_by_codepoint(codepoint).first().copied()
+    }
+
+    /// The positions of the icons having `codepoint`: several when some are aliases of others.
+    pub fn positions_by_codepoint(&self, codepoint: u32) -> &[usize] {
+        self.codepoints
+            .get(&codepoint)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// The number of distinct codepoints, which is less than the number of icons when some are
+    /// aliases of others.
+    pub fn codepoint_count(&self) -> usize {
+        self.codepoints.len()
+    }
+
+    /// The position of the first icon named `name`.
+    pub fn position_by_name(&self, name: &str) -> Option<usize> {
+        self.names.get(name).copied()
+    }
+
+    /// The positions of the icons of `category`.
+    pub fn category_positions(&self, category: &str) -> &[usize] {
+        self.categories
+            .get(category)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// The positions of the icons having at least one token for which `predicate` is true,
+    /// sorted and deduplicated.
+    pub fn positions_matching(&self, mut predicate: impl FnMut(&str) -> bool) -> Vec<usize> {
+        let mut positions = self
+            .tokens
+            .iter()
+            .filter(|(token, _)| predicate(token))
+            .flat_map(|(_, postings)| postings.iter().copied())
+            .collect::<Vec<usize>>();
+        positions.sort_unstable();
+        positions.dedup();
+        positions
+    }
+}
+
+/// The positions present in both sorted lists `a` and `b`.
+pub fn intersect_sorted(a: &[usize], b: &[usize]) -> Vec<usize> {
+    let (mut i, mut j) = (0, 0);
+    let mut result = vec![];
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                result.push(a[i]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::tests::{aliased_list, bundled, list};
+    use crate::{MaterialFontMetaList, Query};
+
+    /// The positions of the icons for which `predicate` is true, found by scanning every icon.
+    fn scan(
+        list: &MaterialFontMetaList,
+        predicate: impl Fn(&MaterialFontMeta) -> bool,
+    ) -> Vec<usize> {
+        list.items()
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| predicate(item))
+            .map(|(position, _)| position)
+            .collect()
+    }
+
+    fn tokens(item: &MaterialFontMeta) -> Vec<String> {
+        let name = item.name().to_lowercase();
+        name.split('_')
+            .map(String::from)
+            .chain(std::iter::once(name.clone()))
+            .chain(item.tags().iter().map(|tag| tag.to_lowercase()))
+            .chain(item.categories().iter().cloned())
+            .collect()
+    }
+
+    fn check_lookups(list: &MaterialFontMetaList) {
+        let index = list.index();
+        let mut codepoints = std::collections::HashSet::new();
+        for item in list.items() {
+            codepoints.insert(item.codepoint());
+            let positions = scan(list, |other| other.codepoint() == item.codepoint());
+            assert_eq!(index.positions_by_codepoint(item.codepoint()), positions);
+            assert_eq!(
+                index.position_by_codepoint(item.codepoint()),
+                Some(positions[0])
+            );
+            let positions = scan(list, |other| other.name() == item.name());
+            assert_eq!(index.position_by_name(item.name()), Some(positions[0]));
+        }
+        assert_eq!(index.codepoint_count(), codepoints.len());
+        assert_eq!(index.position_by_codepoint(0x10ffff), None);
+        assert!(index.positions_by_codepoint(0x10ffff).is_empty());
+        assert_eq!(index.position_by_name("no_such_icon"), None);
+    }
+
+    fn check_categories(list: &MaterialFontMetaList) {
+        for category in list.categories() {
+            let positions = scan(list, |item| item.contains_category(category));
+            assert!(!positions.is_empty());
+            assert_eq!(list.index().category_positions(category), positions);
+            let query = Query::new().category(Some(category.clone()));
+            assert_eq!(
+                query.run_positions(list),
+                scan(list, |item| query.matches(item))
+            );
+        }
+        assert!(list
+            .index()
+            .category_positions("no_such_category")
+            .is_empty());
+    }
+
+    #[test]
+    fn lookups() {
+        check_lookups(bundled());
+        check_lookups(&aliased_list());
+    }
+
+    #[test]
+    fn categories() {
+        check_categories(bundled());
+        check_categories(&aliased_list());
+    }
+
+    #[test]
+    fn token_postings() {
+        let list = bundled();
+        for token in [
+            "arrow",
+            "arrow_back",
+            "back",
+            "home",
+            "av",
+            "navigation",
+            "10k",
+        ] {
+            let positions = scan(list, |item| tokens(item).iter().any(|other| other == token));
+            assert!(!positions.is_empty(), "{}", token);
+            assert_eq!(
+                list.index().positions_matching(|other| other == token),
+                positions
+            );
+        }
+        let positions = list
+            .index()
+            .positions_matching(|token| token.starts_with("arrow"));
+        assert_eq!(
+            positions,
+            scan(list, |item| tokens(item)
+                .iter()
+                .any(|token| token.starts_with("arrow")))
+        );
+        assert!(list.index().positions_matching(|_| false).is_empty());
+        assert_eq!(
+            list.index().positions_matching(|_| true),
+            (0..list.items().len()).collect::<Vec<usize>>()
+        );
+    }
+
+    #[test]
+    fn searches_match_a_scan() {
+        for list in [bundled(), &aliased_list()] {
+            for text in [
+                "arrow back",
+                "hme",
+                "house",
+                "setings",
+                "e88a",
+                "av",
+                "action",
+            ] {
+                let query = Query::new().search_text(text);
+                let mut positions = query.run_positions(list);
+                positions.sort_unstable();
+                assert_eq!(
+                    positions,
+                    scan(list, |item| query.matches(item)),
+                    "{}",
+                    text
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn aliases_share_a_posting_list() {
+        let list = aliased_list();
+        let index = list.index();
+        assert_eq!(index.positions_by_codepoint(0xe88a), [0, 2, 3]);
+        assert_eq!(index.codepoint_count(), 2);
+        assert_eq!(index.position_by_name("cottage"), Some(3));
+        assert_eq!(index.category_positions("action"), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn duplicate_names_keep_the_first_icon() {
+        let list = list(&[("home", 0xe88a), ("home", 0xe88b)]);
+        assert_eq!(list.index().position_by_name("home"), Some(0));
+        assert_eq!(
+            list.index().positions_matching(|token| token == "home"),
+            [0, 1]
+        );
+    }
+
+    #[test]
+    fn intersections() {
+        assert_eq!(intersect_sorted(&[1, 3, 5, 7], &[2, 3, 4, 7, 8]), [3, 7]);
+        assert_eq!(intersect_sorted(&[1, 2, 3], &[1, 2, 3]), [1, 2, 3]);
+        assert!(intersect_sorted(&[1, 3], &[2, 4]).is_empty());
+        assert!(intersect_sorted(&[], &[1, 2]).is_empty());
+        assert!(intersect_sorted(&[1, 2], &[]).is_empty());
+    }
+}
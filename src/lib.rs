@@ -0,0 +1,43 @@
+//! A headless catalog of the [Material Icons](https://fonts.google.com/icons), used by the Iced
+//! Material Icon Browser and usable by other tools (e.g. build scripts) without pulling in Iced.
+//!
+//! ```
+//! use iced_material_icon_browser::{MaterialFontMetaList, Query};
+//!
+//! let list = MaterialFontMetaList::bundled().unwrap();
+//! for item in Query::new().category(Some("av".into())).run(&list) {
+//!     println!("{} {}", item.name(), item.to_hex_codepoint());
+//! }
+//! ```
+
+#![warn(missing_docs)]
+
+pub mod collections;
+pub mod config;
+pub mod diff;
+pub mod export;
+pub mod favorites;
+pub mod font;
+pub mod glyph;
+pub mod index;
+pub mod models;
+pub mod query;
+pub mod query_language;
+pub mod raster;
+pub mod resources;
+pub mod search;
+pub mod snippets;
+pub mod state;
+pub mod subset;
+pub mod usage;
+pub mod validation;
+
+pub use collections::{Collection, CollectionFormat, Collections};
+pub use favorites::Favorites;
+pub use glyph::{GlyphError, GlyphOutline};
+pub use index::SearchIndex;
+pub use models::{LoadError, MaterialFontMeta, MaterialFontMetaList, MaterialFontMetaListHeader};
+pub use query::Query;
+pub use query_language::{ParseError, SearchQuery};
+pub use search::SearchText;
+pub use state::{BrowsingSection, SessionState};
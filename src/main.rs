@@ -1,22 +1,25 @@
 use iced::Application as _;
 
 use copy_to_clipboard_animation::{AnimationInfo, CopyType};
-use models::{LoadError, MaterialFontMeta, MaterialFontMetaList};
+use iced_material_icon_browser::diff::{DiffKind, SnapshotDiff};
+use iced_material_icon_browser::export::{self, RustModuleOptions, VectorFormat};
+use iced_material_icon_browser::font;
+use iced_material_icon_browser::raster::{DensitySet, RasterOptions, Rgba};
+use iced_material_icon_browser::resources::{ICONS_FONT_BYTES, ICONS_FONT_NAME, ICONS_META_BYTES};
+use iced_material_icon_browser::snippets::{CustomSnippets, Snippet};
+use iced_material_icon_browser::subset;
+use iced_material_icon_browser::usage::ProjectUsage;
+use iced_material_icon_browser::validation::{self, Severity, ValidationReport};
+use iced_material_icon_browser::{
+    raster, BrowsingSection, Collection, Collections, Favorites, GlyphError, GlyphOutline,
+    LoadError, MaterialFontMeta, MaterialFontMetaList, ParseError, Query, SearchQuery,
+    SessionState,
+};
 use styling::{CustomContainer, CustomRule, CustomTheme, RowButton, ToolbarButton};
 
-mod models;
 mod styling;
 mod text_input_wrapper;
 
-#[cfg(windows)]
-const ICONS_FONT_BYTES: &[u8] = include_bytes!("..\\resources\\MaterialIcons-Regular.ttf");
-#[cfg(unix)]
-const ICONS_FONT_BYTES: &[u8] = include_bytes!("../resources/MaterialIcons-Regular.ttf");
-#[cfg(windows)]
-const ICONS_META_BYTES: &[u8] =
-    include_bytes!("..\\resources\\2023-09-12-material-icons-meta.json");
-#[cfg(unix)]
-const ICONS_META_BYTES: &[u8] = include_bytes!("../resources/2023-09-12-material-icons-meta.json");
 #[cfg(windows)]
 const FONT_BYTES_REGULAR: &[u8] = include_bytes!("..\\resources\\Roboto\\Roboto-Regular.ttf");
 #[cfg(unix)]
@@ -27,12 +30,16 @@ const FONT_BYTES_BOLD: &[u8] = include_bytes!("..\\resources\\Roboto\\Roboto-Bol
 const FONT_BYTES_BOLD: &[u8] = include_bytes!("../resources/Roboto/Roboto-Bold.ttf");
 
 const APP_TITLE: &'static str = "Iced Material Icon Browser";
+const USAGE: &'static str =
+    "Usage: iced-material-icon-browser [--meta <metadata.json>] [--font <font.ttf|font.otf>]";
 
-const ICONS_FONT_NAME: &'static str = "Material Icons";
 const FONT_NAME: &'static str = "Roboto";
 
 const SCROLLABLE_ICON_LIST_ID: &'static str = "scrollable_icon_list_id";
 const SEARCH_TEXT_INPUT_ID: &'static str = "search_text_input_id";
+const SEARCH_TEXT_INPUT_WIDTH: f32 = 200.0;
+const PNG_NUMBER_INPUT_WIDTH: f32 = 60.0;
+const PNG_COLOR_INPUT_WIDTH: f32 = 100.0;
 
 const SPACING_SMALL: u16 = 5;
 const SPACING_NORMAL: u16 = 10;
@@ -52,6 +59,17 @@ const ICON_FONT_SIZE_MEDIUM: u16 = 34;
 const ICON_FONT_SIZE_TOOLBAR: u16 = 24;
 
 const SIDEBAR_WIDTH: f32 = 200.0;
+const PANEL_HEIGHT: f32 = 160.0;
+const SCROLLBAR_WIDTH: f32 = 10.0;
+const TOOLBAR_HEIGHT: f32 = 52.0;
+
+// NOTE: Grid and list items have fixed heights, so that only the rows intersecting the viewport
+// need to be laid out (see `view_virtualized_column`).
+const ITEM_HEIGHT_SMALL: f32 = 32.0;
+const ITEM_HEIGHT_MEDIUM: f32 = 104.0;
+const VIRTUALIZATION_OVERSCAN_ROWS: usize = 2;
+/// The average width of a character relative to the font size, used to estimate text widths.
+const AVERAGE_CHAR_WIDTH_RATIO: f32 = 0.55;
 
 const WINDOW_INITIAL_WIDTH: u32 = 1000;
 const WINDOW_INITIAL_HEIGHT: u32 = 600;
@@ -69,43 +87,469 @@ const CODEPOINT_LIST: u32 = 57921;
 const CODEPOINT_SEARCH: u32 = 59574;
 const CODEPOINT_CLOSE: u32 = 58829;
 const CODEPOINT_CLOSE_CIRCLE: u32 = 58825;
+const CODEPOINT_CHECK_CIRCLE: u32 = 59500;
+const CODEPOINT_FOLDER_OPEN: u32 = 58056;
+const CODEPOINT_ERROR: u32 = 57344;
+const CODEPOINT_STAR: u32 = 59448;
+const CODEPOINT_STAR_BORDER: u32 = 59450;
+const CODEPOINT_COLLECTION: u32 = 58417;
+const CODEPOINT_LIBRARY_ADD: u32 = 57390;
+const CODEPOINT_IMPORT: u32 = 58052;
+const CODEPOINT_EXPORT: u32 = 58054;
+const CODEPOINT_DELETE: u32 = 59506;
+const CODEPOINT_SAVE: u32 = 57697;
+const CODEPOINT_FONT_DOWNLOAD: u32 = 57703;
+const CODEPOINT_MANAGE_SEARCH: u32 = 61487;
+const CODEPOINT_CODE: u32 = 59503;
+const CODEPOINT_WARNING: u32 = 61571;
+const CODEPOINT_FACT_CHECK: u32 = 61637;
+const CODEPOINT_LAYERS: u32 = 58683;
+const CODEPOINT_COMPARE: u32 = 59669;
+const CODEPOINT_DIFFERENCE: u32 = 60285;
+
+/// Shortens `s` to `max_chars` characters (including the trailing ellipsis).
+fn truncated_string(s: &str, max_chars: usize) -> String {
+    if s.chars().count() <= max_chars {
+        return s.into();
+    }
+    let mut truncated = s.chars().take(max_chars - 1).collect::<String>();
+    truncated.push('…');
+    truncated
+}
 
 fn capitalized_string(s: impl Into<String>) -> String {
     let s: String = s.into();
-    if s.is_empty() {
-        return s;
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => String::new(),
     }
-    let c = format!("{}", &s[0..1]).to_ascii_uppercase();
-    format!("{}{}", c, &s[1..])
 }
 
 async fn type_to_async<T>(t: T) -> T {
     t
 }
 
+fn font_family_name(face: &ttf_parser::Face) -> Option<String> {
+    face.names()
+        .into_iter()
+        .filter(|name| name.name_id == ttf_parser::name_id::FAMILY && name.is_unicode())
+        .find_map(|name| name.to_string())
+}
+
+async fn pick_icon_set_paths() -> Option<IconSetPaths> {
+    let meta = rfd::AsyncFileDialog::new()
+        .set_title("Open icon metadata")
+        .add_filter("Icon metadata", &["json"])
+        .pick_file()
+        .await?;
+    let font = rfd::AsyncFileDialog::new()
+        .set_title("Open icon font (cancel to use the bundled font)")
+        .add_filter("Icon font", &["ttf", "otf"])
+        .pick_file()
+        .await;
+    Some(IconSetPaths {
+        meta: Some(meta.path().to_path_buf()),
+        font: font.map(|handle| handle.path().to_path_buf()),
+    })
+}
+
+async fn export_collection(collection: Collection) -> Result<(), String> {
+    let Some(handle) = rfd::AsyncFileDialog::new()
+        .set_title("Export collection")
+        .add_filter("JSON", &["json"])
+        .add_filter("TOML", &["toml"])
+        .set_file_name(&format!("{}.json", collection.name()))
+        .save_file()
+        .await
+    else {
+        return Ok(());
+    };
+    collection
+        .export(handle.path())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn save_svg(name: String, svg: String) -> Result<(), String> {
+    let Some(handle) = rfd::AsyncFileDialog::new()
+        .set_title("Export SVG")
+        .add_filter("SVG", &["svg"])
+        .set_file_name(&format!("{}.svg", name))
+        .save_file()
+        .await
+    else {
+        return Ok(());
+    };
+    tokio::fs::write(handle.path(), svg)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn save_rust_module(module: String) -> Result<(), String> {
+    let Some(handle) = rfd::AsyncFileDialog::new()
+        .set_title("Export Rust module")
+        .add_filter("Rust", &["rs"])
+        .set_file_name("icons.rs")
+        .save_file()
+        .await
+    else {
+        return Ok(());
+    };
+    tokio::fs::write(handle.path(), module)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Writes a subset font to a file picked by the user, along with its metadata (in a `.json` file
+/// of the same name), asking before replacing files the dialog didn't ask about.
+async fn save_subset_font(font: Vec<u8>, meta_json: String) -> Result<(), String> {
+    let Some(handle) = rfd::AsyncFileDialog::new()
+        .set_title("Build subset font")
+        .add_filter("TrueType font", &["ttf"])
+        .set_file_name("MaterialIcons-Subset.ttf")
+        .save_file()
+        .await
+    else {
+        return Ok(());
+    };
+    // NOTE: The dialog only asks about replacing the picked file, which may not even be the font
+    // (e.g. `icons.json`).
+    let (font_path, meta_path) = subset::output_paths(handle.path());
+    let existing = [&font_path, &meta_path]
+        .into_iter()
+        .filter(|path| path.as_path() != handle.path() && path.exists())
+        .map(|path| path.display().to_string())
+        .collect::<Vec<String>>();
+    if !existing.is_empty() {
+        let replace = rfd::AsyncMessageDialog::new()
+            .set_level(rfd::MessageLevel::Warning)
+            .set_title("Build subset font")
+            .set_description(&format!(
+                "{} already exists. Do you want to replace it?",
+                existing.join(" and ")
+            ))
+            .set_buttons(rfd::MessageButtons::YesNo)
+            .show()
+            .await;
+        if !replace {
+            return Ok(());
+        }
+    }
+    tokio::fs::write(&font_path, font)
+        .await
+        .map_err(|e| format!("{}: {}", font_path.display(), e))?;
+    tokio::fs::write(&meta_path, meta_json)
+        .await
+        .map_err(|e| format!("{}: {}", meta_path.display(), e))
+}
+
+/// Renders `items` to PNG files, in a file picked by the user when there is a single image, or
+/// else in a picked folder. Returns the number of files written.
+async fn export_pngs(
+    font_bytes: std::borrow::Cow<'static, [u8]>,
+    items: Vec<MaterialFontMeta>,
+    options: RasterOptions,
+    densities: DensitySet,
+) -> Result<usize, String> {
+    let single_file = items.len() == 1 && densities == DensitySet::Single;
+    let dialog = rfd::AsyncFileDialog::new().set_title("Export PNG");
+    let handle = if single_file {
+        dialog
+            .add_filter("PNG", &["png"])
+            .set_file_name(&format!("{}.png", items[0].name()))
+            .save_file()
+            .await
+    } else {
+        dialog.pick_folder().await
+    };
+    let Some(handle) = handle else {
+        return Ok(0);
+    };
+
+    let mut count = 0;
+    for item in &items {
+        let files = raster::render_icon(&font_bytes, item, &options, densities)
+            .map_err(|e| format!("{}: {}", item.name(), e))?;
+        for (path, bytes) in files {
+            let path = if single_file {
+                handle.path().to_path_buf()
+            } else {
+                handle.path().join(path)
+            };
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            }
+            tokio::fs::write(&path, bytes)
+                .await
+                .map_err(|e| format!("{}: {}", path.display(), e))?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+/// Scans a folder picked by the user for icon references. Returns `None` if the dialog was
+/// cancelled.
+async fn scan_project() -> Option<Result<ProjectUsage, String>> {
+    let handle = rfd::AsyncFileDialog::new()
+        .set_title("Scan project")
+        .pick_folder()
+        .await?;
+    let root = handle.path().to_path_buf();
+    // NOTE: The walk is synchronous, so it runs on a blocking thread, not on the executor's.
+    let scan = tokio::task::spawn_blocking(move || {
+        ProjectUsage::scan(&root).map_err(|e| format!("{}: {}", root.display(), e))
+    });
+    Some(scan.await.unwrap_or_else(|e| Err(e.to_string())))
+}
+
+/// Loads a metadata file picked by the user, to compare the browsed icon set to. Returns `None` if
+/// the dialog was cancelled.
+async fn pick_comparison() -> Option<(std::path::PathBuf, Result<MaterialFontMetaList, LoadError>)>
+{
+    let handle = rfd::AsyncFileDialog::new()
+        .set_title("Compare with older icon metadata")
+        .add_filter("Icon metadata", &["json"])
+        .pick_file()
+        .await?;
+    let path = handle.path().to_path_buf();
+    let meta_list = MaterialFontMetaList::load_from_path(&path).await;
+    Some((path, meta_list))
+}
+
+async fn import_collection() -> Option<Result<Collection, LoadError>> {
+    let handle = rfd::AsyncFileDialog::new()
+        .set_title("Import collection")
+        .add_filter("Collection", &["json", "toml"])
+        .pick_file()
+        .await?;
+    Some(Collection::import(handle.path()).await)
+}
+
 fn main() -> iced::Result {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+    let icon_set_paths = match IconSetPaths::from_args(args.into_iter()) {
+        Ok(paths) => paths,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let mut state_path = SessionState::default_path();
+    let mut state_error = None;
+    let state = match state_path.as_ref().map(SessionState::load_blocking) {
+        Some(Ok(state)) => state,
+        Some(Err(e)) => {
+            // NOTE: Saving gets disabled (as with favorites), so that the file isn't overwritten.
+            state_error = Some(format!("Failed to load the window state: {}", e));
+            state_path = None;
+            SessionState::default()
+        }
+        None => SessionState::default(),
+    };
+    let (width, height) = state
+        .window_size
+        .unwrap_or((WINDOW_INITIAL_WIDTH, WINDOW_INITIAL_HEIGHT));
+    let size = (width.max(WINDOW_MIN_WIDTH), height.max(WINDOW_MIN_HEIGHT));
+    let position = match state.window_position {
+        Some((x, y)) => iced::window::Position::Specific(x, y),
+        None => iced::window::Position::Default,
+    };
+
     MyApp::run(iced::Settings {
+        flags: Flags {
+            icon_set_paths,
+            state_path,
+            state,
+            state_error,
+        },
         window: iced::window::Settings {
-            position: iced::window::Position::Specific(20, 900),
-            size: (WINDOW_INITIAL_WIDTH, WINDOW_INITIAL_HEIGHT),
+            position,
+            size,
             min_size: Some((WINDOW_MIN_WIDTH, WINDOW_MIN_HEIGHT)),
             ..Default::default()
         },
+        // NOTE: The state is saved before the window gets closed.
+        exit_on_close_request: false,
         ..Default::default()
     })
 }
 
+/// What the application starts with: the icon set to browse and the state of the last session.
+#[derive(Clone, Debug, Default)]
+struct Flags {
+    icon_set_paths: IconSetPaths,
+    state_path: Option<std::path::PathBuf>,
+    state: SessionState,
+    state_error: Option<String>,
+}
+
+/// The files describing the icon set to browse. Missing paths fall back to the
+/// bundled resources.
+#[derive(Clone, Debug, Default)]
+struct IconSetPaths {
+    meta: Option<std::path::PathBuf>,
+    font: Option<std::path::PathBuf>,
+}
+
+impl IconSetPaths {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut paths = Self::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--meta" => {
+                    paths.meta = Some(args.next().ok_or("Missing path after `--meta`")?.into())
+                }
+                "--font" => {
+                    paths.font = Some(args.next().ok_or("Missing path after `--font`")?.into())
+                }
+                _ => return Err(format!("Unexpected argument `{}`", arg)),
+            }
+        }
+        Ok(paths)
+    }
+
+    fn describe(path: &Option<std::path::PathBuf>) -> String {
+        path.as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or(String::from("(bundled)"))
+    }
+}
+
+/// An older snapshot of the metadata, which the browsed icon set is compared to.
+#[derive(Clone, Debug)]
+struct Comparison {
+    path: std::path::PathBuf,
+    meta_list: MaterialFontMetaList,
+    diff: SnapshotDiff,
+}
+
+impl Comparison {
+    fn new(
+        path: std::path::PathBuf,
+        meta_list: MaterialFontMetaList,
+        new: &MaterialFontMetaList,
+    ) -> Self {
+        let diff = SnapshotDiff::new(&meta_list, new);
+        Self {
+            path,
+            meta_list,
+            diff,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct IconSet {
+    paths: IconSetPaths,
+    meta_list: MaterialFontMetaList,
+    font_bytes: Option<Vec<u8>>,
+    renamed_font_bytes: Option<Vec<u8>>,
+    font_family: String,
+}
+
+impl IconSet {
+    async fn load(paths: IconSetPaths) -> Result<Self, LoadError> {
+        let meta_list = match &paths.meta {
+            Some(path) => MaterialFontMetaList::load_from_path(path).await?,
+            None => {
+                MaterialFontMetaList::load_from_bytes_fake_async(std::borrow::Cow::from(
+                    ICONS_META_BYTES,
+                ))
+                .await?
+            }
+        };
+        let (font_bytes, renamed_font_bytes, font_family) = match &paths.font {
+            Some(path) => {
+                let bytes = tokio::fs::read(path).await?;
+                // NOTE: Iced doesn't report fonts it fails to parse, so the font is checked here
+                // before being handed over to it.
+                let face = ttf_parser::Face::parse(&bytes, 0)
+                    .map_err(|e| LoadError::Font(e.to_string()))?;
+                if face.tables().cmap.is_none() {
+                    return Err(LoadError::Font("the font has no character map".into()));
+                }
+                // NOTE: Iced looks fonts up by family, so a font of the same family as the bundled
+                // one (or as a previously opened one) would be shadowed by it. The font is loaded
+                // under a family of its own instead, while exports use it as it was opened.
+                static COUNTER: std::sync::atomic::AtomicUsize =
+                    std::sync::atomic::AtomicUsize::new(0);
+                let family = format!(
+                    "{} (opened {})",
+                    font_family_name(&face).unwrap_or_else(|| String::from(ICONS_FONT_NAME)),
+                    COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1
+                );
+                let renamed_bytes = font::rename_font_family(&bytes, &family)
+                    .map_err(|e| LoadError::Font(e.to_string()))?;
+                (Some(bytes), Some(renamed_bytes), family)
+            }
+            None => (None, None, String::from(ICONS_FONT_NAME)),
+        };
+        Ok(Self {
+            paths,
+            meta_list,
+            font_bytes,
+            renamed_font_bytes,
+            font_family,
+        })
+    }
+}
+
 #[derive(Clone, Debug)]
 struct MyApp {
     meta_list: MaterialFontMetaList,
+    icon_set_paths: IconSetPaths,
+    icon_set_loaded: bool,
+    icons_font_family: &'static str,
+    icons_font_bytes: Option<Vec<u8>>,
+    validation: Option<Result<ValidationReport, String>>,
+    diagnostics_visible: bool,
+    load_error: Option<(IconSetPaths, LoadError)>,
+    bundled_fonts_failed: bool,
     loaded_resources_count: usize,
-    selected_category: Option<String>,
+    section: Section,
     search_text: String,
+    search_query: SearchQuery,
+    search_error: Option<ParseError>,
     search_visible: bool,
-    codepoint: Option<u32>,
+    visible_items: Vec<usize>,
+    focus: FocusArea,
+    focused_item: Option<usize>,
+    scroll_offset: f32,
+    section_scroll_offsets: std::collections::HashMap<Section, f32>,
+    previewed: Option<String>,
+    export_status: Option<Result<String, String>>,
+    png_settings: PngSettings,
+    favorites: Favorites,
+    favorites_path: Option<std::path::PathBuf>,
+    collections: Collections,
+    collections_path: Option<std::path::PathBuf>,
+    collection_error: Option<String>,
+    config_errors: Vec<String>,
+    new_collection_name: String,
+    custom_snippets: CustomSnippets,
+    custom_snippets_error: Option<String>,
+    project_usage: Option<ProjectUsage>,
+    usage_error: Option<String>,
+    comparison: Option<Comparison>,
+    comparison_error: Option<String>,
+    selection: std::collections::BTreeSet<String>,
+    modifiers: iced::keyboard::Modifiers,
     custom_theme: CustomTheme,
     grid_view: bool,
+    collapse_aliases: bool,
     window_size: (u32, u32),
+    window_position: Option<(i32, i32)>,
+    state_path: Option<std::path::PathBuf>,
+    pending_state: Option<SessionState>,
     copy_animation_info: Option<AnimationInfo>,
 }
 
@@ -113,32 +557,252 @@ impl Default for MyApp {
     fn default() -> Self {
         Self {
             meta_list: Default::default(),
+            icon_set_paths: Default::default(),
+            icon_set_loaded: false,
+            icons_font_family: ICONS_FONT_NAME,
+            icons_font_bytes: None,
+            validation: None,
+            diagnostics_visible: false,
+            load_error: None,
+            bundled_fonts_failed: false,
             loaded_resources_count: 0,
-            selected_category: None,
+            section: Section::All,
             search_text: String::new(),
+            search_query: SearchQuery::default(),
+            search_error: None,
             search_visible: false,
-            codepoint: None,
+            visible_items: vec![],
+            focus: FocusArea::Items,
+            focused_item: None,
+            scroll_offset: 0.0,
+            section_scroll_offsets: Default::default(),
+            previewed: None,
+            export_status: None,
+            png_settings: PngSettings::default(),
+            favorites: Favorites::default(),
+            // NOTE: Set once the favorites are loaded, so that they can't be overwritten before.
+            favorites_path: None,
+            collections: Collections::default(),
+            // NOTE: Same as `favorites_path`.
+            collections_path: None,
+            collection_error: None,
+            config_errors: vec![],
+            new_collection_name: String::new(),
+            custom_snippets: CustomSnippets::default(),
+            custom_snippets_error: None,
+            project_usage: None,
+            usage_error: None,
+            comparison: None,
+            comparison_error: None,
+            selection: Default::default(),
+            modifiers: Default::default(),
             custom_theme: CustomTheme::new(),
             grid_view: true,
+            collapse_aliases: true,
             window_size: (WINDOW_INITIAL_WIDTH, WINDOW_INITIAL_HEIGHT),
+            window_position: None,
+            state_path: None,
+            pending_state: None,
             copy_animation_info: None,
         }
     }
 }
 
+/// The icons a Rust module or a subset font is generated for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ExportSource {
+    Selection,
+    Collection(String),
+    Catalog,
+}
+
+/// The size picked for PNG exports.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PngSize {
+    Standard(u32),
+    Custom,
+}
+
+impl std::fmt::Display for PngSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Standard(size) => write!(f, "{}px", size),
+            Self::Custom => write!(f, "Custom"),
+        }
+    }
+}
+
+/// The PNG export options, as typed in the preview.
+#[derive(Clone, Debug)]
+struct PngSettings {
+    size: PngSize,
+    custom_size: String,
+    color: String,
+    background: String,
+    padding: String,
+    densities: DensitySet,
+}
+
+impl Default for PngSettings {
+    fn default() -> Self {
+        Self {
+            size: PngSize::Standard(24),
+            custom_size: String::new(),
+            color: Rgba::BLACK.to_hex(),
+            background: String::new(),
+            padding: String::from("0"),
+            densities: DensitySet::Single,
+        }
+    }
+}
+
+impl PngSettings {
+    fn to_options(&self) -> Result<RasterOptions, String> {
+        let size = match self.size {
+            PngSize::Standard(size) => size,
+            PngSize::Custom => self
+                .custom_size
+                .trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|size| *size > 0)
+                .ok_or_else(|| format!("Invalid size `{}`", self.custom_size))?,
+        };
+        let foreground = Rgba::parse(&self.color)
+            .ok_or_else(|| format!("Invalid color `{}` (expected #rrggbb)", self.color))?;
+        let background = match self.background.trim() {
+            "" | "transparent" => None,
+            background => Some(Rgba::parse(background).ok_or_else(|| {
+                format!("Invalid background `{}` (expected #rrggbb)", background)
+            })?),
+        };
+        let padding = match self.padding.trim() {
+            "" => 0,
+            padding => padding
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid padding `{}`", padding))?,
+        };
+        Ok(RasterOptions {
+            size,
+            foreground,
+            background,
+            padding,
+        })
+    }
+}
+
+/// What the sidebar selects for browsing.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+enum Section {
+    #[default]
+    All,
+    Favorites,
+    Category(String),
+    Collection(String),
+    /// The icons used by the scanned project.
+    Usage,
+    /// The icons of a bucket of the comparison with an older snapshot.
+    Diff(DiffKind),
+}
+
+impl Section {
+    fn label(&self) -> String {
+        match self {
+            Self::All => String::from("All"),
+            Self::Favorites => String::from("Favorites"),
+            Self::Category(name) => capitalized_string(name),
+            Self::Collection(name) => name.clone(),
+            Self::Usage => String::from("Used icons"),
+            Self::Diff(kind) => kind.to_string(),
+        }
+    }
+}
+
+impl From<BrowsingSection> for Section {
+    fn from(section: BrowsingSection) -> Self {
+        match section {
+            BrowsingSection::All => Self::All,
+            BrowsingSection::Favorites => Self::Favorites,
+            BrowsingSection::Category(name) => Self::Category(name),
+            BrowsingSection::Collection(name) => Self::Collection(name),
+        }
+    }
+}
+
+impl From<Section> for BrowsingSection {
+    fn from(section: Section) -> Self {
+        match section {
+            Section::All => Self::All,
+            Section::Favorites => Self::Favorites,
+            Section::Category(name) => Self::Category(name),
+            Section::Collection(name) => Self::Collection(name),
+            // NOTE: Scans and comparisons aren't saved, so the next session starts with all icons.
+            Section::Usage | Section::Diff(_) => Self::All,
+        }
+    }
+}
+
+/// The part of the window receiving keyboard navigation, cycled through with Tab.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FocusArea {
+    Sidebar,
+    Items,
+    Preview,
+}
+
 #[derive(Clone, Debug)]
 enum MyMessage {
-    FontLoaded,
-    MetaListLoaded(MaterialFontMetaList),
-    Category(Option<String>),
+    FontLoaded(Result<(), LoadError>),
+    IconSetLoaded(IconSet),
+    IconSetFontLoaded(IconSet),
+    IconSetLoadFailed(IconSetPaths, LoadError),
+    LoadIconSet(IconSetPaths),
+    DismissLoadError,
+    Open,
+    OpenPicked(Option<IconSetPaths>),
+    Section(Section),
     Search(String),
     SearchFocusState(bool),
     SearchVisibleState(bool),
+    DiagnosticsVisibleState(bool),
     GridViewState(bool),
-    Codepoint(Option<u32>),
+    CollapseAliasesState(bool),
+    Preview(Option<String>),
+    FavoritesLoaded(std::path::PathBuf, Result<Favorites, LoadError>),
+    ToggleFavorite(String),
+    FavoritesSaved(Result<(), String>),
+    CollectionsLoaded(std::path::PathBuf, Result<Collections, LoadError>),
+    CustomSnippetsLoaded(std::path::PathBuf, Result<CustomSnippets, LoadError>),
+    CollectionsSaved(Result<(), String>),
+    DismissConfigErrors,
+    NewCollectionName(String),
+    CreateCollection,
+    AddToCollection(String, Vec<String>),
+    RemoveFromCollection(String, String),
+    DeleteCollection(String),
+    ExportCollection(String),
+    CollectionExported(Result<(), String>),
+    ImportCollection,
+    CollectionImported(Option<Result<Collection, LoadError>>),
+    ScanProject,
+    ProjectScanned(Option<Result<ProjectUsage, String>>),
+    CompareWith,
+    ComparisonLoaded(Option<(std::path::PathBuf, Result<MaterialFontMetaList, LoadError>)>),
+    CloseComparison,
+    ClearSelection,
+    Scrolled(iced::widget::scrollable::Viewport),
     Event(iced::event::Event),
     Copy(String, AnimationInfo),
+    CopyVector(String, VectorFormat),
+    ExportSvg(String),
+    FileExported(Result<(), String>),
+    ExportRustModule(ExportSource, RustModuleOptions),
+    BuildSubsetFont(ExportSource),
+    PngSettings(PngSettings),
+    ExportPng(Vec<String>),
+    PngsExported(Result<usize, String>),
     CopiedAnimationTick,
+    StateSaved(Result<(), String>),
 }
 
 impl MyApp {
@@ -151,6 +815,15 @@ impl MyApp {
         }
     }
 
+    /// The font of the icon set being browsed, which may differ from the bundled
+    /// `icons_font` (used for the UI) when a font was supplied at runtime.
+    fn browsed_icons_font(&self) -> iced::Font {
+        iced::Font {
+            family: iced::font::Family::Name(self.icons_font_family),
+            ..self.icons_font()
+        }
+    }
+
     fn font(&self) -> iced::Font {
         iced::Font {
             weight: iced::font::Weight::Normal,
@@ -176,75 +849,247 @@ impl MyApp {
     }
 
     fn are_resources_loaded(&self) -> bool {
-        self.loaded_resources_count == 4
+        self.loaded_resources_count == 3 && self.icon_set_loaded
     }
 
-    fn view_sidebar(&self) -> iced::Element<'_, MyMessage> {
-        let searching: bool = !self.search_text.is_empty();
+    /// Loads the icon font of the UI and the text fonts (each counting as a loaded resource).
+    fn load_bundled_fonts() -> iced::Command<MyMessage> {
+        iced::Command::batch(
+            [ICONS_FONT_BYTES, FONT_BYTES_REGULAR, FONT_BYTES_BOLD].map(|bytes| {
+                iced::font::load(std::borrow::Cow::from(bytes)).map(|r| {
+                    MyMessage::FontLoaded(r.map_err(|e| LoadError::Font(format!("{:?}", e))))
+                })
+            }),
+        )
+    }
 
-        let mut categories = self
-            .meta_list
-            .categories()
-            .iter()
-            .zip(self.meta_list.category_codepoints())
-            .map(|(name, category_codepoint)| {
-                let selected = if let Some(current) = self.selected_category.as_ref() {
-                    name == current
-                } else {
-                    false
-                };
-                let text = iced::widget::text(capitalized_string(name))
-                    .font(self.selected_font(selected && !searching))
-                    .size(FONT_SIZE_STANDARD);
-                let icon = {
-                    let icon = iced::widget::text(char::from_u32(*category_codepoint).unwrap())
-                        .font(self.icons_font())
-                        .size(ICON_FONT_SIZE_TINY);
-                    if !selected {
-                        icon.style(iced::theme::Text::Color(self.theme().palette().primary))
-                    } else {
-                        icon
-                    }
-                };
-                iced::widget::button(
-                    iced::widget::row!(icon, text)
-                        .align_items(iced::Alignment::Center)
-                        .spacing(SPACING_NORMAL),
-                )
-                .on_press(MyMessage::Category(Some(name.clone())))
-                .style(RowButton::new(selected && !searching).into())
-                .width(iced::Length::Fill)
-                .into()
+    fn load_icon_set(&self, paths: IconSetPaths) -> iced::Command<MyMessage> {
+        iced::Command::perform(IconSet::load(paths.clone()), move |r| match r {
+            Err(e) => MyMessage::IconSetLoadFailed(paths, e),
+            Ok(icon_set) => MyMessage::IconSetLoaded(icon_set),
+        })
+    }
+
+    fn apply_icon_set(&mut self, icon_set: IconSet) {
+        self.icons_font_family = if icon_set.font_family == ICONS_FONT_NAME {
+            ICONS_FONT_NAME
+        } else {
+            // NOTE: `iced::font::Family::Name` requires a `&'static str`, so the family name of a
+            // user-supplied font gets leaked (once per opened font).
+            Box::leak(icon_set.font_family.into_boxed_str())
+        };
+        self.icons_font_bytes = icon_set.font_bytes;
+        self.meta_list = icon_set.meta_list;
+        if let Some(comparison) = self.comparison.take() {
+            self.comparison = Some(Comparison::new(
+                comparison.path,
+                comparison.meta_list,
+                &self.meta_list,
+            ));
+        }
+        let font_bytes = self.icons_font_bytes.as_deref().unwrap_or(ICONS_FONT_BYTES);
+        self.validation =
+            Some(validation::validate(&self.meta_list, font_bytes).map_err(|e| e.to_string()));
+        self.icon_set_paths = icon_set.paths;
+        self.icon_set_loaded = true;
+        self.load_error = None;
+        self.section = Section::All;
+        self.previewed = None;
+        self.section_scroll_offsets.clear();
+        self.refresh_visible_items();
+    }
+
+    /// Restores what was browsed in the last session, once the icon set and the fonts are
+    /// loaded (i.e. once the grid or list can be scrolled).
+    fn restore_session_state(&mut self) -> iced::Command<MyMessage> {
+        if !self.are_resources_loaded() {
+            return iced::Command::none();
+        }
+        let Some(state) = self.pending_state.take() else {
+            return iced::Command::none();
+        };
+        self.section = match Section::from(state.section) {
+            Section::Category(category) if !self.meta_list.categories().contains(&category) => {
+                Section::All
+            }
+            section => section,
+        };
+        self.search_visible = !state.search_text.is_empty();
+        self.set_search_text(state.search_text);
+        // NOTE: States saved before aliases were told apart only have the codepoint, which
+        // previews the canonical icon.
+        self.previewed = state
+            .name
+            .as_deref()
+            .and_then(|name| self.meta_list.get_item_by_name(name))
+            .or_else(|| {
+                state
+                    .codepoint
+                    .and_then(|codepoint| self.meta_list.get_item(codepoint))
             })
-            .collect::<Vec<iced::Element<'_, MyMessage>>>();
+            .map(|item| self.shown_item(item).name().clone());
+        self.focused_item = self
+            .previewed
+            .as_deref()
+            .and_then(|name| self.visible_index(name));
+        self.scroll_offset = state.scroll_offset.max(0.0);
+        if self.search_query.is_empty() {
+            self.section_scroll_offsets
+                .insert(self.section.clone(), self.scroll_offset);
+        }
+        self.scroll_to_offset()
+    }
 
-        let all = {
-            let selected = self.selected_category.is_none() || searching;
-            let text = iced::widget::text("All")
-                .font(self.selected_font(selected))
-                .size(FONT_SIZE_STANDARD);
-            let icon = {
-                let icon = iced::widget::text(char::from_u32(CODEPOINT_GRID).unwrap())
-                    .font(self.icons_font())
-                    .size(ICON_FONT_SIZE_TINY);
-                if !selected {
-                    icon.style(iced::theme::Text::Color(self.theme().palette().primary))
-                } else {
-                    icon
-                }
-            };
+    /// The state to save on exit. If the last session's state hasn't been restored yet (e.g.
+    /// because the icon set failed to load), it is kept, except for the window's geometry.
+    fn session_state(&self) -> SessionState {
+        let state = match &self.pending_state {
+            Some(state) => state.clone(),
+            None => SessionState {
+                grid_view: self.grid_view,
+                collapse_aliases: self.collapse_aliases,
+                section: self.section.clone().into(),
+                name: self.previewed.clone(),
+                codepoint: self.previewed_item().map(|item| item.codepoint()),
+                search_text: self.search_text.clone(),
+                scroll_offset: self.scroll_offset,
+                ..Default::default()
+            },
+        };
+        SessionState {
+            window_size: Some(self.window_size),
+            window_position: self.window_position.or(state.window_position),
+            ..state
+        }
+    }
+
+    /// The outline of the glyph of `codepoint` in the browsed font.
+    fn glyph_outline(&self, codepoint: u32) -> Result<GlyphOutline, GlyphError> {
+        let font_bytes = self.icons_font_bytes.as_deref().unwrap_or(ICONS_FONT_BYTES);
+        GlyphOutline::from_font(font_bytes, codepoint)
+    }
+
+    fn view_load_error<'a>(
+        &'a self,
+        paths: &'a IconSetPaths,
+        error: &'a LoadError,
+    ) -> iced::Element<'a, MyMessage> {
+        let icon = iced::widget::text(char::from_u32(CODEPOINT_ERROR).unwrap())
+            .style(iced::theme::Text::Color(self.theme().palette().danger))
+            .font(self.icons_font())
+            .size(ICON_FONT_SIZE_BIG);
+        let title = iced::widget::text("The icon set could not be loaded")
+            .font(self.bold_font())
+            .size(FONT_SIZE_LARGE);
+        let message = iced::widget::text(error.to_string())
+            .font(self.font())
+            .size(FONT_SIZE_STANDARD)
+            .horizontal_alignment(iced::alignment::Horizontal::Center);
+        let files = iced::widget::column!(
+            iced::widget::text(format!("Metadata: {}", IconSetPaths::describe(&paths.meta)))
+                .font(self.font())
+                .size(FONT_SIZE_SMALL),
+            iced::widget::text(format!("Font: {}", IconSetPaths::describe(&paths.font)))
+                .font(self.font())
+                .size(FONT_SIZE_SMALL),
+        )
+        .align_items(iced::Alignment::Center)
+        .spacing(SPACING_SMALL);
+
+        let button = |label: &'static str, message: MyMessage| {
             iced::widget::button(
-                iced::widget::row!(icon, text)
-                    .align_items(iced::Alignment::Center)
-                    .spacing(SPACING_NORMAL),
+                iced::widget::text(label)
+                    .font(self.bold_font())
+                    .size(FONT_SIZE_STANDARD),
             )
-            .on_press(MyMessage::Category(None))
-            .style(RowButton::new(selected).into())
-            .width(iced::Length::Fill)
-            .into()
+            .on_press(message)
+            .style(ToolbarButton::default().into())
+        };
+        let mut buttons = iced::widget::row!(
+            button("Retry", MyMessage::LoadIconSet(paths.clone())),
+            button("Open…", MyMessage::Open),
+            button(
+                "Use bundled icons",
+                MyMessage::LoadIconSet(Default::default())
+            ),
+        )
+        .spacing(SPACING_NORMAL);
+        if self.icon_set_loaded {
+            buttons = buttons.push(button("Dismiss", MyMessage::DismissLoadError));
+        }
+
+        iced::widget::container(
+            iced::widget::column!(icon, title, message, files, buttons)
+                .align_items(iced::Alignment::Center)
+                .spacing(SPACING_LARGE),
+        )
+        .width(iced::Length::Fill)
+        .height(iced::Length::Fill)
+        .center_x()
+        .center_y()
+        .padding(SPACING_EXTRA_LARGE)
+        .into()
+    }
+
+    fn view_sidebar_button(
+        &self,
+        section: Section,
+        icon: iced::widget::Text<'static>,
+    ) -> iced::Element<'_, MyMessage> {
+        // NOTE: Searches cover all icons, regardless of the selected section.
+        let searching: bool = !self.search_query.is_empty();
+        let selected = if searching {
+            section == Section::All
+        } else {
+            section == self.section
+        };
+        let text = iced::widget::text(section.label())
+            .font(self.selected_font(selected))
+            .size(FONT_SIZE_STANDARD);
+        let icon = if !selected {
+            icon.style(iced::theme::Text::Color(self.theme().palette().primary))
+        } else {
+            icon
         };
+        iced::widget::button(
+            iced::widget::row!(icon.size(ICON_FONT_SIZE_TINY), text)
+                .align_items(iced::Alignment::Center)
+                .spacing(SPACING_NORMAL),
+        )
+        .on_press(MyMessage::Section(section))
+        .style(
+            RowButton::new(selected)
+                .focused(self.focus == FocusArea::Sidebar && selected)
+                .into(),
+        )
+        .width(iced::Length::Fill)
+        .into()
+    }
+
+    fn view_sidebar(&self) -> iced::Element<'_, MyMessage> {
+        let all =
+            iced::widget::text(char::from_u32(CODEPOINT_GRID).unwrap()).font(self.icons_font());
+        let favorites =
+            iced::widget::text(char::from_u32(CODEPOINT_STAR).unwrap()).font(self.icons_font());
+        let mut categories = vec![
+            self.view_sidebar_button(Section::All, all),
+            self.view_sidebar_button(Section::Favorites, favorites),
+        ];
+        categories.extend(
+            self.meta_list
+                .categories()
+                .iter()
+                .zip(self.meta_list.category_codepoints())
+                .map(|(name, category_codepoint)| {
+                    let icon = iced::widget::text(char::from_u32(*category_codepoint).unwrap())
+                        .font(self.browsed_icons_font());
+                    self.view_sidebar_button(Section::Category(name.clone()), icon)
+                }),
+        );
 
-        categories.insert(0, all);
+        categories.push(self.view_sidebar_collections());
+        categories.push(self.view_sidebar_usage());
+        categories.push(self.view_sidebar_comparison());
 
         let column = iced::widget::column(categories)
             .height(iced::Length::Fill)
@@ -261,90 +1106,412 @@ impl MyApp {
             iced::widget::scrollable::Direction::Vertical(Default::default()),
         );
 
-        let heading = iced::widget::container(
-            iced::widget::text("Categories")
-                .style(iced::theme::Text::Color(iced::Color {
-                    a: 0.25,
-                    ..self.theme().palette().text
-                }))
-                .size(FONT_SIZE_SMALLER)
-                .font(self.bold_font()),
-        )
-        .padding([SPACING_NORMAL, 0, SPACING_NORMAL, 0]);
+        let heading = self.view_sidebar_heading("Categories");
+        let heading =
+            iced::widget::container(heading).padding([SPACING_NORMAL, 0, SPACING_NORMAL, 0]);
 
-        iced::widget::container(iced::widget::column!(heading, scrollable))
-            .style(CustomContainer::sidebar().move_to_style())
-            .height(iced::Length::Fill)
-            .padding([SPACING_NORMAL, SPACING_LARGE])
-            .into()
+        iced::widget::container(iced::widget::column!(
+            heading,
+            self.view_sidebar_config_errors(),
+            scrollable
+        ))
+        .style(CustomContainer::sidebar().move_to_style())
+        .height(iced::Length::Fill)
+        .padding([SPACING_NORMAL, SPACING_LARGE])
+        .into()
     }
 
-    fn view_toolbar_view_mode(&self) -> iced::Element<'_, MyMessage> {
-        let list_view = {
-            let icon = iced::widget::text(char::from_u32(CODEPOINT_LIST).unwrap())
+    /// The load and save failures of the favorites, collections and window state (with a button
+    /// dismissing them), if any.
+    fn view_sidebar_config_errors(&self) -> iced::Element<'_, MyMessage> {
+        if self.config_errors.is_empty() {
+            return iced::widget::column(vec![]).into();
+        }
+        let dismiss_button = {
+            let icon = iced::widget::text(char::from_u32(CODEPOINT_CLOSE).unwrap())
                 .font(self.icons_font())
-                .size(ICON_FONT_SIZE_TOOLBAR);
-            let button = iced::widget::button(icon).style(ToolbarButton::text().into());
-            if self.grid_view {
-                button.on_press(MyMessage::GridViewState(false))
-            } else {
-                button
-            }
+                .size(ICON_FONT_SIZE_TINY);
+            iced::widget::button(icon)
+                .on_press(MyMessage::DismissConfigErrors)
+                .style(ToolbarButton::text().into())
+                .padding(0)
         };
+        let errors = self
+            .config_errors
+            .iter()
+            .map(|error| {
+                iced::widget::text(error)
+                    .font(self.font())
+                    .size(FONT_SIZE_SMALLER)
+                    .style(iced::theme::Text::Color(self.theme().palette().danger))
+                    .into()
+            })
+            .collect::<Vec<iced::Element<'_, MyMessage>>>();
+        iced::widget::row!(
+            iced::widget::column(errors)
+                .spacing(SPACING_SMALL)
+                .width(iced::Length::Fill),
+            dismiss_button
+        )
+        .spacing(SPACING_SMALL)
+        .width(iced::Length::Fixed(SIDEBAR_WIDTH))
+        .padding([0, 0, SPACING_NORMAL, 0])
+        .into()
+    }
 
-        let grid_view = {
-            let icon = iced::widget::text(char::from_u32(CODEPOINT_GRID).unwrap())
+    fn view_sidebar_heading(&self, label: &'static str) -> iced::widget::Text<'static> {
+        iced::widget::text(label)
+            .style(iced::theme::Text::Color(iced::Color {
+                a: 0.25,
+                ..self.theme().palette().text
+            }))
+            .size(FONT_SIZE_SMALLER)
+            .font(self.bold_font())
+    }
+
+    /// The "Collections" heading (with the import button), the collections, and the text input
+    /// creating new ones.
+    fn view_sidebar_collections(&self) -> iced::Element<'_, MyMessage> {
+        let import_button = {
+            let icon = iced::widget::text(char::from_u32(CODEPOINT_IMPORT).unwrap())
                 .font(self.icons_font())
-                .size(ICON_FONT_SIZE_TOOLBAR);
-            let button = iced::widget::button(icon).style(ToolbarButton::text().into());
-            if !self.grid_view {
-                button.on_press(MyMessage::GridViewState(true))
-            } else {
-                button
-            }
+                .size(ICON_FONT_SIZE_TINY);
+            iced::widget::button(icon)
+                .on_press(MyMessage::ImportCollection)
+                .style(ToolbarButton::text().into())
+                .padding(0)
         };
+        let heading = iced::widget::row!(
+            self.view_sidebar_heading("Collections"),
+            iced::widget::container("").width(iced::Length::Fill),
+            import_button,
+        )
+        .align_items(iced::Alignment::Center);
 
-        iced::widget::row!(grid_view, list_view)
-            .align_items(iced::Alignment::Center)
-            .into()
+        let heading =
+            iced::widget::container(heading).padding([SPACING_LARGE, 0, SPACING_NORMAL, 0]);
+        let mut column = iced::widget::column!(heading);
+        for collection in self.collections.collections() {
+            let icon = iced::widget::text(char::from_u32(CODEPOINT_COLLECTION).unwrap())
+                .font(self.icons_font());
+            column = column.push(
+                self.view_sidebar_button(Section::Collection(collection.name().clone()), icon),
+            );
+        }
+
+        let text_input = iced::widget::text_input("New collection", &self.new_collection_name)
+            .on_input(MyMessage::NewCollectionName)
+            .on_submit(MyMessage::CreateCollection)
+            .font(self.font())
+            .size(FONT_SIZE_STANDARD);
+        column = column.push(iced::widget::container(text_input).padding([
+            SPACING_SMALL,
+            0,
+            SPACING_NORMAL,
+            0,
+        ]));
+        if let Some(error) = &self.collection_error {
+            column = column.push(
+                iced::widget::text(error)
+                    .font(self.font())
+                    .size(FONT_SIZE_SMALLER)
+                    .style(iced::theme::Text::Color(self.theme().palette().danger)),
+            );
+        }
+        column.into()
     }
 
-    fn view_toolbar_search(&self) -> iced::Element<'_, MyMessage> {
-        if !self.search_visible {
-            let icon = iced::widget::text(char::from_u32(CODEPOINT_SEARCH).unwrap())
+    /// The actions on the multi-selection (Ctrl-click) or, without one, on the selected
+    /// collection.
+    fn view_toolbar_collection_actions(&self) -> iced::Element<'_, MyMessage> {
+        let icon_button = |codepoint: u32, message: MyMessage| {
+            let icon = iced::widget::text(char::from_u32(codepoint).unwrap())
                 .font(self.icons_font())
                 .size(ICON_FONT_SIZE_TOOLBAR);
-            let button = iced::widget::button(icon)
-                .on_press(MyMessage::SearchVisibleState(true))
-                .style(ToolbarButton::text().into());
-            iced::widget::row!(button)
+            iced::widget::button(icon)
+                .on_press(message)
+                .style(ToolbarButton::text().into())
+        };
+
+        if !self.selection.is_empty() {
+            let count = iced::widget::text(format!("{} selected", self.selection.len()))
+                .font(self.font())
+                .size(FONT_SIZE_SMALL);
+            let selection = self.selection.iter().cloned().collect::<Vec<String>>();
+            let collections = self
+                .collections
+                .collections()
+                .iter()
+                .map(|collection| collection.name().clone())
+                .collect::<Vec<String>>();
+            let pick_list = iced::widget::pick_list(collections, None, move |name| {
+                MyMessage::AddToCollection(name, selection.clone())
+            })
+            .placeholder("Add to collection…")
+            .font(self.font())
+            .text_size(FONT_SIZE_SMALL);
+            return iced::widget::row!(
+                self.view_export_status(),
+                count,
+                pick_list,
+                icon_button(
+                    CODEPOINT_SAVE,
+                    MyMessage::ExportPng(self.selection.iter().cloned().collect())
+                ),
+                self.view_toolbar_rust_module(ExportSource::Selection),
+                icon_button(
+                    CODEPOINT_FONT_DOWNLOAD,
+                    MyMessage::BuildSubsetFont(ExportSource::Selection)
+                ),
+                icon_button(CODEPOINT_CLOSE, MyMessage::ClearSelection)
+            )
+            .spacing(SPACING_NORMAL)
+            .align_items(iced::Alignment::Center)
+            .into();
+        }
+
+        match &self.section {
+            Section::Collection(name) if self.search_query.is_empty() => iced::widget::row!(
+                self.view_export_status(),
+                self.view_toolbar_rust_module(ExportSource::Collection(name.clone())),
+                icon_button(
+                    CODEPOINT_FONT_DOWNLOAD,
+                    MyMessage::BuildSubsetFont(ExportSource::Collection(name.clone()))
+                ),
+                icon_button(CODEPOINT_EXPORT, MyMessage::ExportCollection(name.clone())),
+                icon_button(CODEPOINT_DELETE, MyMessage::DeleteCollection(name.clone())),
+            )
+            .spacing(SPACING_NORMAL)
+            .align_items(iced::Alignment::Center)
+            .into(),
+            Section::All if self.search_query.is_empty() => iced::widget::row!(
+                self.view_export_status(),
+                self.view_toolbar_rust_module(ExportSource::Catalog),
+            )
+            .spacing(SPACING_NORMAL)
+            .align_items(iced::Alignment::Center)
+            .into(),
+            _ => iced::widget::row(vec![]).into(),
+        }
+    }
+
+    /// The icons of `source` (all of them, for the catalog).
+    fn export_source_items(&self, source: &ExportSource) -> Vec<&MaterialFontMeta> {
+        let names = match source {
+            ExportSource::Selection => self.selection.iter().collect::<Vec<&String>>(),
+            ExportSource::Collection(name) => self
+                .collections
+                .get(name)
+                .map(|collection| collection.icons().iter().collect())
+                .unwrap_or_default(),
+            ExportSource::Catalog => return self.meta_list.items().iter().collect(),
+        };
+        names
+            .into_iter()
+            .filter_map(|name| self.meta_list.get_item_by_name(name))
+            .collect()
+    }
+
+    /// A picker generating a Rust module declaring the codepoints of the icons of `source`.
+    fn view_toolbar_rust_module(&self, source: ExportSource) -> iced::Element<'_, MyMessage> {
+        iced::widget::pick_list(&RustModuleOptions::ALL[..], None, move |options| {
+            MyMessage::ExportRustModule(source.clone(), options)
+        })
+        .placeholder("Export as Rust…")
+        .font(self.font())
+        .text_size(FONT_SIZE_SMALL)
+        .into()
+    }
+
+    fn view_toolbar_open(&self) -> iced::Element<'_, MyMessage> {
+        let icon = iced::widget::text(char::from_u32(CODEPOINT_FOLDER_OPEN).unwrap())
+            .font(self.icons_font())
+            .size(ICON_FONT_SIZE_TOOLBAR);
+        iced::widget::button(icon)
+            .on_press(MyMessage::Open)
+            .style(ToolbarButton::text().into())
+            .into()
+    }
+
+    /// A button showing (or hiding) the diagnostics panel, in the danger color when the metadata
+    /// doesn't match the font.
+    fn view_toolbar_diagnostics(&self) -> iced::Element<'_, MyMessage> {
+        let valid = match &self.validation {
+            Some(Ok(report)) => report.is_valid(),
+            Some(Err(_)) => false,
+            None => true,
+        };
+        let icon = iced::widget::text(char::from_u32(CODEPOINT_FACT_CHECK).unwrap())
+            .font(self.icons_font())
+            .size(ICON_FONT_SIZE_TOOLBAR);
+        let icon = if valid {
+            icon
         } else {
-            let text_input = iced::widget::text_input("Search", &self.search_text)
-                .on_input(MyMessage::Search)
-                .width(iced::Length::Fixed(200.0))
-                .id(iced::widget::text_input::Id::new(SEARCH_TEXT_INPUT_ID));
-            let text_input =
-                text_input_wrapper::my_text_input_wrapper(text_input, MyMessage::SearchFocusState);
-            let icon = iced::widget::text(char::from_u32(CODEPOINT_CLOSE).unwrap())
+            icon.style(iced::theme::Text::Color(self.theme().palette().danger))
+        };
+        iced::widget::button(icon)
+            .on_press(MyMessage::DiagnosticsVisibleState(
+                !self.diagnostics_visible,
+            ))
+            .style(ToolbarButton::text().into())
+            .into()
+    }
+
+    /// The problems found by checking the metadata against the font (see
+    /// [`validation::validate`]).
+    fn view_diagnostics(&self) -> iced::Element<'_, MyMessage> {
+        let small_text = |content: String| {
+            iced::widget::text(content)
+                .font(self.font())
+                .size(FONT_SIZE_SMALL)
+        };
+        let summary = match &self.validation {
+            None => String::from("The icon set isn't loaded"),
+            Some(Err(e)) => format!("The font could not be checked: {}", e),
+            Some(Ok(report)) if report.problems().is_empty() => format!(
+                "The {} icons match the glyphs of the font",
+                self.meta_list.items().len()
+            ),
+            Some(Ok(report)) => format!(
+                "{} error(s), {} warning(s)",
+                report.count(Severity::Error),
+                report.count(Severity::Warning)
+            ),
+        };
+        let heading = iced::widget::row!(
+            iced::widget::text("Diagnostics")
+                .font(self.bold_font())
+                .size(FONT_SIZE_LARGE),
+            small_text(summary),
+        )
+        .spacing(SPACING_LARGE)
+        .align_items(iced::Alignment::Center);
+
+        let mut column = iced::widget::column!(heading).spacing(SPACING_SMALL);
+        if let Some(Ok(report)) = &self.validation {
+            for problem in report.problems() {
+                let (codepoint, color) = match problem.severity() {
+                    Severity::Error => (CODEPOINT_ERROR, self.theme().palette().danger),
+                    Severity::Warning => (CODEPOINT_WARNING, self.theme().palette().text),
+                };
+                let icon = iced::widget::text(char::from_u32(codepoint).unwrap())
+                    .font(self.icons_font())
+                    .size(ICON_FONT_SIZE_TINY)
+                    .style(iced::theme::Text::Color(color));
+                column = column.push(
+                    iced::widget::row!(icon, small_text(problem.to_string()))
+                        .align_items(iced::Alignment::Center)
+                        .spacing(SPACING_SMALL),
+                );
+            }
+        }
+
+        iced::widget::container(iced::widget::scrollable(
+            iced::widget::container(column)
+                .width(iced::Length::Fill)
+                .padding([SPACING_NORMAL, SPACING_LARGE]),
+        ))
+        .style(CustomContainer::toolbar().move_to_style())
+        .width(iced::Length::Fill)
+        .height(iced::Length::Fixed(PANEL_HEIGHT))
+        .into()
+    }
+
+    /// A button collapsing the icons sharing a codepoint into their canonical icon (or showing
+    /// them all again), for icon sets having aliases.
+    fn view_toolbar_aliases(&self) -> iced::Element<'_, MyMessage> {
+        if !self.meta_list.has_aliases() {
+            return iced::widget::row(vec![]).into();
+        }
+        let icon = iced::widget::text(char::from_u32(CODEPOINT_LAYERS).unwrap())
+            .font(self.icons_font())
+            .size(ICON_FONT_SIZE_TOOLBAR);
+        let icon = if self.collapse_aliases {
+            icon.style(iced::theme::Text::Color(self.theme().palette().primary))
+        } else {
+            icon
+        };
+        iced::widget::button(icon)
+            .on_press(MyMessage::CollapseAliasesState(!self.collapse_aliases))
+            .style(ToolbarButton::text().into())
+            .into()
+    }
+
+    fn view_toolbar_view_mode(&self) -> iced::Element<'_, MyMessage> {
+        let list_view = {
+            let icon = iced::widget::text(char::from_u32(CODEPOINT_LIST).unwrap())
+                .font(self.icons_font())
+                .size(ICON_FONT_SIZE_TOOLBAR);
+            let button = iced::widget::button(icon).style(ToolbarButton::text().into());
+            if self.grid_view {
+                button.on_press(MyMessage::GridViewState(false))
+            } else {
+                button
+            }
+        };
+
+        let grid_view = {
+            let icon = iced::widget::text(char::from_u32(CODEPOINT_GRID).unwrap())
+                .font(self.icons_font())
+                .size(ICON_FONT_SIZE_TOOLBAR);
+            let button = iced::widget::button(icon).style(ToolbarButton::text().into());
+            if !self.grid_view {
+                button.on_press(MyMessage::GridViewState(true))
+            } else {
+                button
+            }
+        };
+
+        iced::widget::row!(grid_view, list_view)
+            .align_items(iced::Alignment::Center)
+            .into()
+    }
+
+    fn view_toolbar_search(&self) -> iced::Element<'_, MyMessage> {
+        if !self.search_visible {
+            let icon = iced::widget::text(char::from_u32(CODEPOINT_SEARCH).unwrap())
                 .font(self.icons_font())
                 .size(ICON_FONT_SIZE_TOOLBAR);
             let button = iced::widget::button(icon)
-                .on_press(MyMessage::SearchVisibleState(false))
+                .on_press(MyMessage::SearchVisibleState(true))
                 .style(ToolbarButton::text().into());
-            iced::widget::row!(text_input, button)
+            return iced::widget::row!(button)
+                .align_items(iced::Alignment::Center)
+                .into();
+        }
+
+        let text_input = iced::widget::text_input("Search", &self.search_text)
+            .on_input(MyMessage::Search)
+            .width(iced::Length::Fixed(SEARCH_TEXT_INPUT_WIDTH))
+            .id(iced::widget::text_input::Id::new(SEARCH_TEXT_INPUT_ID));
+        let text_input =
+            text_input_wrapper::my_text_input_wrapper(text_input, MyMessage::SearchFocusState);
+        let icon = iced::widget::text(char::from_u32(CODEPOINT_CLOSE).unwrap())
+            .font(self.icons_font())
+            .size(ICON_FONT_SIZE_TOOLBAR);
+        let button = iced::widget::button(icon)
+            .on_press(MyMessage::SearchVisibleState(false))
+            .style(ToolbarButton::text().into());
+        let row = iced::widget::row!(text_input, button).align_items(iced::Alignment::Center);
+
+        match &self.search_error {
+            Some(error) => {
+                let error = iced::widget::text(error.to_string())
+                    .font(self.font())
+                    .size(FONT_SIZE_SMALLER)
+                    .width(iced::Length::Fixed(SEARCH_TEXT_INPUT_WIDTH))
+                    .style(iced::theme::Text::Color(self.theme().palette().danger));
+                iced::widget::column!(row, error)
+                    .spacing(SPACING_SMALL)
+                    .into()
+            }
+            None => row.into(),
         }
-        .align_items(iced::Alignment::Center)
-        .into()
     }
 
     fn view_toolbar_active_category_and_count(&self) -> iced::Element<'_, MyMessage> {
-        let active_categor = if self.search_text.is_empty() {
-            iced::widget::text(
-                self.selected_category
-                    .as_ref()
-                    .map(|v| capitalized_string(v))
-                    .unwrap_or(String::from("All")),
-            )
+        let active_categor = if self.search_query.is_empty() {
+            iced::widget::text(self.section.label())
         } else {
             iced::widget::text("Search All")
         }
@@ -360,6 +1527,10 @@ impl MyApp {
         let row = iced::widget::row!(
             self.view_toolbar_active_category_and_count(),
             iced::widget::container("").width(iced::Length::Fill),
+            self.view_toolbar_collection_actions(),
+            self.view_toolbar_open(),
+            self.view_toolbar_diagnostics(),
+            self.view_toolbar_aliases(),
             self.view_toolbar_view_mode(),
             self.view_toolbar_search(),
         )
@@ -383,18 +1554,14 @@ impl MyApp {
             self.view_icon_list()
         };
 
-        if let Some(codepoint) = self.codepoint {
-            let item = match self.meta_list.get_item(codepoint) {
-                Some(item) => item,
-                None => panic!("This should not be possible"),
-            };
-
-            let preview = iced::widget::container(self.view_item_preview(item))
-                .style(CustomContainer::preview().move_to_style())
-                .width(iced::Length::Fill)
-                .height(iced::Length::Fill)
-                .center_x()
-                .center_y();
+        if let Some(item) = self.previewed_item() {
+            let preview =
+                iced::widget::container(iced::widget::scrollable(self.view_item_preview(item)))
+                    .style(CustomContainer::preview().move_to_style())
+                    .width(iced::Length::Fill)
+                    .height(iced::Length::Fill)
+                    .center_x()
+                    .center_y();
 
             iced::widget::row!(
                 icon_list_or_grid,
@@ -407,6 +1574,27 @@ impl MyApp {
         .into()
     }
 
+    /// The height of the grid or list's viewport, estimated from the window's (as with
+    /// virtualization).
+    fn viewport_height(&self) -> f32 {
+        let panels = [self.diagnostics_visible, self.diff_panel_kind().is_some()]
+            .iter()
+            .filter(|visible| **visible)
+            .count();
+        self.window_size.1 as f32 - TOOLBAR_HEIGHT - panels as f32 * PANEL_HEIGHT
+    }
+
+    /// The bucket whose differences are listed above the grid, when one is selected (and no
+    /// search is in progress, since searches cover all icons).
+    fn diff_panel_kind(&self) -> Option<DiffKind> {
+        match &self.section {
+            Section::Diff(kind) if self.search_query.is_empty() && self.comparison.is_some() => {
+                Some(*kind)
+            }
+            _ => None,
+        }
+    }
+
     fn get_items_per_row(&self) -> usize {
         let value: usize = match self.window_size.0 {
             1300u32..=u32::MAX => 8usize,
@@ -417,7 +1605,7 @@ impl MyApp {
             _ => 3usize,
         };
 
-        if self.codepoint.is_some() {
+        if self.previewed.is_some() {
             let value = value / 2;
             if value > 1 {
                 value
@@ -429,104 +1617,472 @@ impl MyApp {
         }
     }
 
-    fn view_icon_grid(&self) -> iced::Element<'_, MyMessage> {
-        let items = self
-            .meta_list
-            .items()
+    /// Re-runs the query for the selected section and search, which is only needed when one of
+    /// them (or the icon set, or the favorites) changes, rather than on every `view`.
+    fn refresh_visible_items(&mut self) {
+        let query = match &self.section {
+            Section::All => Query::new(),
+            Section::Favorites => {
+                Query::new().names(Some(self.favorites.names().iter().cloned().collect()))
+            }
+            Section::Category(category) => Query::new().category(Some(category.clone())),
+            Section::Collection(name) => Query::new().names(Some(
+                self.collections
+                    .get(name)
+                    .map(|collection| collection.icons().iter().cloned().collect())
+                    .unwrap_or_default(),
+            )),
+            Section::Diff(kind) => Query::new().names(Some(
+                self.comparison
+                    .iter()
+                    .flat_map(|comparison| comparison.diff.bucket(*kind))
+                    .filter_map(|entry| entry.new_name.clone())
+                    .collect(),
+            )),
+            Section::Usage => Query::new().names(Some(
+                self.project_usage
+                    .iter()
+                    .flat_map(|usage| usage.used_icons(&self.meta_list))
+                    .map(|(item, _)| item.name().clone())
+                    .collect(),
+            )),
+        };
+        self.visible_items = query
+            .search(self.search_query.clone())
+            .collapse_aliases(self.collapse_aliases)
+            .run_positions(&self.meta_list);
+        self.focused_item = None;
+    }
+
+    /// Scrolls the grid or list to where it was when the selected category was last browsed, or
+    /// to the top while searching (the best matches come first).
+    fn restore_scroll_offset(&mut self) -> iced::Command<MyMessage> {
+        self.scroll_offset = if self.search_query.is_empty() {
+            self.section_scroll_offsets
+                .get(&self.section)
+                .copied()
+                .unwrap_or(0.0)
+        } else {
+            0.0
+        };
+        self.scroll_to_offset()
+    }
+
+    fn scroll_to_offset(&self) -> iced::Command<MyMessage> {
+        iced::widget::scrollable::scroll_to(
+            iced::widget::scrollable::Id::new(SCROLLABLE_ICON_LIST_ID),
+            iced::widget::scrollable::AbsoluteOffset {
+                x: 0.0,
+                y: self.scroll_offset,
+            },
+        )
+    }
+
+    /// The distance from the top of the grid or list content to the top of the item at `index`
+    /// in `visible_items`, given the current layout.
+    fn item_top(&self, index: usize) -> f32 {
+        if self.grid_view {
+            let row = index / self.get_items_per_row();
+            SPACING_LARGE as f32 + row as f32 * (ITEM_HEIGHT_MEDIUM + SPACING_LARGE as f32)
+        } else {
+            SPACING_NORMAL as f32 + index as f32 * (ITEM_HEIGHT_SMALL + SPACING_SMALL as f32)
+        }
+    }
+
+    /// The previewed icon, found by name since aliases share their codepoint.
+    fn previewed_item(&self) -> Option<&MaterialFontMeta> {
+        self.previewed
+            .as_deref()
+            .and_then(|name| self.meta_list.get_item_by_name(name))
+    }
+
+    /// The icon whose tile shows `item`, i.e. its canonical icon when aliases are collapsed.
+    fn shown_item<'a>(&'a self, item: &'a MaterialFontMeta) -> &'a MaterialFontMeta {
+        if self.collapse_aliases {
+            self.meta_list.get_item(item.codepoint()).unwrap_or(item)
+        } else {
+            item
+        }
+    }
+
+    /// The position of the item named `name`: its index in `visible_items` and its distance from
+    /// the top of the viewport. To be taken before a layout change (e.g. the preview opening,
+    /// which halves the items per row) and passed to `scroll_to_anchor` after it.
+    fn item_anchor(&self, name: &str) -> Option<(usize, f32)> {
+        let index = self.visible_index(name)?;
+        Some((index, self.item_top(index) - self.scroll_offset))
+    }
+
+    /// The index in `visible_items` of the item named `name`.
+    fn visible_index(&self, name: &str) -> Option<usize> {
+        self.visible_items
             .iter()
-            .filter(|item| self.filter_item(item))
-            .map(|item| self.view_item_preview_medium(item))
-            .collect::<Vec<iced::Element<'_, MyMessage>>>();
+            .position(|position| self.meta_list.items()[*position].name() == name)
+    }
 
-        let items_per_row: usize = self.get_items_per_row();
+    fn visible_name(&self, index: usize) -> &String {
+        self.meta_list.items()[self.visible_items[index]].name()
+    }
+
+    /// Whether `item` is previewed or part of the multi-selection.
+    fn is_item_selected(&self, item: &MaterialFontMeta) -> bool {
+        self.previewed.as_ref() == Some(item.name()) || self.selection.contains(item.name())
+    }
+
+    fn is_item_focused(&self, item: &MaterialFontMeta) -> bool {
+        self.focus == FocusArea::Items
+            && self.focused_item.map(|index| self.visible_name(index)) == Some(item.name())
+    }
+
+    /// Handles the navigation keys (arrows, Home/End, PageUp/PageDown, Enter and Tab) for the
+    /// focused area, returning `None` for other keys.
+    fn navigate(
+        &mut self,
+        key_code: iced::keyboard::KeyCode,
+        modifiers: iced::keyboard::Modifiers,
+    ) -> Option<iced::Command<MyMessage>> {
+        if modifiers.control() || modifiers.alt() || modifiers.logo() {
+            return None;
+        }
+        if key_code == iced::keyboard::KeyCode::Tab {
+            self.cycle_focus(modifiers.shift());
+            return Some(iced::Command::none());
+        }
+        match self.focus {
+            FocusArea::Sidebar => self.navigate_sidebar(key_code),
+            FocusArea::Items | FocusArea::Preview => self.navigate_items(key_code),
+        }
+    }
 
-        let mut column = iced::widget::column(vec![]);
-        let mut row = iced::widget::row(vec![]);
-        let mut last_index: usize = 0;
-        for (index, item) in items.into_iter().enumerate() {
-            last_index = index % items_per_row;
-            if last_index != 0 {
-                row = row.push(item);
+    fn cycle_focus(&mut self, backwards: bool) {
+        let mut areas = vec![FocusArea::Sidebar, FocusArea::Items];
+        if self.previewed.is_some() {
+            areas.push(FocusArea::Preview);
+        }
+        // NOTE: The preview may have been closed while focused, in which case the items are
+        // considered focused instead.
+        let current = areas
+            .iter()
+            .position(|area| *area == self.focus)
+            .unwrap_or(1);
+        let next = if backwards {
+            (current + areas.len() - 1) % areas.len()
+        } else {
+            (current + 1) % areas.len()
+        };
+        self.focus = areas[next];
+    }
+
+    fn navigate_sidebar(
+        &mut self,
+        key_code: iced::keyboard::KeyCode,
+    ) -> Option<iced::Command<MyMessage>> {
+        use iced::keyboard::KeyCode;
+
+        let sections = self.sidebar_sections();
+        let current = sections
+            .iter()
+            .position(|section| *section == self.section)
+            .unwrap_or(0);
+        let last = sections.len() - 1;
+        let target = match key_code {
+            KeyCode::Up => current.saturating_sub(1),
+            KeyCode::Down => (current + 1).min(last),
+            KeyCode::Home | KeyCode::PageUp => 0,
+            KeyCode::End | KeyCode::PageDown => last,
+            KeyCode::Enter | KeyCode::Right => {
+                self.focus = FocusArea::Items;
+                return Some(iced::Command::none());
+            }
+            _ => return None,
+        };
+        if target == current {
+            return Some(iced::Command::none());
+        }
+        let section = sections[target].clone();
+        Some(self.update(MyMessage::Section(section)))
+    }
+
+    /// The sections of the sidebar, in display order.
+    fn sidebar_sections(&self) -> Vec<Section> {
+        let mut sections = vec![Section::All, Section::Favorites];
+        sections.extend(
+            self.meta_list
+                .categories()
+                .iter()
+                .cloned()
+                .map(Section::Category),
+        );
+        sections.extend(
+            self.collections
+                .collections()
+                .iter()
+                .map(|collection| Section::Collection(collection.name().clone())),
+        );
+        if self.project_usage.is_some() {
+            sections.push(Section::Usage);
+        }
+        if self.comparison.is_some() {
+            sections.extend(DiffKind::ALL.map(Section::Diff));
+        }
+        sections
+    }
+
+    fn navigate_items(
+        &mut self,
+        key_code: iced::keyboard::KeyCode,
+    ) -> Option<iced::Command<MyMessage>> {
+        use iced::keyboard::KeyCode;
+
+        let count = self.visible_items.len();
+        if count == 0 {
+            return None;
+        }
+        // NOTE: While the preview is focused, all arrows move to the previous or next item.
+        let in_grid = self.grid_view && self.focus == FocusArea::Items;
+        let step = if in_grid { self.get_items_per_row() } else { 1 };
+        let row_stride = if self.grid_view {
+            ITEM_HEIGHT_MEDIUM + SPACING_LARGE as f32
+        } else {
+            ITEM_HEIGHT_SMALL + SPACING_SMALL as f32
+        };
+        let page_rows = (self.viewport_height() / row_stride) as usize;
+        let page = page_rows.max(1)
+            * if self.grid_view {
+                self.get_items_per_row()
             } else {
-                last_index = 0;
-                let tmp = row.spacing(SPACING_LARGE);
-                column = column.push(
-                    tmp.width(iced::Length::Fill)
-                        .align_items(iced::Alignment::Center),
-                );
-                row = iced::widget::row(vec![item]);
+                1
+            };
+        let last = count - 1;
+
+        let current = self.focused_item.or_else(|| {
+            self.previewed
+                .as_deref()
+                .and_then(|name| self.visible_index(name))
+        });
+        let Some(current) = current else {
+            return match key_code {
+                KeyCode::Up
+                | KeyCode::Down
+                | KeyCode::Left
+                | KeyCode::Right
+                | KeyCode::Home
+                | KeyCode::PageUp => Some(self.focus_item(0)),
+                KeyCode::End | KeyCode::PageDown => Some(self.focus_item(last)),
+                _ => None,
+            };
+        };
+
+        let target = match key_code {
+            KeyCode::Left if in_grid || self.focus == FocusArea::Preview => {
+                current.saturating_sub(1)
+            }
+            KeyCode::Right if in_grid || self.focus == FocusArea::Preview => {
+                (current + 1).min(last)
             }
+            KeyCode::Up => current.checked_sub(step).unwrap_or(current),
+            // NOTE: Moving down from the row above a partial last row lands on its last item.
+            KeyCode::Down if current / step < last / step => (current + step).min(last),
+            KeyCode::Down => current,
+            KeyCode::PageUp => current.saturating_sub(page),
+            KeyCode::PageDown => (current + page).min(last),
+            KeyCode::Home => 0,
+            KeyCode::End => last,
+            KeyCode::Enter if self.focus == FocusArea::Preview => {
+                self.focus = FocusArea::Items;
+                return Some(iced::Command::none());
+            }
+            KeyCode::Enter => {
+                let name = self.visible_name(current).clone();
+                return Some(self.update(MyMessage::Preview(Some(name))));
+            }
+            _ => return None,
+        };
+        Some(self.focus_item(target))
+    }
+
+    /// Moves the keyboard focus to the item at `index` in `visible_items` and scrolls it into
+    /// view. While the preview is open, it follows the focused item.
+    fn focus_item(&mut self, index: usize) -> iced::Command<MyMessage> {
+        let anchor = (index, self.item_top(index) - self.scroll_offset);
+        self.focused_item = Some(index);
+        if self.previewed.is_some() {
+            self.previewed = Some(self.visible_name(index).clone());
         }
-        while last_index < items_per_row - 1 {
-            last_index += 1;
-            row = row.push(
-                iced::widget::container("")
-                    .width(iced::Length::Fill)
-                    .padding(SPACING_LARGE),
-            );
+        self.scroll_to_anchor(Some(anchor))
+    }
+
+    /// Scrolls so that the item of `anchor` is at the same distance from the top of the viewport
+    /// as before the layout change, or just inside the viewport if it wasn't (fully) visible.
+    fn scroll_to_anchor(&mut self, anchor: Option<(usize, f32)>) -> iced::Command<MyMessage> {
+        let Some((index, viewport_top)) = anchor else {
+            return iced::Command::none();
+        };
+        let item_height = if self.grid_view {
+            ITEM_HEIGHT_MEDIUM
+        } else {
+            ITEM_HEIGHT_SMALL
+        };
+        let viewport_height = self.viewport_height();
+        let viewport_top = viewport_top.clamp(0.0, (viewport_height - item_height).max(0.0));
+        self.scroll_offset = (self.item_top(index) - viewport_top).max(0.0);
+        if self.search_query.is_empty() {
+            self.section_scroll_offsets
+                .insert(self.section.clone(), self.scroll_offset);
         }
-        column = column.push(row.spacing(SPACING_LARGE));
-        column = column.padding([0, SPACING_LARGE]).spacing(SPACING_LARGE);
+        self.scroll_to_offset()
+    }
+
+    /// Updates the search text and, if it parses, the search query. While the text is invalid
+    /// (e.g. `pop:>` while typing `pop:>1000`), the previous query stays in effect and the error
+    /// is displayed under the search box.
+    fn set_search_text(&mut self, text: String) {
+        match SearchQuery::parse(&text) {
+            Ok(query) => {
+                self.search_query = query;
+                self.search_error = None;
+            }
+            Err(e) => self.search_error = Some(e),
+        }
+        self.search_text = text;
+        self.refresh_visible_items();
+    }
+
+    fn view_icon_grid(&self) -> iced::Element<'_, MyMessage> {
+        let items_per_row: usize = self.get_items_per_row();
+        let row_count = self.visible_items.len().div_ceil(items_per_row);
+        let rows =
+            self.visible_row_range(row_count, ITEM_HEIGHT_MEDIUM, SPACING_LARGE, SPACING_LARGE);
+        let name_max_chars = self.grid_name_max_chars();
+
+        let visible_rows = self.visible_items[rows.start * items_per_row..]
+            .chunks(items_per_row)
+            .take(rows.len())
+            .map(|positions| {
+                let mut row = iced::widget::row(vec![])
+                    .spacing(SPACING_LARGE)
+                    .width(iced::Length::Fill)
+                    .height(iced::Length::Fixed(ITEM_HEIGHT_MEDIUM))
+                    .align_items(iced::Alignment::Center);
+                for position in positions {
+                    let item = &self.meta_list.items()[*position];
+                    row = row.push(self.view_item_preview_medium(item, name_max_chars));
+                }
+                for _ in positions.len()..items_per_row {
+                    row = row.push(iced::widget::container("").width(iced::Length::Fill));
+                }
+                row.into()
+            })
+            .collect::<Vec<iced::Element<'_, MyMessage>>>();
+
+        let column = Self::view_virtualized_column(
+            visible_rows,
+            rows,
+            row_count,
+            ITEM_HEIGHT_MEDIUM,
+            SPACING_LARGE,
+        )
+        .padding(SPACING_LARGE);
 
         iced::widget::scrollable(
-            iced::widget::container(column)
-                .style(CustomContainer::default().move_to_style())
-                .padding([0, 0, SPACING_LARGE, 0]),
+            iced::widget::container(column).style(CustomContainer::default().move_to_style()),
         )
         .direction(iced::widget::scrollable::Direction::Vertical(
             Default::default(),
         ))
         .width(iced::Length::Fill)
         .id(iced::widget::scrollable::Id::new(SCROLLABLE_ICON_LIST_ID))
+        .on_scroll(MyMessage::Scrolled)
         .into()
     }
 
-    fn filter_item(&self, item: &MaterialFontMeta) -> bool {
-        if self.search_text.is_empty() {
-            if let Some(category) = &self.selected_category {
-                if !item.contains_category(category) {
-                    return false;
-                }
-            }
-            return true;
-        }
+    /// The rows (out of `row_count` rows of `row_height`, separated by `spacing`, below a
+    /// `padding`) intersecting the viewport, plus a few rows above and below it.
+    fn visible_row_range(
+        &self,
+        row_count: usize,
+        row_height: f32,
+        spacing: u16,
+        padding: u16,
+    ) -> std::ops::Range<usize> {
+        let row_stride = row_height + spacing as f32;
+        // NOTE: The viewport's height isn't known here, so the window's height (which is larger)
+        // is used instead.
+        let viewport_rows = (self.window_size.1 as f32 / row_stride).ceil() as usize + 1;
+        // NOTE: When the content shrinks (e.g. after a filter change), the scrollable clamps its
+        // offset to show the last rows, but `scroll_offset` is only updated on the next scroll.
+        let first = (((self.scroll_offset - padding as f32) / row_stride).max(0.0) as usize)
+            .min(row_count.saturating_sub(viewport_rows))
+            .saturating_sub(VIRTUALIZATION_OVERSCAN_ROWS);
+        let end = (first + viewport_rows + 2 * VIRTUALIZATION_OVERSCAN_ROWS).min(row_count);
+        first..end
+    }
 
-        if self.search_text.is_empty() {
-            return true;
+    /// A column made of the `visible_rows` (whose indices are `rows`) preceded and followed by
+    /// empty space taking the place of the other rows, so that the content has the same height
+    /// (and the scrollbar the same size) as if all `row_count` rows were laid out.
+    fn view_virtualized_column(
+        visible_rows: Vec<iced::Element<'_, MyMessage>>,
+        rows: std::ops::Range<usize>,
+        row_count: usize,
+        row_height: f32,
+        spacing: u16,
+    ) -> iced::widget::Column<'_, MyMessage> {
+        let row_stride = row_height + spacing as f32;
+        let mut column = iced::widget::column(vec![])
+            .width(iced::Length::Fill)
+            .spacing(spacing);
+        if rows.start > 0 {
+            column = column.push(iced::widget::Space::with_height(iced::Length::Fixed(
+                rows.start as f32 * row_stride - spacing as f32,
+            )));
         }
-
-        if item.name().starts_with(&self.search_text)
-            || item.contains_tag(&self.search_text)
-            || item.matches_hex_codepoint(&self.search_text)
-            || item.matches_codepoint(&self.search_text)
-        {
-            return true;
+        for row in visible_rows {
+            column = column.push(row);
         }
+        if rows.end < row_count {
+            column = column.push(iced::widget::Space::with_height(iced::Length::Fixed(
+                (row_count - rows.end) as f32 * row_stride - spacing as f32,
+            )));
+        }
+        column
+    }
 
-        false
+    /// How many characters of an icon name fit on a single line of a grid item, estimated from
+    /// the window's width since grid items must keep a fixed height.
+    fn grid_name_max_chars(&self) -> usize {
+        let items_per_row = self.get_items_per_row() as f32;
+        let mut content_width = self.window_size.0 as f32 - SIDEBAR_WIDTH;
+        if self.previewed.is_some() {
+            content_width /= 2.0;
+        }
+        let row_width = content_width - 2.0 * SPACING_LARGE as f32 - SCROLLBAR_WIDTH;
+        let item_width = (row_width - (items_per_row - 1.0) * SPACING_LARGE as f32) / items_per_row;
+        let text_width = item_width - 2.0 * SPACING_LARGE as f32;
+        ((text_width / (FONT_SIZE_STANDARD * AVERAGE_CHAR_WIDTH_RATIO)) as usize).max(4)
     }
 
     fn visible_count(&self) -> usize {
-        self.meta_list
-            .items()
-            .iter()
-            .filter(|item| self.filter_item(item))
-            .count()
+        self.visible_items.len()
     }
 
     fn view_icon_list(&self) -> iced::Element<'_, MyMessage> {
-        let items = self
-            .meta_list
-            .items()
+        let row_count = self.visible_items.len();
+        let rows =
+            self.visible_row_range(row_count, ITEM_HEIGHT_SMALL, SPACING_SMALL, SPACING_NORMAL);
+        let visible_rows = self.visible_items[rows.clone()]
             .iter()
-            .filter(|item| self.filter_item(item))
-            .map(|item| self.view_item_preview_small(item))
+            .map(|position| self.view_item_preview_small(&self.meta_list.items()[*position]))
             .collect::<Vec<iced::Element<'_, MyMessage>>>();
 
-        let column = iced::widget::column(items)
-            .width(iced::Length::Fill)
-            .spacing(SPACING_SMALL)
-            .padding([SPACING_NORMAL, SPACING_LARGE]);
+        let column = Self::view_virtualized_column(
+            visible_rows,
+            rows,
+            row_count,
+            ITEM_HEIGHT_SMALL,
+            SPACING_SMALL,
+        )
+        .padding([SPACING_NORMAL, SPACING_LARGE]);
 
         iced::widget::scrollable(
             iced::widget::container(column).style(CustomContainer::default().move_to_style()),
@@ -536,12 +2092,13 @@ impl MyApp {
         ))
         .width(iced::Length::Fill)
         .id(iced::widget::scrollable::Id::new(SCROLLABLE_ICON_LIST_ID))
+        .on_scroll(MyMessage::Scrolled)
         .into()
     }
 
     fn view_item_preview(&self, item: &MaterialFontMeta) -> iced::Element<'_, MyMessage> {
         let previewed_icon = iced::widget::text(item.to_char())
-            .font(self.icons_font())
+            .font(self.browsed_icons_font())
             .size(ICON_FONT_SIZE_BIG);
         let name = {
             let label = iced::widget::text("Name:")
@@ -602,81 +2159,815 @@ impl MyApp {
                 .unwrap_or((CODEPOINT_COPY, iced::theme::Text::Default));
             let icon = iced::widget::text(char::from_u32(codepoint).unwrap())
                 .font(self.icons_font())
-                .style(icon_style)
-                .size(ICON_FONT_SIZE_SMALLER);
-            let button = iced::widget::button(icon)
-                .on_press(MyMessage::Copy(
-                    item.to_hex_codepoint(),
-                    AnimationInfo::hex_codepoint(COPY_ANIMATION_STEPS),
-                ))
+                .style(icon_style)
+                .size(ICON_FONT_SIZE_SMALLER);
+            let button = iced::widget::button(icon)
+                .on_press(MyMessage::Copy(
+                    item.to_hex_codepoint(),
+                    AnimationInfo::hex_codepoint(COPY_ANIMATION_STEPS),
+                ))
+                .style(ToolbarButton::text().into())
+                .padding(0);
+            iced::widget::row!(button, label, value,)
+                .align_items(iced::Alignment::Center)
+                .width(iced::Length::Fill)
+                .height(iced::Length::Shrink)
+                .spacing(SPACING_NORMAL)
+        };
+        let codepoint = {
+            let label = iced::widget::text("Codepoint (u32):")
+                .font(self.font())
+                .size(FONT_SIZE_LARGE);
+            let value = iced::widget::text(item.codepoint().to_string())
+                .font(self.font())
+                .size(FONT_SIZE_LARGE);
+            let (codepoint, icon_style) = self
+                .copy_animation_info
+                .map(|info| {
+                    if let CopyType::Codepoint = info.copy_type() {
+                        (
+                            CODEPOINT_SUCCESS,
+                            iced::theme::Text::Color(self.theme().palette().success),
+                        )
+                    } else {
+                        (CODEPOINT_COPY, iced::theme::Text::Default)
+                    }
+                })
+                .unwrap_or((CODEPOINT_COPY, iced::theme::Text::Default));
+            let icon = iced::widget::text(char::from_u32(codepoint).unwrap())
+                .style(icon_style)
+                .font(self.icons_font())
+                .size(ICON_FONT_SIZE_SMALLER);
+            let button = iced::widget::button(icon)
+                .on_press(MyMessage::Copy(
+                    item.codepoint().to_string(),
+                    AnimationInfo::codepoint(COPY_ANIMATION_STEPS),
+                ))
+                .style(ToolbarButton::text().into())
+                .padding(0);
+            iced::widget::row!(button, label, value,)
+                .align_items(iced::Alignment::Center)
+                .width(iced::Length::Fill)
+                .height(iced::Length::Shrink)
+                .spacing(SPACING_NORMAL)
+        };
+        let svg = {
+            let label = iced::widget::text("SVG:")
+                .font(self.font())
+                .size(FONT_SIZE_LARGE);
+            let save_icon = iced::widget::text(char::from_u32(CODEPOINT_SAVE).unwrap())
+                .font(self.icons_font())
+                .size(ICON_FONT_SIZE_SMALLER);
+            let save_button = iced::widget::button(save_icon)
+                .on_press(MyMessage::ExportSvg(item.name().clone()))
+                .style(ToolbarButton::text().into())
+                .padding(0);
+            iced::widget::row!(
+                self.view_copy_button(
+                    CopyType::Vector(VectorFormat::Svg),
+                    MyMessage::CopyVector(item.name().clone(), VectorFormat::Svg)
+                ),
+                label,
+                save_button,
+            )
+            .align_items(iced::Alignment::Center)
+            .width(iced::Length::Fill)
+            .height(iced::Length::Shrink)
+            .spacing(SPACING_NORMAL)
+        };
+        let vector_drawable = self.view_item_preview_vector_row(
+            item,
+            "VectorDrawable:",
+            format!("ic_{}.xml", item.name()),
+            VectorFormat::VectorDrawable,
+        );
+        let compose = self.view_item_preview_vector_row(
+            item,
+            "Compose:",
+            format!("{}: ImageVector", export::pascal_case(item.name())),
+            VectorFormat::ComposeImageVector,
+        );
+        let snippets = self.view_item_preview_snippets(item);
+        let png = self.view_item_preview_png(item);
+        let version = self.view_item_preview_info_row("Version:", item.version().to_string());
+        let sizes = self.view_item_preview_info_row(
+            "Sizes (px):",
+            item.sizes_px()
+                .iter()
+                .map(|size| size.to_string())
+                .collect::<Vec<String>>()
+                .join(", "),
+        );
+        let families = self.view_item_preview_families(item);
+        let collections = self.view_item_preview_collections(item);
+        let aliases = self.meta_list.aliases(item);
+        let aliases = if aliases.is_empty() {
+            iced::widget::column(vec![]).into()
+        } else {
+            self.view_item_preview_info_row(
+                "Aliases:",
+                aliases
+                    .iter()
+                    .map(|alias| alias.name().as_str())
+                    .collect::<Vec<&str>>()
+                    .join(", "),
+            )
+        };
+        let usage = self.view_item_preview_usage(item);
+        let diff = self
+            .comparison
+            .iter()
+            .flat_map(|comparison| comparison.diff.entries())
+            .find(|entry| entry.new_name.as_ref() == Some(item.name()));
+        let diff = match diff {
+            Some(entry) => {
+                self.view_item_preview_info_row(format!("{}:", entry.kind), entry.to_string())
+            }
+            None => iced::widget::column(vec![]).into(),
+        };
+        let favorite_button = {
+            let favorite = self.favorites.contains(item.name());
+            let icon = if favorite {
+                iced::widget::text(char::from_u32(CODEPOINT_STAR).unwrap())
+                    .style(iced::theme::Text::Color(self.theme().palette().primary))
+            } else {
+                iced::widget::text(char::from_u32(CODEPOINT_STAR_BORDER).unwrap())
+            }
+            .font(self.icons_font())
+            .size(ICON_FONT_SIZE_SMALL);
+            let label = iced::widget::text(if favorite {
+                "Remove from favorites"
+            } else {
+                "Add to favorites"
+            })
+            .font(self.font())
+            .size(FONT_SIZE_STANDARD);
+            iced::widget::button(
+                iced::widget::row!(icon, label)
+                    .align_items(iced::Alignment::Center)
+                    .spacing(SPACING_SMALL),
+            )
+            .on_press(MyMessage::ToggleFavorite(item.name().clone()))
+            .style(ToolbarButton::text().into())
+        };
+        let close_button = {
+            let icon = iced::widget::text(char::from_u32(CODEPOINT_CLOSE_CIRCLE).unwrap())
+                .font(self.icons_font())
+                .size(ICON_FONT_SIZE_SMALL);
+            iced::widget::button(icon)
+                .on_press(MyMessage::Preview(None))
+                .style(ToolbarButton::text().into())
+        };
+        iced::widget::column!(
+            previewed_icon,
+            favorite_button,
+            name,
+            aliases,
+            diff,
+            codepoint_hex,
+            codepoint,
+            vector_drawable,
+            compose,
+            svg,
+            snippets,
+            png,
+            version,
+            sizes,
+            families,
+            collections,
+            usage,
+            self.view_export_status(),
+            close_button
+        )
+        .spacing(SPACING_NORMAL)
+        .align_items(iced::Alignment::Center)
+        .padding([SPACING_LARGE, SPACING_EXTRA_LARGE])
+        .into()
+    }
+
+    /// A copy button that turns into a check mark while the animation for `copy_type` runs.
+    fn view_copy_button(
+        &self,
+        copy_type: CopyType,
+        message: MyMessage,
+    ) -> iced::Element<'_, MyMessage> {
+        let copied = self
+            .copy_animation_info
+            .map(|info| info.copy_type() == copy_type)
+            .unwrap_or(false);
+        let icon = if copied {
+            iced::widget::text(char::from_u32(CODEPOINT_SUCCESS).unwrap())
+                .style(iced::theme::Text::Color(self.theme().palette().success))
+        } else {
+            iced::widget::text(char::from_u32(CODEPOINT_COPY).unwrap())
+        };
+        iced::widget::button(icon.font(self.icons_font()).size(ICON_FONT_SIZE_SMALLER))
+            .on_press(message)
+            .style(ToolbarButton::text().into())
+            .padding(0)
+            .into()
+    }
+
+    /// The outcome of the last export (or its error).
+    fn view_export_status(&self) -> iced::Element<'_, MyMessage> {
+        let (message, color) = match &self.export_status {
+            Some(Ok(message)) => (message, self.theme().palette().success),
+            Some(Err(error)) => (error, self.theme().palette().danger),
+            None => return iced::widget::row(vec![]).into(),
+        };
+        iced::widget::text(message)
+            .font(self.font())
+            .size(FONT_SIZE_SMALL)
+            .style(iced::theme::Text::Color(color))
+            .into()
+    }
+
+    /// The PNG export options, and the button exporting the previewed icon with them.
+    fn view_item_preview_png(&self, item: &MaterialFontMeta) -> iced::Element<'_, MyMessage> {
+        let settings = &self.png_settings;
+        let label = |text: &'static str| {
+            iced::widget::text(text)
+                .font(self.font())
+                .size(FONT_SIZE_STANDARD)
+        };
+        let text_input = |placeholder: &str, value: &str, width: f32| {
+            iced::widget::text_input(placeholder, value)
+                .font(self.font())
+                .size(FONT_SIZE_STANDARD)
+                .width(iced::Length::Fixed(width))
+        };
+
+        let sizes = raster::STANDARD_SIZES
+            .iter()
+            .map(|size| PngSize::Standard(*size))
+            .chain(std::iter::once(PngSize::Custom))
+            .collect::<Vec<PngSize>>();
+        let size = iced::widget::pick_list(
+            sizes,
+            Some(settings.size),
+            self.edit_png_settings(|settings, size| settings.size = size),
+        )
+        .font(self.font())
+        .text_size(FONT_SIZE_STANDARD);
+        let mut size_row = iced::widget::row!(label("PNG size:"), size)
+            .align_items(iced::Alignment::Center)
+            .spacing(SPACING_NORMAL);
+        if settings.size == PngSize::Custom {
+            size_row = size_row.push(
+                text_input("px", &settings.custom_size, PNG_NUMBER_INPUT_WIDTH)
+                    .on_input(self.edit_png_settings(|settings, size| settings.custom_size = size)),
+            );
+        }
+
+        let colors = iced::widget::row!(
+            label("Color:"),
+            text_input("#000000", &settings.color, PNG_COLOR_INPUT_WIDTH)
+                .on_input(self.edit_png_settings(|settings, color| settings.color = color)),
+            label("Background:"),
+            text_input("transparent", &settings.background, PNG_COLOR_INPUT_WIDTH)
+                .on_input(self.edit_png_settings(|settings, color| settings.background = color)),
+        )
+        .align_items(iced::Alignment::Center)
+        .spacing(SPACING_NORMAL);
+
+        let layout = iced::widget::row!(
+            label("Padding:"),
+            text_input("0", &settings.padding, PNG_NUMBER_INPUT_WIDTH)
+                .on_input(self.edit_png_settings(|settings, padding| settings.padding = padding)),
+            iced::widget::pick_list(
+                &DensitySet::ALL[..],
+                Some(settings.densities),
+                self.edit_png_settings(|settings, densities| settings.densities = densities),
+            )
+            .font(self.font())
+            .text_size(FONT_SIZE_STANDARD),
+        )
+        .align_items(iced::Alignment::Center)
+        .spacing(SPACING_NORMAL);
+
+        let export_button = {
+            let icon = iced::widget::text(char::from_u32(CODEPOINT_SAVE).unwrap())
+                .font(self.icons_font())
+                .size(ICON_FONT_SIZE_SMALLER);
+            iced::widget::button(
+                iced::widget::row!(icon, label("Export PNG…"))
+                    .align_items(iced::Alignment::Center)
+                    .spacing(SPACING_SMALL),
+            )
+            .on_press(MyMessage::ExportPng(vec![item.name().clone()]))
+            .style(ToolbarButton::text().into())
+        };
+
+        iced::widget::column!(size_row, colors, layout, export_button)
+            .width(iced::Length::Fill)
+            .spacing(SPACING_SMALL)
+            .into()
+    }
+
+    /// A function turning the value of a PNG option's input into the message updating it.
+    fn edit_png_settings<T: 'static>(
+        &self,
+        edit: fn(&mut PngSettings, T),
+    ) -> impl Fn(T) -> MyMessage + 'static {
+        let settings = self.png_settings.clone();
+        move |value| {
+            let mut settings = settings.clone();
+            edit(&mut settings, value);
+            MyMessage::PngSettings(settings)
+        }
+    }
+
+    /// Renders the icons named `names` to PNG files, with the options of the preview.
+    fn export_pngs(&mut self, names: Vec<String>) -> iced::Command<MyMessage> {
+        let options = match self.png_settings.to_options() {
+            Ok(options) => options,
+            Err(e) => {
+                self.export_status = Some(Err(e));
+                return iced::Command::none();
+            }
+        };
+        let items = names
+            .iter()
+            .filter_map(|name| self.meta_list.get_item_by_name(name))
+            .cloned()
+            .collect::<Vec<MaterialFontMeta>>();
+        let font_bytes = match &self.icons_font_bytes {
+            Some(bytes) => std::borrow::Cow::from(bytes.clone()),
+            None => std::borrow::Cow::from(ICONS_FONT_BYTES),
+        };
+        iced::Command::perform(
+            export_pngs(font_bytes, items, options, self.png_settings.densities),
+            MyMessage::PngsExported,
+        )
+    }
+
+    /// The code snippets referencing `item` in other frameworks (the built-in ones, then the
+    /// user's), each with its copy button.
+    fn view_item_preview_snippets(&self, item: &MaterialFontMeta) -> iced::Element<'_, MyMessage> {
+        let label = iced::widget::text("Snippets:")
+            .font(self.font())
+            .size(FONT_SIZE_LARGE);
+        let built_in = Snippet::ALL.iter().map(|snippet| {
+            self.view_snippet_row(
+                snippet.to_string(),
+                snippet.render(item),
+                CopyType::Snippet(*snippet),
+            )
+        });
+        let custom = self
+            .custom_snippets
+            .snippets()
+            .iter()
+            .enumerate()
+            .map(|(index, template)| {
+                self.view_snippet_row(
+                    template.name().clone(),
+                    template.render(item),
+                    CopyType::CustomSnippet(index),
+                )
+            });
+        let errors = self
+            .custom_snippets_error
+            .iter()
+            .cloned()
+            .chain(
+                self.custom_snippets
+                    .rejected()
+                    .iter()
+                    .map(|(name, e)| format!("Skipped the \"{}\" snippet: {}", name, e)),
+            )
+            .map(|error| {
+                iced::Element::from(
+                    iced::widget::text(error)
+                        .font(self.font())
+                        .size(FONT_SIZE_SMALLER)
+                        .style(iced::theme::Text::Color(self.theme().palette().danger)),
+                )
+            });
+        iced::widget::column(
+            std::iter::once(iced::Element::from(label))
+                .chain(built_in)
+                .chain(custom)
+                .chain(errors)
+                .collect(),
+        )
+        .width(iced::Length::Fill)
+        .spacing(SPACING_SMALL)
+        .into()
+    }
+
+    fn view_snippet_row(
+        &self,
+        name: String,
+        code: String,
+        copy_type: CopyType,
+    ) -> iced::Element<'_, MyMessage> {
+        let button = self.view_copy_button(
+            copy_type,
+            MyMessage::Copy(
+                code.clone(),
+                AnimationInfo::new(copy_type, COPY_ANIMATION_STEPS),
+            ),
+        );
+        let name = iced::widget::text(format!("{}:", name))
+            .font(self.bold_font())
+            .size(FONT_SIZE_SMALL);
+        let code = iced::widget::text(code)
+            .font(self.font())
+            .size(FONT_SIZE_SMALLER);
+        iced::widget::row!(button, iced::widget::column!(name, code))
+            .align_items(iced::Alignment::Center)
+            .spacing(SPACING_NORMAL)
+            .into()
+    }
+
+    /// A row copying the glyph of `item` converted to `format`.
+    fn view_item_preview_vector_row(
+        &self,
+        item: &MaterialFontMeta,
+        label: &'static str,
+        value: String,
+        format: VectorFormat,
+    ) -> iced::Element<'_, MyMessage> {
+        let label = iced::widget::text(label)
+            .font(self.font())
+            .size(FONT_SIZE_LARGE);
+        let value = iced::widget::text(value)
+            .font(self.font())
+            .size(FONT_SIZE_LARGE);
+        iced::widget::row!(
+            self.view_copy_button(
+                CopyType::Vector(format),
+                MyMessage::CopyVector(item.name().clone(), format)
+            ),
+            label,
+            value,
+        )
+        .align_items(iced::Alignment::Center)
+        .width(iced::Length::Fill)
+        .height(iced::Length::Shrink)
+        .spacing(SPACING_NORMAL)
+        .into()
+    }
+
+    fn view_item_preview_info_row(
+        &self,
+        label: impl Into<String>,
+        value: impl Into<String>,
+    ) -> iced::Element<'_, MyMessage> {
+        let label = iced::widget::text(label.into())
+            .font(self.font())
+            .size(FONT_SIZE_LARGE);
+        let value = iced::widget::text(value.into())
+            .font(self.font())
+            .size(FONT_SIZE_LARGE);
+        iced::widget::row!(label, value)
+            .align_items(iced::Alignment::Center)
+            .width(iced::Length::Fill)
+            .height(iced::Length::Shrink)
+            .spacing(SPACING_NORMAL)
+            .into()
+    }
+
+    fn view_item_preview_families(&self, item: &MaterialFontMeta) -> iced::Element<'_, MyMessage> {
+        let missing = self.meta_list.missing_families(item);
+        let label = iced::widget::text(if missing.is_empty() {
+            "Families: available in all"
+        } else {
+            "Families:"
+        })
+        .font(self.font())
+        .size(FONT_SIZE_LARGE);
+
+        let families = self
+            .meta_list
+            .header()
+            .families()
+            .iter()
+            .map(|family| {
+                let supported = !missing.contains(family);
+                let (codepoint, color) = if supported {
+                    (CODEPOINT_CHECK_CIRCLE, self.theme().palette().success)
+                } else {
+                    (CODEPOINT_CLOSE_CIRCLE, self.theme().palette().danger)
+                };
+                let icon = iced::widget::text(char::from_u32(codepoint).unwrap())
+                    .style(iced::theme::Text::Color(color))
+                    .font(self.icons_font())
+                    .size(ICON_FONT_SIZE_TINY);
+                let name = iced::widget::text(family)
+                    .font(self.font())
+                    .size(FONT_SIZE_SMALL);
+                iced::widget::row!(icon, name)
+                    .align_items(iced::Alignment::Center)
+                    .spacing(SPACING_SMALL)
+                    .into()
+            })
+            .collect::<Vec<iced::Element<'_, MyMessage>>>();
+
+        iced::widget::column!(label, iced::widget::column(families).spacing(SPACING_SMALL))
+            .width(iced::Length::Fill)
+            .spacing(SPACING_SMALL)
+            .into()
+    }
+
+    /// The collections containing `item` (each with a button removing it from the collection),
+    /// and a picker adding it to another one.
+    fn view_item_preview_collections(
+        &self,
+        item: &MaterialFontMeta,
+    ) -> iced::Element<'_, MyMessage> {
+        let (containing, others): (Vec<&Collection>, Vec<&Collection>) = self
+            .collections
+            .collections()
+            .iter()
+            .partition(|collection| collection.contains(item.name()));
+
+        let label = iced::widget::text(if containing.is_empty() {
+            "Collections: none"
+        } else {
+            "Collections:"
+        })
+        .font(self.font())
+        .size(FONT_SIZE_LARGE);
+
+        let mut column = iced::widget::column!(label)
+            .width(iced::Length::Fill)
+            .spacing(SPACING_SMALL);
+        for collection in containing {
+            let icon = iced::widget::text(char::from_u32(CODEPOINT_CLOSE_CIRCLE).unwrap())
+                .font(self.icons_font())
+                .size(ICON_FONT_SIZE_TINY);
+            let button = iced::widget::button(icon)
+                .on_press(MyMessage::RemoveFromCollection(
+                    collection.name().clone(),
+                    item.name().clone(),
+                ))
+                .style(ToolbarButton::text().into())
+                .padding(0);
+            let name = iced::widget::text(collection.name())
+                .font(self.font())
+                .size(FONT_SIZE_SMALL);
+            column = column.push(
+                iced::widget::row!(button, name)
+                    .align_items(iced::Alignment::Center)
+                    .spacing(SPACING_SMALL),
+            );
+        }
+
+        if !others.is_empty() {
+            let icon = iced::widget::text(char::from_u32(CODEPOINT_LIBRARY_ADD).unwrap())
+                .font(self.icons_font())
+                .size(ICON_FONT_SIZE_SMALLER);
+            let names = others
+                .iter()
+                .map(|collection| collection.name().clone())
+                .collect::<Vec<String>>();
+            let icon_name = item.name().clone();
+            let pick_list = iced::widget::pick_list(names, None, move |name| {
+                MyMessage::AddToCollection(name, vec![icon_name.clone()])
+            })
+            .placeholder("Add to collection…")
+            .font(self.font())
+            .text_size(FONT_SIZE_SMALL);
+            column = column.push(
+                iced::widget::row!(icon, pick_list)
+                    .align_items(iced::Alignment::Center)
+                    .spacing(SPACING_SMALL),
+            );
+        }
+        column.into()
+    }
+
+    /// The "Project usage" heading (with the scan button), the section of the used icons, and the
+    /// references to icons the browsed set doesn't have.
+    fn view_sidebar_usage(&self) -> iced::Element<'_, MyMessage> {
+        let scan_button = {
+            let icon = iced::widget::text(char::from_u32(CODEPOINT_MANAGE_SEARCH).unwrap())
+                .font(self.icons_font())
+                .size(ICON_FONT_SIZE_TINY);
+            iced::widget::button(icon)
+                .on_press(MyMessage::ScanProject)
+                .style(ToolbarButton::text().into())
+                .padding(0)
+        };
+        let heading = iced::widget::row!(
+            self.view_sidebar_heading("Project usage"),
+            iced::widget::container("").width(iced::Length::Fill),
+            scan_button,
+        )
+        .align_items(iced::Alignment::Center);
+
+        let heading =
+            iced::widget::container(heading).padding([SPACING_LARGE, 0, SPACING_NORMAL, 0]);
+        let mut column = iced::widget::column!(heading).spacing(SPACING_SMALL);
+        let small_text = |content: String| {
+            iced::widget::text(content)
+                .font(self.font())
+                .size(FONT_SIZE_SMALLER)
+        };
+        if let Some(error) = &self.usage_error {
+            column = column.push(
+                small_text(format!("Scan failed: {}", error))
+                    .style(iced::theme::Text::Color(self.theme().palette().danger)),
+            );
+        }
+        let Some(usage) = &self.project_usage else {
+            return column.into();
+        };
+        for (path, error) in usage.skipped() {
+            column = column.push(
+                small_text(format!("Skipped {}: {}", path.display(), error))
+                    .style(iced::theme::Text::Color(self.theme().palette().danger)),
+            );
+        }
+
+        let icon =
+            iced::widget::text(char::from_u32(CODEPOINT_CODE).unwrap()).font(self.icons_font());
+        column = column.push(self.view_sidebar_button(Section::Usage, icon));
+        column = column.push(small_text(usage.root().display().to_string()));
+        let unknown = usage.unknown_references(&self.meta_list);
+        if !unknown.is_empty() {
+            let icon = iced::widget::text(char::from_u32(CODEPOINT_WARNING).unwrap())
+                .font(self.icons_font())
+                .size(ICON_FONT_SIZE_TINY)
+                .style(iced::theme::Text::Color(self.theme().palette().danger));
+            let label = small_text(format!("Unknown icons ({}):", unknown.len()));
+            column = column.push(
+                iced::widget::row!(icon, label)
+                    .align_items(iced::Alignment::Center)
+                    .spacing(SPACING_SMALL),
+            );
+            for reference in unknown {
+                column = column.push(small_text(format!(
+                    "{} ({}) {}",
+                    reference.key,
+                    reference.kind,
+                    reference.location()
+                )));
+            }
+        }
+        column.into()
+    }
+
+    /// The "Compare" heading (with the button picking an older snapshot), and the buckets of the
+    /// comparison, with their number of icons.
+    fn view_sidebar_comparison(&self) -> iced::Element<'_, MyMessage> {
+        let compare_button = {
+            let icon = iced::widget::text(char::from_u32(CODEPOINT_COMPARE).unwrap())
+                .font(self.icons_font())
+                .size(ICON_FONT_SIZE_TINY);
+            iced::widget::button(icon)
+                .on_press(MyMessage::CompareWith)
                 .style(ToolbarButton::text().into())
-                .padding(0);
-            iced::widget::row!(button, label, value,)
-                .align_items(iced::Alignment::Center)
-                .width(iced::Length::Fill)
-                .height(iced::Length::Shrink)
-                .spacing(SPACING_NORMAL)
+                .padding(0)
         };
-        let codepoint = {
-            let label = iced::widget::text("Codepoint (u32):")
-                .font(self.font())
-                .size(FONT_SIZE_LARGE);
-            let value = iced::widget::text(item.codepoint().to_string())
+        let heading = iced::widget::row!(
+            self.view_sidebar_heading("Compare"),
+            iced::widget::container("").width(iced::Length::Fill),
+            compare_button,
+        )
+        .align_items(iced::Alignment::Center);
+
+        let heading =
+            iced::widget::container(heading).padding([SPACING_LARGE, 0, SPACING_NORMAL, 0]);
+        let mut column = iced::widget::column!(heading).spacing(SPACING_SMALL);
+        let small_text = |content: String| {
+            iced::widget::text(content)
                 .font(self.font())
-                .size(FONT_SIZE_LARGE);
-            let (codepoint, icon_style) = self
-                .copy_animation_info
-                .map(|info| {
-                    if let CopyType::Codepoint = info.copy_type() {
-                        (
-                            CODEPOINT_SUCCESS,
-                            iced::theme::Text::Color(self.theme().palette().success),
-                        )
-                    } else {
-                        (CODEPOINT_COPY, iced::theme::Text::Default)
-                    }
-                })
-                .unwrap_or((CODEPOINT_COPY, iced::theme::Text::Default));
-            let icon = iced::widget::text(char::from_u32(codepoint).unwrap())
-                .style(icon_style)
-                .font(self.icons_font())
-                .size(ICON_FONT_SIZE_SMALLER);
-            let button = iced::widget::button(icon)
-                .on_press(MyMessage::Copy(
-                    item.codepoint().to_string(),
-                    AnimationInfo::codepoint(COPY_ANIMATION_STEPS),
-                ))
-                .style(ToolbarButton::text().into())
-                .padding(0);
-            iced::widget::row!(button, label, value,)
-                .align_items(iced::Alignment::Center)
-                .width(iced::Length::Fill)
-                .height(iced::Length::Shrink)
-                .spacing(SPACING_NORMAL)
+                .size(FONT_SIZE_SMALLER)
+        };
+        if let Some(error) = &self.comparison_error {
+            column = column.push(
+                small_text(format!("Comparison failed: {}", error))
+                    .style(iced::theme::Text::Color(self.theme().palette().danger)),
+            );
+        }
+        let Some(comparison) = &self.comparison else {
+            return column.into();
         };
+
         let close_button = {
             let icon = iced::widget::text(char::from_u32(CODEPOINT_CLOSE_CIRCLE).unwrap())
                 .font(self.icons_font())
-                .size(ICON_FONT_SIZE_SMALL);
+                .size(ICON_FONT_SIZE_TINY);
             iced::widget::button(icon)
-                .on_press(MyMessage::Codepoint(None))
+                .on_press(MyMessage::CloseComparison)
                 .style(ToolbarButton::text().into())
+                .padding(0)
         };
-        iced::widget::column!(previewed_icon, name, codepoint_hex, codepoint, close_button)
-            .spacing(SPACING_NORMAL)
-            .align_items(iced::Alignment::Center)
-            .padding([SPACING_LARGE, SPACING_EXTRA_LARGE])
-            .into()
+        let file_name = comparison
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        column = column.push(
+            iced::widget::row!(close_button, small_text(format!("Since {}", file_name)))
+                .align_items(iced::Alignment::Center)
+                .spacing(SPACING_SMALL),
+        );
+        for kind in DiffKind::ALL {
+            let icon = iced::widget::text(char::from_u32(CODEPOINT_DIFFERENCE).unwrap())
+                .font(self.icons_font());
+            let count = small_text(comparison.diff.count(kind).to_string());
+            column = column.push(
+                iced::widget::row!(self.view_sidebar_button(Section::Diff(kind), icon), count)
+                    .align_items(iced::Alignment::Center),
+            );
+        }
+        column.into()
     }
 
-    fn view_item_preview_small(&self, item: &MaterialFontMeta) -> iced::Element<'_, MyMessage> {
-        let selected = if let Some(current) = self.codepoint {
-            item.codepoint() == current
-        } else {
-            false
+    /// The differences of the `kind` bucket of the comparison, including the removed icons (which
+    /// the grid can't show).
+    fn view_diff_panel(&self, kind: DiffKind) -> iced::Element<'_, MyMessage> {
+        let Some(comparison) = &self.comparison else {
+            return iced::widget::column(vec![]).into();
+        };
+        let entries = comparison.diff.bucket(kind);
+        let heading = iced::widget::row!(
+            iced::widget::text(format!("{} since {}", kind, comparison.path.display()))
+                .font(self.bold_font())
+                .size(FONT_SIZE_LARGE),
+            iced::widget::text(format!("{} icon(s)", entries.len()))
+                .font(self.font())
+                .size(FONT_SIZE_SMALL),
+        )
+        .spacing(SPACING_LARGE)
+        .align_items(iced::Alignment::Center);
+
+        let mut column = iced::widget::column!(heading).spacing(SPACING_SMALL);
+        for entry in entries {
+            column = column.push(
+                iced::widget::text(entry.to_string())
+                    .font(self.font())
+                    .size(FONT_SIZE_SMALL),
+            );
+        }
+        iced::widget::container(iced::widget::scrollable(
+            iced::widget::container(column)
+                .width(iced::Length::Fill)
+                .padding([SPACING_NORMAL, SPACING_LARGE]),
+        ))
+        .style(CustomContainer::toolbar().move_to_style())
+        .width(iced::Length::Fill)
+        .height(iced::Length::Fixed(PANEL_HEIGHT))
+        .into()
+    }
+
+    /// The files and lines of the scanned project referencing `item` (nothing if no project was
+    /// scanned).
+    fn view_item_preview_usage(&self, item: &MaterialFontMeta) -> iced::Element<'_, MyMessage> {
+        let Some(usage) = &self.project_usage else {
+            return iced::widget::column(vec![]).into();
         };
+        let locations = usage
+            .references()
+            .iter()
+            .filter(|reference| {
+                reference
+                    .resolve(&self.meta_list)
+                    .map(|resolved| resolved.name() == item.name())
+                    .unwrap_or(false)
+            })
+            .map(|reference| {
+                iced::widget::text(format!("{} ({})", reference.location(), reference.kind))
+                    .font(self.font())
+                    .size(FONT_SIZE_SMALL)
+                    .into()
+            })
+            .collect::<Vec<iced::Element<'_, MyMessage>>>();
+        let label = iced::widget::text(if locations.is_empty() {
+            "Used in: none"
+        } else {
+            "Used in:"
+        })
+        .font(self.font())
+        .size(FONT_SIZE_LARGE);
+        iced::widget::column!(
+            label,
+            iced::widget::column(locations).spacing(SPACING_SMALL)
+        )
+        .width(iced::Length::Fill)
+        .spacing(SPACING_SMALL)
+        .into()
+    }
+
+    fn save_collections(&self) -> iced::Command<MyMessage> {
+        match &self.collections_path {
+            Some(path) => {
+                iced::Command::perform(self.collections.clone().save(path.clone()), |r| {
+                    MyMessage::CollectionsSaved(r.map_err(|e| e.to_string()))
+                })
+            }
+            None => iced::Command::none(),
+        }
+    }
+
+    fn view_item_preview_small(&self, item: &MaterialFontMeta) -> iced::Element<'_, MyMessage> {
+        let selected = self.is_item_selected(item);
         let icon = iced::widget::text(item.to_char())
-            .font(self.icons_font())
+            .font(self.browsed_icons_font())
             .size(ICON_FONT_SIZE_SMALL);
         let name = iced::widget::text(item.name())
             .font(self.selected_font(selected))
@@ -685,31 +2976,40 @@ impl MyApp {
             .spacing(SPACING_NORMAL)
             .align_items(iced::Alignment::Center);
         iced::widget::button(row)
-            .on_press(MyMessage::Codepoint(Some(item.codepoint())))
-            .style(RowButton::new(selected).into())
+            .on_press(MyMessage::Preview(Some(item.name().clone())))
+            .style(
+                RowButton::new(selected)
+                    .focused(self.is_item_focused(item))
+                    .into(),
+            )
             .width(iced::Length::Fill)
+            .height(iced::Length::Fixed(ITEM_HEIGHT_SMALL))
             .padding(0)
             .into()
     }
 
-    fn view_item_preview_medium(&self, item: &MaterialFontMeta) -> iced::Element<'_, MyMessage> {
-        let selected = if let Some(current) = self.codepoint {
-            item.codepoint() == current
-        } else {
-            false
-        };
+    fn view_item_preview_medium(
+        &self,
+        item: &MaterialFontMeta,
+        name_max_chars: usize,
+    ) -> iced::Element<'_, MyMessage> {
+        let selected = self.is_item_selected(item);
         let icon = iced::widget::text(item.to_char())
-            .font(self.icons_font())
+            .font(self.browsed_icons_font())
             .size(ICON_FONT_SIZE_MEDIUM);
-        let name = iced::widget::text(item.name())
+        let name = iced::widget::text(truncated_string(item.name(), name_max_chars))
             .font(self.selected_font(selected))
             .size(FONT_SIZE_STANDARD);
         let column = iced::widget::column!(icon, name).align_items(iced::Alignment::Center);
         iced::widget::button(column)
-            .on_press(MyMessage::Codepoint(Some(item.codepoint())))
-            .style(RowButton::new_bordered(selected).into())
+            .on_press(MyMessage::Preview(Some(item.name().clone())))
+            .style(
+                RowButton::new_bordered(selected)
+                    .focused(self.is_item_focused(item))
+                    .into(),
+            )
             .width(iced::Length::Fill)
-            .height(iced::Length::Shrink)
+            .height(iced::Length::Fixed(ITEM_HEIGHT_MEDIUM))
             .padding(SPACING_LARGE)
             .into()
     }
@@ -717,39 +3017,61 @@ impl MyApp {
 
 impl iced::Application for MyApp {
     type Executor = iced::executor::Default;
-    type Flags = ();
+    type Flags = Flags;
     type Message = MyMessage;
     type Theme = iced::theme::Theme;
 
-    fn new(_flags: Self::Flags) -> (Self, iced::Command<Self::Message>) {
-        let mut commands: Vec<iced::Command<MyMessage>> =
-            vec![ICONS_FONT_BYTES, FONT_BYTES_REGULAR, FONT_BYTES_BOLD]
-                .iter()
-                .map(|&bytes| {
-                    iced::font::load(std::borrow::Cow::from(bytes)).map(|r| {
-                        if let Err(e) = r {
-                            panic!("{:?}", e);
-                        }
-                        MyMessage::FontLoaded
-                    })
-                })
-                .collect();
+    fn new(flags: Self::Flags) -> (Self, iced::Command<Self::Message>) {
+        let mut app = Self {
+            window_size: flags
+                .state
+                .window_size
+                .unwrap_or(Self::default().window_size),
+            window_position: flags.state.window_position,
+            grid_view: flags.state.grid_view,
+            collapse_aliases: flags.state.collapse_aliases,
+            state_path: flags.state_path,
+            pending_state: Some(flags.state),
+            config_errors: flags.state_error.into_iter().collect(),
+            ..Self::default()
+        };
 
-        commands.push(iced::Command::perform(
-            MaterialFontMetaList::load_from_bytes_fake_async(std::borrow::Cow::from(
-                ICONS_META_BYTES,
-            )),
-            |r: Result<MaterialFontMetaList, LoadError>| match r {
-                Err(e) => panic!("{:?}", e),
-                Ok(list) => MyMessage::MetaListLoaded(list),
-            },
-        ));
+        let mut commands = vec![
+            Self::load_bundled_fonts(),
+            app.load_icon_set(flags.icon_set_paths),
+        ];
+        if let Some(path) = Favorites::default_path() {
+            commands.push(iced::Command::perform(
+                Favorites::load(path.clone()),
+                move |r| MyMessage::FavoritesLoaded(path, r),
+            ));
+        }
+        if let Some(path) = Collections::default_path() {
+            commands.push(iced::Command::perform(
+                Collections::load(path.clone()),
+                move |r| MyMessage::CollectionsLoaded(path, r),
+            ));
+        }
+        if let Some(path) = CustomSnippets::default_path() {
+            commands.push(iced::Command::perform(
+                CustomSnippets::load(path.clone()),
+                move |r| MyMessage::CustomSnippetsLoaded(path, r),
+            ));
+        }
 
-        (Default::default(), iced::Command::batch(commands))
+        (app, iced::Command::batch(commands))
     }
 
     fn title(&self) -> String {
-        APP_TITLE.into()
+        match self
+            .icon_set_paths
+            .meta
+            .as_ref()
+            .and_then(|path| path.file_name())
+        {
+            Some(file_name) => format!("{} - {}", APP_TITLE, file_name.to_string_lossy()),
+            None => APP_TITLE.into(),
+        }
     }
 
     fn theme(&self) -> Self::Theme {
@@ -770,6 +3092,10 @@ impl iced::Application for MyApp {
     }
 
     fn view(&self) -> iced::Element<'_, Self::Message, iced::Renderer<Self::Theme>> {
+        if let Some((paths, error)) = &self.load_error {
+            return self.view_load_error(paths, error);
+        }
+
         if !self.are_resources_loaded() {
             return iced::widget::container("")
                 .width(iced::Length::Fill)
@@ -780,14 +3106,25 @@ impl iced::Application for MyApp {
                 .into();
         }
 
+        let mut main_column = iced::widget::column!(
+            self.view_toolbar(),
+            iced::widget::horizontal_rule(0).style(CustomRule::dark().move_to_style()),
+        );
+        if self.diagnostics_visible {
+            main_column = main_column
+                .push(self.view_diagnostics())
+                .push(iced::widget::horizontal_rule(0).style(CustomRule::dark().move_to_style()));
+        }
+        if let Some(kind) = self.diff_panel_kind() {
+            main_column = main_column
+                .push(self.view_diff_panel(kind))
+                .push(iced::widget::horizontal_rule(0).style(CustomRule::dark().move_to_style()));
+        }
+
         iced::widget::row!(
             self.view_sidebar(),
             iced::widget::vertical_rule(0).style(CustomRule::dark().move_to_style()),
-            iced::widget::column!(
-                self.view_toolbar(),
-                iced::widget::horizontal_rule(0).style(CustomRule::dark().move_to_style()),
-                self.view_content(),
-            )
+            main_column.push(self.view_content()),
         )
         .into()
     }
@@ -807,18 +3144,54 @@ impl iced::Application for MyApp {
                 if let iced::event::Event::Window(iced::window::Event::Resized { width, height }) =
                     e
                 {
+                    let anchor = self
+                        .previewed
+                        .as_deref()
+                        .and_then(|name| self.item_anchor(name));
                     self.window_size = (width, height);
+                    return self.scroll_to_anchor(anchor);
+                }
+                if let iced::event::Event::Window(iced::window::Event::Moved { x, y }) = e {
+                    self.window_position = Some((x, y));
+                }
+                if let iced::event::Event::Window(iced::window::Event::CloseRequested) = e {
+                    return match &self.state_path {
+                        Some(path) => {
+                            iced::Command::perform(self.session_state().save(path.clone()), |r| {
+                                MyMessage::StateSaved(r.map_err(|e| e.to_string()))
+                            })
+                        }
+                        None => iced::window::close(),
+                    };
+                }
+                if let iced::event::Event::Keyboard(iced::keyboard::Event::ModifiersChanged(
+                    modifiers,
+                )) = e
+                {
+                    self.modifiers = modifiers;
                 }
                 if let iced::event::Event::Keyboard(iced::keyboard::Event::KeyPressed {
                     key_code: iced::keyboard::KeyCode::Escape,
                     ..
                 }) = e
                 {
-                    if self.codepoint.is_some() {
-                        self.codepoint = None;
+                    if self.previewed.is_some() {
+                        let anchor = self
+                            .previewed
+                            .as_deref()
+                            .and_then(|name| self.item_anchor(name));
+                        self.previewed = None;
+                        if self.focus == FocusArea::Preview {
+                            self.focus = FocusArea::Items;
+                        }
+                        return self.scroll_to_anchor(anchor);
+                    } else if !self.selection.is_empty() {
+                        self.selection.clear();
+                        return iced::Command::none();
                     } else if self.search_visible {
                         self.search_visible = false;
-                        self.search_text = "".into();
+                        self.set_search_text(String::new());
+                        return self.restore_scroll_offset();
                     }
                 }
                 if let iced::event::Event::Keyboard(iced::keyboard::Event::KeyPressed {
@@ -837,13 +3210,39 @@ impl iced::Application for MyApp {
                         );
                     }
                 }
+                if let iced::event::Event::Keyboard(iced::keyboard::Event::KeyPressed {
+                    key_code,
+                    modifiers,
+                }) = e
+                {
+                    if let Some(command) = self.navigate(key_code, modifiers) {
+                        return command;
+                    }
+                }
                 iced::Command::none()
             }
+            MyMessage::CollapseAliasesState(collapse_aliases) => {
+                self.collapse_aliases = collapse_aliases;
+                self.refresh_visible_items();
+                // NOTE: Offsets don't carry over, since the sections have fewer (or more) icons.
+                self.section_scroll_offsets.clear();
+                self.previewed = self
+                    .previewed_item()
+                    .map(|item| self.shown_item(item).name().clone());
+                self.focused_item = self
+                    .previewed
+                    .as_deref()
+                    .and_then(|name| self.visible_index(name));
+                self.restore_scroll_offset()
+            }
             MyMessage::GridViewState(grid_view) => {
                 self.grid_view = grid_view;
-                self.codepoint = None;
+                self.previewed = None;
                 self.search_visible = false;
-                self.search_text = "".into();
+                self.set_search_text(String::new());
+                // NOTE: Offsets don't carry over, since grid and list rows have different heights.
+                self.section_scroll_offsets.clear();
+                self.scroll_offset = 0.0;
                 iced::widget::scrollable::snap_to(
                     iced::widget::scrollable::Id::new(SCROLLABLE_ICON_LIST_ID),
                     iced::widget::scrollable::RelativeOffset::START,
@@ -853,11 +3252,84 @@ impl iced::Application for MyApp {
                 self.copy_animation_info = Some(animation_info);
                 iced::clipboard::write(s)
             }
+            MyMessage::CopyVector(name, format) => {
+                let Some(item) = self.meta_list.get_item_by_name(&name) else {
+                    return iced::Command::none();
+                };
+                match self.glyph_outline(item.codepoint()) {
+                    Ok(outline) => {
+                        let code = format.convert(item, &outline);
+                        self.update(MyMessage::Copy(
+                            code,
+                            AnimationInfo::vector(format, COPY_ANIMATION_STEPS),
+                        ))
+                    }
+                    Err(e) => {
+                        self.export_status = Some(Err(e.to_string()));
+                        iced::Command::none()
+                    }
+                }
+            }
+            MyMessage::ExportSvg(name) => {
+                let Some(item) = self.meta_list.get_item_by_name(&name) else {
+                    return iced::Command::none();
+                };
+                match self.glyph_outline(item.codepoint()) {
+                    Ok(outline) => iced::Command::perform(
+                        save_svg(item.name().clone(), export::to_svg(&outline)),
+                        MyMessage::FileExported,
+                    ),
+                    Err(e) => {
+                        self.export_status = Some(Err(e.to_string()));
+                        iced::Command::none()
+                    }
+                }
+            }
+            MyMessage::PngSettings(settings) => {
+                self.png_settings = settings;
+                self.export_status = None;
+                iced::Command::none()
+            }
+            MyMessage::ExportPng(names) => self.export_pngs(names),
+            MyMessage::PngsExported(r) => {
+                self.export_status = match r {
+                    Ok(0) => None,
+                    Ok(1) => Some(Ok(String::from("Exported 1 file"))),
+                    Ok(count) => Some(Ok(format!("Exported {} files", count))),
+                    Err(e) => Some(Err(format!("Export failed: {}", e))),
+                };
+                iced::Command::none()
+            }
+            MyMessage::ExportRustModule(source, options) => {
+                let items = self.export_source_items(&source);
+                iced::Command::perform(
+                    save_rust_module(export::to_rust_module(&items, &options)),
+                    MyMessage::FileExported,
+                )
+            }
+            MyMessage::BuildSubsetFont(source) => {
+                let items = self.export_source_items(&source);
+                let font_bytes = self.icons_font_bytes.as_deref().unwrap_or(ICONS_FONT_BYTES);
+                match subset::subset_icons(font_bytes, self.meta_list.header(), &items) {
+                    Ok((font, meta_json)) => iced::Command::perform(
+                        save_subset_font(font, meta_json),
+                        MyMessage::FileExported,
+                    ),
+                    Err(e) => {
+                        self.export_status = Some(Err(e.to_string()));
+                        iced::Command::none()
+                    }
+                }
+            }
+            MyMessage::FileExported(r) => {
+                self.export_status = r.err().map(|e| Err(format!("Export failed: {}", e)));
+                iced::Command::none()
+            }
             MyMessage::SearchFocusState(is_focused) => {
                 if !is_focused && self.search_visible && self.search_text.is_empty() {
                     // println!("Forcing search to hide");
                     self.search_visible = false;
-                    self.search_text = "".into();
+                    self.set_search_text(String::new());
                     // iced::widget::scrollable::snap_to(
                     //     iced::widget::scrollable::Id::new(SCROLLABLE_ICON_LIST_ID),
                     //     iced::widget::scrollable::RelativeOffset::START,
@@ -867,52 +3339,339 @@ impl iced::Application for MyApp {
             }
             MyMessage::SearchVisibleState(visible) => {
                 self.search_visible = visible;
-                self.codepoint = None;
+                self.previewed = None;
                 if !visible {
-                    self.search_text = "".into();
-                    iced::Command::none()
+                    self.set_search_text(String::new());
+                    self.restore_scroll_offset()
                 } else {
                     iced::widget::text_input::focus(iced::widget::text_input::Id::new(
                         SEARCH_TEXT_INPUT_ID,
                     ))
                 }
             }
-            MyMessage::Codepoint(codepoint) => {
-                self.codepoint = codepoint;
+            MyMessage::Preview(Some(name)) if self.modifiers.command() => {
+                // NOTE: Ctrl-clicking (Cmd-clicking on macOS) toggles the icon in the
+                // multi-selection instead of previewing it.
+                if !self.selection.remove(&name) {
+                    self.selection.insert(name);
+                }
+                iced::Command::none()
+            }
+            MyMessage::Preview(name) => {
+                // NOTE: Opening or closing the preview changes the number of items per row in grid
+                // mode, which would otherwise move the clicked (or closed) item out of view.
+                let anchor = name
+                    .as_deref()
+                    .or(self.previewed.as_deref())
+                    .and_then(|name| self.item_anchor(name));
+                if let Some(name) = &name {
+                    self.focused_item = self.visible_index(name);
+                } else if self.focus == FocusArea::Preview {
+                    self.focus = FocusArea::Items;
+                }
+                self.previewed = name;
+                self.export_status = None;
+                self.scroll_to_anchor(anchor)
+            }
+            MyMessage::FavoritesLoaded(path, r) => {
+                match r {
+                    Ok(favorites) => {
+                        self.favorites = favorites;
+                        self.favorites_path = Some(path);
+                        self.refresh_visible_items();
+                    }
+                    // NOTE: Saving stays disabled (`favorites_path` is `None`), so that a file
+                    // that couldn't be read doesn't get overwritten.
+                    Err(e) => self.config_errors.push(format!(
+                        "Failed to load favorites from {}: {}",
+                        path.display(),
+                        e
+                    )),
+                }
+                iced::Command::none()
+            }
+            MyMessage::CustomSnippetsLoaded(path, r) => {
+                match r {
+                    Ok(snippets) => self.custom_snippets = snippets,
+                    Err(e) => {
+                        self.custom_snippets_error =
+                            Some(format!("Failed to load {}: {}", path.display(), e))
+                    }
+                }
+                iced::Command::none()
+            }
+            MyMessage::ToggleFavorite(name) => {
+                self.favorites.toggle(&name);
+                if self.section == Section::Favorites {
+                    self.refresh_visible_items();
+                }
+                match &self.favorites_path {
+                    Some(path) => {
+                        iced::Command::perform(self.favorites.clone().save(path.clone()), |r| {
+                            MyMessage::FavoritesSaved(r.map_err(|e| e.to_string()))
+                        })
+                    }
+                    None => iced::Command::none(),
+                }
+            }
+            MyMessage::FavoritesSaved(r) => {
+                if let Err(e) = r {
+                    self.config_errors
+                        .push(format!("Failed to save favorites: {}", e));
+                }
+                iced::Command::none()
+            }
+            MyMessage::CollectionsLoaded(path, r) => {
+                match r {
+                    Ok(collections) => {
+                        self.collections = collections;
+                        self.collections_path = Some(path);
+                        self.refresh_visible_items();
+                    }
+                    // NOTE: As with favorites, saving stays disabled.
+                    Err(e) => self.config_errors.push(format!(
+                        "Failed to load collections from {}: {}",
+                        path.display(),
+                        e
+                    )),
+                }
+                iced::Command::none()
+            }
+            MyMessage::CollectionsSaved(r) => {
+                if let Err(e) = r {
+                    self.config_errors
+                        .push(format!("Failed to save collections: {}", e));
+                }
+                iced::Command::none()
+            }
+            MyMessage::DismissConfigErrors => {
+                self.config_errors.clear();
+                iced::Command::none()
+            }
+            MyMessage::NewCollectionName(name) => {
+                self.new_collection_name = name;
+                self.collection_error = None;
+                iced::Command::none()
+            }
+            MyMessage::CreateCollection => {
+                let name = self.new_collection_name.trim().to_string();
+                if name.is_empty() {
+                    return iced::Command::none();
+                }
+                if !self.collections.create(&name) {
+                    self.collection_error = Some(format!("\"{}\" already exists", name));
+                    return iced::Command::none();
+                }
+                self.new_collection_name.clear();
+                self.collection_error = None;
+                self.save_collections()
+            }
+            MyMessage::AddToCollection(name, icons) => {
+                let Some(collection) = self.collections.get_mut(&name) else {
+                    return iced::Command::none();
+                };
+                for icon in icons {
+                    collection.add(icon);
+                }
+                self.selection.clear();
+                if self.section == Section::Collection(name) {
+                    self.refresh_visible_items();
+                }
+                self.save_collections()
+            }
+            MyMessage::RemoveFromCollection(name, icon) => {
+                let Some(collection) = self.collections.get_mut(&name) else {
+                    return iced::Command::none();
+                };
+                collection.remove(&icon);
+                if self.section == Section::Collection(name) {
+                    self.refresh_visible_items();
+                }
+                self.save_collections()
+            }
+            MyMessage::DeleteCollection(name) => {
+                self.collections.remove(&name);
+                if self.section == Section::Collection(name) {
+                    self.section_scroll_offsets.remove(&self.section);
+                    return iced::Command::batch(vec![
+                        self.update(MyMessage::Section(Section::All)),
+                        self.save_collections(),
+                    ]);
+                }
+                self.save_collections()
+            }
+            MyMessage::ExportCollection(name) => match self.collections.get(&name) {
+                Some(collection) => iced::Command::perform(
+                    export_collection(collection.clone()),
+                    MyMessage::CollectionExported,
+                ),
+                None => iced::Command::none(),
+            },
+            MyMessage::CollectionExported(r) => {
+                self.collection_error = r.err().map(|e| format!("Export failed: {}", e));
+                iced::Command::none()
+            }
+            MyMessage::ImportCollection => {
+                iced::Command::perform(import_collection(), MyMessage::CollectionImported)
+            }
+            MyMessage::CollectionImported(r) => match r {
+                None => iced::Command::none(),
+                Some(Err(e)) => {
+                    self.collection_error = Some(format!("Import failed: {}", e));
+                    iced::Command::none()
+                }
+                Some(Ok(collection)) => {
+                    self.collection_error = None;
+                    let section = Section::Collection(collection.name().clone());
+                    self.collections.import(collection);
+                    iced::Command::batch(vec![
+                        self.update(MyMessage::Section(section)),
+                        self.save_collections(),
+                    ])
+                }
+            },
+            MyMessage::DiagnosticsVisibleState(visible) => {
+                self.diagnostics_visible = visible;
+                iced::Command::none()
+            }
+            MyMessage::CompareWith => {
+                iced::Command::perform(pick_comparison(), MyMessage::ComparisonLoaded)
+            }
+            MyMessage::ComparisonLoaded(r) => match r {
+                None => iced::Command::none(),
+                Some((path, Err(e))) => {
+                    self.comparison_error = Some(format!("{}: {}", path.display(), e));
+                    iced::Command::none()
+                }
+                Some((path, Ok(meta_list))) => {
+                    self.comparison_error = None;
+                    self.comparison = Some(Comparison::new(path, meta_list, &self.meta_list));
+                    for kind in DiffKind::ALL {
+                        self.section_scroll_offsets.remove(&Section::Diff(kind));
+                    }
+                    self.update(MyMessage::Section(Section::Diff(DiffKind::Added)))
+                }
+            },
+            MyMessage::CloseComparison => {
+                self.comparison = None;
+                if matches!(self.section, Section::Diff(_)) {
+                    return self.update(MyMessage::Section(Section::All));
+                }
+                iced::Command::none()
+            }
+            MyMessage::ScanProject => {
+                iced::Command::perform(scan_project(), MyMessage::ProjectScanned)
+            }
+            MyMessage::ProjectScanned(r) => match r {
+                None => iced::Command::none(),
+                Some(Err(e)) => {
+                    self.usage_error = Some(e);
+                    iced::Command::none()
+                }
+                Some(Ok(usage)) => {
+                    self.usage_error = None;
+                    self.project_usage = Some(usage);
+                    // NOTE: The offset of a previous scan's list doesn't apply to this one.
+                    self.section_scroll_offsets.remove(&Section::Usage);
+                    self.update(MyMessage::Section(Section::Usage))
+                }
+            },
+            MyMessage::ClearSelection => {
+                self.selection.clear();
+                iced::Command::none()
+            }
+            MyMessage::Scrolled(viewport) => {
+                self.scroll_offset = viewport.absolute_offset().y;
+                if self.search_query.is_empty() {
+                    self.section_scroll_offsets
+                        .insert(self.section.clone(), self.scroll_offset);
+                }
                 iced::Command::none()
             }
             MyMessage::Search(text) => {
-                self.search_text = text;
-                self.codepoint = None;
-                iced::widget::scrollable::snap_to(
-                    iced::widget::scrollable::Id::new(SCROLLABLE_ICON_LIST_ID),
-                    iced::widget::scrollable::RelativeOffset::START,
-                )
+                self.set_search_text(text);
+                self.previewed = None;
+                self.restore_scroll_offset()
             }
-            MyMessage::Category(category) => {
-                self.selected_category = category;
-                self.codepoint = None;
+            MyMessage::Section(section) => {
+                self.section = section;
+                self.previewed = None;
                 self.search_visible = false;
-                self.search_text = "".into();
-                iced::widget::scrollable::snap_to(
-                    iced::widget::scrollable::Id::new(SCROLLABLE_ICON_LIST_ID),
-                    iced::widget::scrollable::RelativeOffset::START,
-                )
+                self.set_search_text(String::new());
+                self.restore_scroll_offset()
+            }
+            MyMessage::StateSaved(r) => match r {
+                Ok(()) => iced::window::close(),
+                // NOTE: The window stays open to show the error; closing it again doesn't retry.
+                Err(e) => {
+                    self.config_errors
+                        .push(format!("Failed to save the window state: {}", e));
+                    self.state_path = None;
+                    iced::Command::none()
+                }
+            },
+            MyMessage::FontLoaded(r) => match r {
+                Ok(()) => {
+                    self.loaded_resources_count += 1;
+                    self.restore_session_state()
+                }
+                Err(e) => {
+                    self.bundled_fonts_failed = true;
+                    self.load_error = Some((self.icon_set_paths.clone(), e));
+                    iced::Command::none()
+                }
+            },
+            MyMessage::IconSetLoaded(icon_set) => match icon_set.renamed_font_bytes.clone() {
+                None => {
+                    self.apply_icon_set(icon_set);
+                    self.restore_session_state()
+                }
+                Some(bytes) => {
+                    iced::font::load(std::borrow::Cow::from(bytes)).map(move |r| match r {
+                        Ok(()) => MyMessage::IconSetFontLoaded(icon_set.clone()),
+                        Err(e) => MyMessage::IconSetLoadFailed(
+                            icon_set.paths.clone(),
+                            LoadError::Font(format!("{:?}", e)),
+                        ),
+                    })
+                }
+            },
+            MyMessage::IconSetFontLoaded(icon_set) => {
+                self.apply_icon_set(icon_set);
+                self.restore_session_state()
             }
-            MyMessage::FontLoaded => {
-                self.loaded_resources_count += 1;
+            MyMessage::IconSetLoadFailed(paths, error) => {
+                self.load_error = Some((paths, error));
                 iced::Command::none()
             }
-            MyMessage::MetaListLoaded(meta_list) => {
-                self.meta_list = meta_list;
-                self.loaded_resources_count += 1;
+            MyMessage::LoadIconSet(paths) => {
+                self.load_error = None;
+                if !self.bundled_fonts_failed {
+                    return self.load_icon_set(paths);
+                }
+                // NOTE: Without the bundled fonts, the window would stay blank, so they are
+                // loaded again too (all of them, since the counter can't tell which one failed).
+                self.bundled_fonts_failed = false;
+                self.loaded_resources_count = 0;
+                iced::Command::batch([Self::load_bundled_fonts(), self.load_icon_set(paths)])
+            }
+            MyMessage::DismissLoadError => {
+                self.load_error = None;
                 iced::Command::none()
             }
+            MyMessage::Open => iced::Command::perform(pick_icon_set_paths(), MyMessage::OpenPicked),
+            MyMessage::OpenPicked(paths) => match paths {
+                Some(paths) => self.update(MyMessage::LoadIconSet(paths)),
+                None => iced::Command::none(),
+            },
         }
     }
 }
 
 mod copy_to_clipboard_animation {
+    use iced_material_icon_browser::export::VectorFormat;
+    use iced_material_icon_browser::snippets::Snippet;
+
     #[derive(Clone, Debug, Copy)]
     pub struct Progress(f32);
 
@@ -921,6 +3680,10 @@ mod copy_to_clipboard_animation {
         Name,
         Codepoint,
         HexCodepoint,
+        Vector(VectorFormat),
+        Snippet(Snippet),
+        /// The user-defined snippet at this index.
+        CustomSnippet(usize),
     }
 
     #[derive(Copy, Debug, Clone)]
@@ -939,7 +3702,7 @@ mod copy_to_clipboard_animation {
             self.progress.0 == 1.0
         }
 
-        fn new(copy_type: CopyType, steps: u64) -> Self {
+        pub fn new(copy_type: CopyType, steps: u64) -> Self {
             if steps == 0 {
                 panic!("Steps should be more than 0");
             }
@@ -962,6 +3725,10 @@ mod copy_to_clipboard_animation {
             Self::new(CopyType::HexCodepoint, steps)
         }
 
+        pub fn vector(format: VectorFormat, steps: u64) -> Self {
+            Self::new(CopyType::Vector(format), steps)
+        }
+
         pub fn advance(self) -> Self {
             let progress = (self.progress.0 + (1.0f32 / (self.steps as f32))).min(1.0);
             Self {
@@ -1,45 +1,125 @@
-use serde::Deserialize;
+//! The Material Icons metadata model, as found in the `*-material-icons-meta.json` files.
 
-#[derive(Clone, Debug, Deserialize)]
+use serde::{Deserialize, Serialize};
+
+use crate::index::SearchIndex;
+
+/// A single icon entry of the metadata file.
+///
+/// Its codepoint is checked when it's parsed, so that it's always a valid `char`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct MaterialFontMeta {
     name: String,
+    #[serde(default)]
+    version: u32,
+    popularity: u64,
+    #[serde(deserialize_with = "deserialize_codepoint")]
     codepoint: u32,
+    #[serde(default)]
+    unsupported_families: Vec<String>,
     categories: Vec<String>,
     tags: Vec<String>,
-    popularity: u64,
+    #[serde(default)]
+    sizes_px: Vec<u32>,
+}
+
+/// Whether `codepoint` is in a Private Use Area, where icon fonts put their glyphs.
+pub(crate) fn is_private_use(codepoint: u32) -> bool {
+    (0xe000..=0xf8ff).contains(&codepoint) || (0xf0000..=0x10fffd).contains(&codepoint)
+}
+
+/// Rejects the codepoints that aren't `char`s (surrogates, or above `U+10FFFF`).
+fn deserialize_codepoint<'de, D>(deserializer: D) -> Result<u32, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let codepoint = u32::deserialize(deserializer)?;
+    match char::from_u32(codepoint) {
+        Some(_) => Ok(codepoint),
+        None => Err(serde::de::Error::custom(format!(
+            "invalid codepoint {} (not a Unicode scalar value)",
+            codepoint
+        ))),
+    }
 }
 
 impl MaterialFontMeta {
+    /// The icon name (e.g. `arrow_back`), which is also its ligature in the font.
     pub fn name(&self) -> &String {
         &self.name
     }
 
+    /// The icon's Unicode codepoint in the font (private use area).
     pub fn codepoint(&self) -> u32 {
         self.codepoint
     }
 
+    /// The icon's design revision.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// A relative usage score; the higher, the more popular.
+    pub fn popularity(&self) -> u64 {
+        self.popularity
+    }
+
+    /// The categories (e.g. `av`, `navigation`) the icon belongs to.
+    pub fn categories(&self) -> &Vec<String> {
+        &self.categories
+    }
+
+    /// Free-form keywords describing the icon.
+    pub fn tags(&self) -> &Vec<String> {
+        &self.tags
+    }
+
+    /// The font families (e.g. `Material Icons Two Tone`) the icon is missing from.
+    pub fn unsupported_families(&self) -> &Vec<String> {
+        &self.unsupported_families
+    }
+
+    /// The pixel sizes the icon was designed for.
+    pub fn sizes_px(&self) -> &Vec<u32> {
+        &self.sizes_px
+    }
+
+    /// Whether the icon is available in `family`.
+    pub fn supports_family(&self, family: &str) -> bool {
+        !self
+            .unsupported_families
+            .iter()
+            .any(|value| value == family)
+    }
+
+    /// The icon as a `char`, to be rendered with the icon font.
     pub fn to_char(&self) -> char {
-        char::from_u32(self.codepoint).unwrap()
+        char::from_u32(self.codepoint).expect("codepoints are checked when parsed")
     }
 
+    /// The codepoint as a lowercase hex string, zero-padded to 4 digits (e.g. `e5c4`).
     pub fn to_hex_codepoint(&self) -> String {
         format!("{:04x}", self.codepoint)
     }
 
-    pub fn contains_category(&self, category: &String) -> bool {
-        self.categories.contains(category)
+    /// Whether the icon belongs to `category`.
+    pub fn contains_category(&self, category: &str) -> bool {
+        self.categories.iter().any(|value| value == category)
     }
 
-    pub fn contains_tag(&self, tag: &String) -> bool {
-        self.tags.contains(tag)
+    /// Whether the icon has exactly `tag` among its tags.
+    pub fn contains_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|value| value == tag)
     }
 
-    pub fn matches_hex_codepoint(&self, codepoint: &String) -> bool {
+    /// Whether the hex codepoint ends with `codepoint` (e.g. `5c4` matches `e5c4`).
+    pub fn matches_hex_codepoint(&self, codepoint: &str) -> bool {
         let hex_codepoint = format!("{:08x}", self.codepoint);
         hex_codepoint.ends_with(codepoint)
     }
 
-    pub fn matches_codepoint(&self, codepoint: &String) -> bool {
+    /// Whether `codepoint`, parsed as a decimal number, is the icon's codepoint.
+    pub fn matches_codepoint(&self, codepoint: &str) -> bool {
         match codepoint.parse::<u32>() {
             Err(_) => false,
             Ok(codepoint) => self.codepoint == codepoint,
@@ -47,24 +127,148 @@ impl MaterialFontMeta {
     }
 }
 
+/// The top-level fields of the metadata file, i.e. everything but the `icons` array.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MaterialFontMetaListHeader {
+    host: String,
+    asset_url_pattern: String,
+    families: Vec<String>,
+}
+
+impl MaterialFontMetaListHeader {
+    /// The host serving the icon assets (e.g. `fonts.gstatic.com`).
+    pub fn host(&self) -> &String {
+        &self.host
+    }
+
+    /// The path pattern of the icon assets, with `{family}`, `{icon}`, `{version}` and
+    /// `{asset}` placeholders.
+    pub fn asset_url_pattern(&self) -> &String {
+        &self.asset_url_pattern
+    }
+
+    /// The font families the metadata covers.
+    pub fn families(&self) -> &Vec<String> {
+        &self.families
+    }
+
+    /// Builds the download URL of an asset (e.g. `baseline_home_black_24dp.png`) by
+    /// filling in the placeholders of `asset_url_pattern`.
+    pub fn asset_url(&self, family: &str, item: &MaterialFontMeta, asset: &str) -> String {
+        let family = family.to_lowercase().replace(' ', "");
+        let path = self
+            .asset_url_pattern
+            .replace("{family}", &family)
+            .replace("{icon}", &item.name)
+            .replace("{version}", &item.version.to_string())
+            .replace("{asset}", asset);
+        format!("https://{}{}", self.host, path)
+    }
+}
+
+/// The parsed metadata file: its header, the icons and the categories derived from them.
 #[derive(Clone, Debug)]
 pub struct MaterialFontMetaList {
+    header: MaterialFontMetaListHeader,
     items: Vec<MaterialFontMeta>,
     categories: Vec<String>,
     category_codepoints: Vec<u32>,
+    max_popularity: u64,
+    index: SearchIndex,
 }
 
-#[derive(Debug)]
+/// Why a metadata, font or configuration file could not be loaded.
+#[derive(Clone, Debug)]
 pub enum LoadError {
-    // IO(std::io::Error),
-    Serde(String),
+    /// The file could not be read.
+    IO(std::sync::Arc<std::io::Error>),
+    /// The file is not valid JSON.
+    #[allow(missing_docs)]
+    Json {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// The file is valid JSON, but doesn't look like Material Icons metadata.
+    Schema(String),
+    /// The file is valid JSON (or TOML), but isn't a valid configuration file (favorites,
+    /// collections, snippets or state) or exported collection.
+    Invalid(String),
+    /// The file is not valid TOML (or doesn't have the expected fields).
+    Toml(String),
+    /// The font file was rejected.
+    Font(String),
 }
 
-// impl std::convert::From<std::io::Error> for LoadError {
-//     fn from(value: std::io::Error) -> Self {
-//         Self::IO(value)
-//     }
-// }
+impl std::convert::From<std::io::Error> for LoadError {
+    fn from(value: std::io::Error) -> Self {
+        Self::IO(std::sync::Arc::new(value))
+    }
+}
+
+impl std::convert::From<serde_json::Error> for LoadError {
+    fn from(value: serde_json::Error) -> Self {
+        match value.classify() {
+            serde_json::error::Category::Io => {
+                Self::IO(std::sync::Arc::new(std::io::Error::from(value)))
+            }
+            serde_json::error::Category::Syntax | serde_json::error::Category::Eof => {
+                // NOTE: `serde_json` appends the location to its messages, but we want to
+                // report it separately.
+                let message = value.to_string();
+                let location = format!(" at line {} column {}", value.line(), value.column());
+                Self::Json {
+                    line: value.line(),
+                    column: value.column(),
+                    message: message
+                        .strip_suffix(&location)
+                        .map(String::from)
+                        .unwrap_or(message),
+                }
+            }
+            serde_json::error::Category::Data => Self::Schema(value.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IO(e) => write!(f, "The file could not be read: {}", e),
+            Self::Json {
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "Invalid JSON at line {}, column {}: {}",
+                line, column, message
+            ),
+            Self::Schema(message) => write!(
+                f,
+                "The file does not look like Material Icons metadata: {}",
+                message
+            ),
+            Self::Invalid(message) => write!(f, "The file is invalid: {}", message),
+            Self::Toml(message) => write!(f, "Invalid TOML: {}", message),
+            Self::Font(message) => write!(f, "The font was rejected: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl LoadError {
+    /// Converts an error parsing a configuration file, whose unexpected contents are reported as
+    /// [`Invalid`](Self::Invalid) rather than as [`Schema`](Self::Schema) (i.e. metadata) errors.
+    pub(crate) fn from_config_json(value: serde_json::Error) -> Self {
+        match Self::from(value) {
+            Self::Schema(message) => Self::Invalid(message),
+            e => e,
+        }
+    }
+}
 
 impl Default for MaterialFontMetaList {
     fn default() -> Self {
@@ -73,55 +277,179 @@ impl Default for MaterialFontMetaList {
 }
 
 impl MaterialFontMetaList {
+    /// A list without any icons.
     pub fn empty() -> Self {
         Self {
+            header: Default::default(),
             items: vec![],
             categories: vec![],
             category_codepoints: vec![],
+            max_popularity: 0,
+            index: SearchIndex::default(),
         }
     }
 
+    /// The top-level fields of the metadata file.
+    pub fn header(&self) -> &MaterialFontMetaListHeader {
+        &self.header
+    }
+
+    /// Returns the families (listed in the header) in which `item` is not available.
+    pub fn missing_families(&self, item: &MaterialFontMeta) -> Vec<String> {
+        self.header
+            .families
+            .iter()
+            .filter(|family| !item.supports_family(family))
+            .cloned()
+            .collect()
+    }
+
+    /// All icons, in file order.
     pub fn items(&self) -> &Vec<MaterialFontMeta> {
         &self.items
     }
 
+    /// The first icon having `codepoint`, if any, which is the canonical one when several icons
+    /// share the codepoint (see [`aliases`](Self::aliases)).
     pub fn get_item(&self, codepoint: u32) -> Option<&MaterialFontMeta> {
-        self.items.iter().find(|item| item.codepoint == codepoint)
+        self.index
+            .position_by_codepoint(codepoint)
+            .map(|position| &self.items[position])
     }
 
+    /// The other names of the glyph of `item`, i.e. the icons sharing its codepoint (in file
+    /// order), the first icon of which is the canonical one.
+    ///
+    /// ```
+    /// use iced_material_icon_browser::MaterialFontMetaList;
+    ///
+    /// let list = MaterialFontMetaList::bundled().unwrap();
+    /// let item = list.get_item_by_name("arrow_back").unwrap();
+    /// assert!(list.aliases(item).is_empty());
+    /// assert!(list.is_canonical(item));
+    /// ```
+    pub fn aliases(&self, item: &MaterialFontMeta) -> Vec<&MaterialFontMeta> {
+        self.index
+            .positions_by_codepoint(item.codepoint)
+            .iter()
+            .map(|position| &self.items[*position])
+            .filter(|alias| alias.name != item.name)
+            .collect()
+    }
+
+    /// Whether `item` is the canonical icon of its codepoint (the first one in file order), rather
+    /// than an alias.
+    pub fn is_canonical(&self, item: &MaterialFontMeta) -> bool {
+        self.get_item(item.codepoint)
+            .map(|canonical| canonical.name == item.name)
+            .unwrap_or(true)
+    }
+
+    /// Whether some icons are aliases of others.
+    pub fn has_aliases(&self) -> bool {
+        self.index.codepoint_count() < self.items.len()
+    }
+
+    /// The lookup tables built from the icons.
+    pub fn index(&self) -> &SearchIndex {
+        &self.index
+    }
+
+    /// The popularity of `item` relative to the most popular icon of the list, on a logarithmic
+    /// scale from `0.0` to `1.0` (popularities span several orders of magnitude).
+    pub fn normalized_popularity(&self, item: &MaterialFontMeta) -> f32 {
+        if self.max_popularity == 0 {
+            return 0.0;
+        }
+        ((item.popularity as f64).ln_1p() / (self.max_popularity as f64).ln_1p()) as f32
+    }
+
+    /// The icon named `name`, if any.
+    pub fn get_item_by_name(&self, name: &str) -> Option<&MaterialFontMeta> {
+        self.index
+            .position_by_name(name)
+            .map(|position| &self.items[position])
+    }
+
+    /// Finds an icon by name or by codepoint, the latter being either decimal (`58820`) or
+    /// hexadecimal (`e5c4`, `0xe5c4`, `U+E5C4`). Names take precedence, since some of them
+    /// (e.g. `10k`) look like numbers.
+    pub fn lookup(&self, key: &str) -> Option<&MaterialFontMeta> {
+        if let Some(item) = self.get_item_by_name(key) {
+            return Some(item);
+        }
+        let hex = key
+            .strip_prefix("0x")
+            .or_else(|| key.strip_prefix("U+"))
+            .or_else(|| key.strip_prefix("u+"));
+        let codepoint = match hex {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => key
+                .parse::<u32>()
+                .ok()
+                .or_else(|| u32::from_str_radix(key, 16).ok())?,
+        };
+        self.get_item(codepoint)
+    }
+
+    /// The sorted, deduplicated categories of all icons.
     pub fn categories(&self) -> &Vec<String> {
         &self.categories
     }
 
+    /// For each entry of `categories`, the codepoint of its most popular icon.
     pub fn category_codepoints(&self) -> &Vec<u32> {
         &self.category_codepoints
     }
 
+    /// Parses the metadata bundled with the crate (see [`crate::resources`]).
+    pub fn bundled() -> Result<Self, LoadError> {
+        Self::load_from_bytes(crate::resources::ICONS_META_BYTES)
+    }
+
+    /// Reads and parses a metadata file.
+    pub async fn load_from_path(path: impl AsRef<std::path::Path>) -> Result<Self, LoadError> {
+        let bytes = tokio::fs::read(path).await?;
+        Self::load_from_bytes(bytes)
+    }
+
+    /// The blocking version of [`Self::load_from_path`], for use outside of an async runtime.
+    pub fn load_from_path_blocking(path: impl AsRef<std::path::Path>) -> Result<Self, LoadError> {
+        let bytes = std::fs::read(path)?;
+        Self::load_from_bytes(bytes)
+    }
+
+    /// [`Self::load_from_bytes`], wrapped in a future.
     pub async fn load_from_bytes_fake_async(
         bytes: impl Into<std::borrow::Cow<'static, [u8]>>,
     ) -> Result<Self, LoadError> {
         Self::load_from_bytes(bytes)
     }
 
+    /// Parses the content of a metadata file.
     pub fn load_from_bytes(
         bytes: impl Into<std::borrow::Cow<'static, [u8]>>,
     ) -> Result<Self, LoadError> {
-        let value = match serde_json::from_slice::<serde_json::Value>(&bytes.into()) {
-            Ok(value) => value,
-            Err(e) => return Err(LoadError::Serde(e.to_string())),
-        };
-
+        let value = serde_json::from_slice::<serde_json::Value>(&bytes.into())?;
         Self::parse_json_value(value)
     }
 
-    fn parse_json_value(value: serde_json::Value) -> Result<Self, LoadError> {
-        let icons = value["icons"].clone();
-
-        let items = match serde_json::from_value::<Vec<MaterialFontMeta>>(icons) {
-            Ok(v) => v,
-            Err(e) => return Err(LoadError::Serde(e.to_string())),
+    fn parse_json_value(mut value: serde_json::Value) -> Result<Self, LoadError> {
+        let icons = match value.get_mut("icons") {
+            Some(icons) => icons.take(),
+            None => return Err(LoadError::Schema("missing `icons` array".into())),
         };
 
+        let items = serde_json::from_value::<Vec<MaterialFontMeta>>(icons)?;
+
+        // NOTE: `icons` was taken out of `value` above, so only the header fields are left.
+        let header = serde_json::from_value::<MaterialFontMetaListHeader>(value)?;
+
+        Ok(Self::from_items(header, items))
+    }
+
+    /// Builds a list from already parsed icons, deriving the categories from them.
+    pub fn from_items(header: MaterialFontMetaListHeader, items: Vec<MaterialFontMeta>) -> Self {
         let categories = {
             let mut values = items
                 .iter()
@@ -149,10 +477,111 @@ impl MaterialFontMetaList {
             })
             .collect::<Vec<u32>>();
 
-        Ok(Self {
+        let max_popularity = items.iter().map(|item| item.popularity).max().unwrap_or(0);
+        let index = SearchIndex::build(&items);
+
+        Self {
+            header,
             items,
             categories,
             category_codepoints,
-        })
+            max_popularity,
+            index,
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A list of `icons` (names and codepoints), built with [`MaterialFontMetaList::from_items`].
+    pub(crate) fn list(icons: &[(&str, u32)]) -> MaterialFontMetaList {
+        let items = icons
+            .iter()
+            .map(|(name, codepoint)| {
+                serde_json::json!({
+                    "name": name,
+                    "popularity": 0,
+                    "codepoint": codepoint,
+                    "categories": ["action"],
+                    "tags": [],
+                })
+            })
+            .collect::<Vec<serde_json::Value>>();
+        let items = serde_json::from_value(serde_json::Value::Array(items)).unwrap();
+        MaterialFontMetaList::from_items(Default::default(), items)
+    }
+
+    /// The bundled list, parsed once for all tests.
+    pub(crate) fn bundled() -> &'static MaterialFontMetaList {
+        static LIST: std::sync::OnceLock<MaterialFontMetaList> = std::sync::OnceLock::new();
+        LIST.get_or_init(|| MaterialFontMetaList::bundled().unwrap())
+    }
+
+    /// `home` with the `house` and `cottage` aliases, and `search`.
+    pub(crate) fn aliased_list() -> MaterialFontMetaList {
+        list(&[
+            ("home", 0xe88a),
+            ("search", 0xe8b6),
+            ("house", 0xe88a),
+            ("cottage", 0xe88a),
+        ])
+    }
+
+    fn names(items: Vec<&MaterialFontMeta>) -> Vec<&str> {
+        items.iter().map(|item| item.name().as_str()).collect()
+    }
+
+    #[test]
+    fn aliases() {
+        let list = aliased_list();
+        let item = |name| list.get_item_by_name(name).unwrap();
+        assert_eq!(names(list.aliases(item("home"))), ["house", "cottage"]);
+        assert_eq!(names(list.aliases(item("house"))), ["home", "cottage"]);
+        assert_eq!(names(list.aliases(item("cottage"))), ["home", "house"]);
+        assert!(list.aliases(item("search")).is_empty());
+        assert_eq!(list.get_item(0xe88a).unwrap().name(), "home");
+    }
+
+    #[test]
+    fn is_canonical() {
+        let list = aliased_list();
+        let item = |name| list.get_item_by_name(name).unwrap();
+        assert!(list.is_canonical(item("home")));
+        assert!(!list.is_canonical(item("house")));
+        assert!(!list.is_canonical(item("cottage")));
+        assert!(list.is_canonical(item("search")));
+    }
+
+    #[test]
+    fn has_aliases() {
+        assert!(aliased_list().has_aliases());
+        assert!(!list(&[("home", 0xe88a), ("search", 0xe8b6)]).has_aliases());
+        assert!(!list(&[]).has_aliases());
+    }
+
+    fn icons_json(codepoint: u32) -> Vec<u8> {
+        format!(
+            r#"{{"icons": [{{"name": "bad", "popularity": 0, "codepoint": {}, "categories": [], "tags": []}}]}}"#,
+            codepoint
+        )
+        .into_bytes()
+    }
+
+    #[test]
+    fn invalid_codepoints_are_rejected() {
+        assert!(MaterialFontMetaList::load_from_bytes(icons_json(0xe88a)).is_ok());
+        for codepoint in [0xd800, 0x110000] {
+            assert!(matches!(
+                MaterialFontMetaList::load_from_bytes(icons_json(codepoint)),
+                Err(LoadError::Schema(_))
+            ));
+            let item = format!(
+                r#"{{"name": "bad", "popularity": 0, "codepoint": {}, "categories": [], "tags": []}}"#,
+                codepoint
+            );
+            assert!(serde_json::from_str::<MaterialFontMeta>(&item).is_err());
+        }
     }
 }
@@ -0,0 +1,207 @@
+//! Filtering of a [`MaterialFontMetaList`], as done by the browser's sidebar and search box.
+
+use crate::models::{MaterialFontMeta, MaterialFontMetaList};
+use crate::query_language::SearchQuery;
+
+/// Selects icons by category and/or name (e.g. favorites) or, when the search is not empty, by
+/// search (in which case the category and names are ignored, like "Search All" in the browser).
+///
+/// ```
+/// use iced_material_icon_browser::{MaterialFontMetaList, Query, SearchQuery};
+///
+/// let list = MaterialFontMetaList::bundled().unwrap();
+/// let items = Query::new().search_text("arrow back").run(&list);
+/// assert_eq!(items[0].name(), "arrow_back");
+///
+/// let search = SearchQuery::parse("name:/^arrow_/ -tag:navigation cp:e5c4..e5ff").unwrap();
+/// let items = Query::new().search(search).run(&list);
+/// assert!(items.iter().all(|item| item.name().starts_with("arrow_")));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Query {
+    category: Option<String>,
+    names: Option<std::collections::HashSet<String>>,
+    search: SearchQuery,
+    collapse_aliases: bool,
+}
+
+impl Query {
+    /// A query matching all icons.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restricts the query to the icons of `category` (`None` meaning all categories).
+    pub fn category(self, category: Option<String>) -> Self {
+        Self { category, ..self }
+    }
+
+    /// Restricts the query to the icons named in `names` (`None` meaning all icons).
+    pub fn names(self, names: Option<std::collections::HashSet<String>>) -> Self {
+        Self { names, ..self }
+    }
+
+    /// Restricts the query to the icons matching `search_text` fuzzily, without interpreting
+    /// any query syntax (see [`SearchText::score`](crate::SearchText::score)).
+    pub fn search_text(self, search_text: impl AsRef<str>) -> Self {
+        self.search(SearchQuery::plain(search_text.as_ref()))
+    }
+
+    /// Restricts the query to the icons matching `search` (see [`SearchQuery::parse`]).
+    pub fn search(self, search: SearchQuery) -> Self {
+        Self { search, ..self }
+    }
+
+    /// Whether icons sharing a codepoint are returned once, as the canonical icon (see
+    /// [`MaterialFontMetaList::aliases`]), in place of the first of them that is selected.
+    pub fn collapse_aliases(self, collapse_aliases: bool) -> Self {
+        Self {
+            collapse_aliases,
+            ..self
+        }
+    }
+
+    /// Whether `item` is selected by the query (aliases aside).
+    pub fn matches(&self, item: &MaterialFontMeta) -> bool {
+        if self.search.is_empty() {
+            if let Some(category) = &self.category {
+                if !item.contains_category(category) {
+                    return false;
+                }
+            }
+            if let Some(names) = &self.names {
+                if !names.contains(item.name()) {
+                    return false;
+                }
+            }
+            return true;
+        }
+
+        if !self.search.matches_filters(item) {
+            return false;
+        }
+        let text = self.search.text();
+        text.is_empty() || text.score(item, 0.0).is_some()
+    }
+
+    /// The icons of `list` selected by the query: in list order when browsing a category, from
+    /// most to least relevant when searching, or from most to least popular when only filtering.
+    pub fn run<'a>(&self, list: &'a MaterialFontMetaList) -> Vec<&'a MaterialFontMeta> {
+        self.run_positions(list)
+            .into_iter()
+            .map(|position| &list.items()[position])
+            .collect()
+    }
+
+    /// Like [`Query::run`], but returns the positions of the icons in [`MaterialFontMetaList::items`],
+    /// which (unlike references) can be kept alongside the list, e.g. to cache the result.
+    pub fn run_positions(&self, list: &MaterialFontMetaList) -> Vec<usize> {
+        let positions = self.select_positions(list);
+        if !self.collapse_aliases || !list.has_aliases() {
+            return positions;
+        }
+        let items = list.items();
+        let mut seen = std::collections::HashSet::new();
+        positions
+            .into_iter()
+            .map(|position| {
+                list.index()
+                    .position_by_codepoint(items[position].codepoint())
+                    .unwrap_or(position)
+            })
+            .filter(|position| seen.insert(*position))
+            .collect()
+    }
+
+    fn select_positions(&self, list: &MaterialFontMetaList) -> Vec<usize> {
+        let items = list.items();
+        if self.search.is_empty() {
+            let positions = match &self.category {
+                Some(category) => list.index().category_positions(category).to_vec(),
+                None => (0..items.len()).collect(),
+            };
+            return match &self.names {
+                Some(names) => positions
+                    .into_iter()
+                    .filter(|position| names.contains(items[*position].name()))
+                    .collect(),
+                None => positions,
+            };
+        }
+
+        let text = self.search.text();
+        let candidates = if text.is_empty() {
+            (0..items.len()).collect()
+        } else {
+            text.candidates(list)
+        };
+        let mut scored = candidates
+            .into_iter()
+            .filter(|position| self.search.matches_filters(&items[*position]))
+            .filter_map(|position| {
+                let item = &items[position];
+                let popularity = list.normalized_popularity(item);
+                if text.is_empty() {
+                    return Some((position, popularity));
+                }
+                text.score(item, popularity).map(|score| (position, score))
+            })
+            .collect::<Vec<(usize, f32)>>();
+        scored.sort_by(|(a, a_score), (b, b_score)| {
+            b_score
+                .total_cmp(a_score)
+                .then_with(|| items[*a].name().cmp(items[*b].name()))
+        });
+        scored.into_iter().map(|(position, _)| position).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::tests::aliased_list;
+
+    fn search(query: &str) -> Query {
+        Query::new().search(SearchQuery::parse(query).unwrap())
+    }
+
+    /// The positions of `query`, sorted (searches being ordered by relevance).
+    fn sorted_positions(query: Query, list: &MaterialFontMetaList) -> Vec<usize> {
+        let mut positions = query.run_positions(list);
+        positions.sort();
+        positions
+    }
+
+    #[test]
+    fn run_positions_uncollapsed() {
+        let list = aliased_list();
+        assert_eq!(Query::new().run_positions(&list), [0, 1, 2, 3]);
+        assert_eq!(search("name:house").run_positions(&list), [2]);
+        assert_eq!(
+            sorted_positions(search("name:/^(cottage|house)$/"), &list),
+            [2, 3]
+        );
+    }
+
+    #[test]
+    fn run_positions_collapsed() {
+        let list = aliased_list();
+        let collapsed = |query: Query| query.collapse_aliases(true).run_positions(&list);
+        assert_eq!(collapsed(Query::new()), [0, 1]);
+        // Aliases stand for their canonical icon, which is listed once.
+        assert_eq!(collapsed(search("name:house")), [0]);
+        assert_eq!(collapsed(search("name:/^(cottage|house)$/")), [0]);
+        assert_eq!(
+            sorted_positions(
+                search("name:/^(search|cottage)$/").collapse_aliases(true),
+                &list
+            ),
+            [0, 1]
+        );
+        assert_eq!(
+            collapsed(Query::new().names(Some(["cottage".to_string()].into()))),
+            [0]
+        );
+        assert!(collapsed(search("name:missing")).is_empty());
+    }
+}
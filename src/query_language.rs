@@ -0,0 +1,692 @@
+//! A small query language for the search box, combining fuzzy words with field filters:
+//!
+//! ```text
+//! arrow tag:navigation -tag:outline cat:"av" pop:>1000 cp:e5c4..e5ff name:/^arrow_/
+//! ```
+//!
+//! * Bare words are matched fuzzily (see [`crate::SearchText`]); `-word` excludes the icons
+//!   whose name contains `word` or which have `word` as a tag.
+//! * `name:` (contains), `tag:` and `cat:` (equals) take a word, a `"quoted value"` or a
+//!   `/regular expression/`, and are case-insensitive.
+//! * `pop:` (decimal) and `cp:` (hexadecimal) take a number, a comparison (`>N`, `>=N`, `<N`,
+//!   `<=N`, `=N`) or an inclusive range (`N..M`).
+//! * Any filter can be negated with a leading `-`.
+
+use crate::models::MaterialFontMeta;
+use crate::search::SearchText;
+
+/// The part of an icon a [`Filter`] looks at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Field {
+    /// The icon name (`name:`).
+    Name,
+    /// The icon tags (`tag:`).
+    Tag,
+    /// The icon categories (`cat:`).
+    Category,
+    /// The icon popularity (`pop:`).
+    Popularity,
+    /// The icon codepoint (`cp:`).
+    Codepoint,
+    /// The name or the tags (negated bare words).
+    Keyword,
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "name" | "n" => Some(Self::Name),
+            "tag" | "t" => Some(Self::Tag),
+            "cat" | "category" | "c" => Some(Self::Category),
+            "pop" | "popularity" | "p" => Some(Self::Popularity),
+            "cp" | "codepoint" => Some(Self::Codepoint),
+            _ => None,
+        }
+    }
+
+    fn is_numeric(&self) -> bool {
+        matches!(self, Self::Popularity | Self::Codepoint)
+    }
+}
+
+/// What a [`Filter`] expects from its field.
+#[derive(Clone, Debug)]
+pub enum Predicate {
+    /// A case-insensitive text (contained in names, equal to tags and categories).
+    Text(String),
+    /// A regular expression.
+    Pattern(regex::Regex),
+    /// A number comparison.
+    Compare(std::cmp::Ordering, bool, u64),
+    /// An inclusive number range.
+    Range(u64, u64),
+}
+
+impl Predicate {
+    fn matches_text(&self, value: &str, contains: bool) -> bool {
+        match self {
+            Self::Text(text) => {
+                let value = value.to_lowercase();
+                if contains {
+                    value.contains(text.as_str())
+                } else {
+                    value == *text
+                }
+            }
+            Self::Pattern(regex) => regex.is_match(value),
+            Self::Compare(..) | Self::Range(..) => false,
+        }
+    }
+
+    fn matches_number(&self, value: u64) -> bool {
+        match self {
+            Self::Compare(ordering, or_equal, number) => {
+                let actual = value.cmp(number);
+                actual == *ordering || (*or_equal && actual == std::cmp::Ordering::Equal)
+            }
+            Self::Range(start, end) => (*start..=*end).contains(&value),
+            Self::Text(_) | Self::Pattern(_) => false,
+        }
+    }
+}
+
+/// A single `field:value` term of a query.
+#[derive(Clone, Debug)]
+pub struct Filter {
+    field: Field,
+    predicate: Predicate,
+    negated: bool,
+}
+
+impl Filter {
+    /// The field the filter looks at.
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    /// Whether the filter excludes the icons it matches.
+    pub fn is_negated(&self) -> bool {
+        self.negated
+    }
+
+    /// Whether `item` passes the filter.
+    pub fn matches(&self, item: &MaterialFontMeta) -> bool {
+        let predicate = &self.predicate;
+        let matches = match self.field {
+            Field::Name => predicate.matches_text(item.name(), true),
+            Field::Tag => item
+                .tags()
+                .iter()
+                .any(|tag| predicate.matches_text(tag, false)),
+            Field::Category => item
+                .categories()
+                .iter()
+                .any(|category| predicate.matches_text(category, false)),
+            Field::Keyword => {
+                predicate.matches_text(item.name(), true)
+                    || item
+                        .tags()
+                        .iter()
+                        .any(|tag| predicate.matches_text(tag, false))
+            }
+            Field::Popularity => predicate.matches_number(item.popularity()),
+            Field::Codepoint => predicate.matches_number(item.codepoint() as u64),
+        };
+        matches != self.negated
+    }
+}
+
+/// Why a query could not be parsed, with the location of the faulty term.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    message: String,
+    start: usize,
+    end: usize,
+}
+
+impl ParseError {
+    fn new(message: impl Into<String>, span: std::ops::Range<usize>) -> Self {
+        Self {
+            message: message.into(),
+            start: span.start,
+            end: span.end,
+        }
+    }
+
+    /// The description of the error.
+    pub fn message(&self) -> &String {
+        &self.message
+    }
+
+    /// The character range of the faulty part of the query.
+    pub fn span(&self) -> std::ops::Range<usize> {
+        self.start..self.end
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (at column {})", self.message, self.start + 1)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A parsed search: fuzzy words plus filters, all of which must match.
+#[derive(Clone, Debug, Default)]
+pub struct SearchQuery {
+    text: SearchText,
+    filters: Vec<Filter>,
+}
+
+impl SearchQuery {
+    /// A query made of fuzzy words only, without interpreting any syntax.
+    pub fn plain(text: &str) -> Self {
+        Self {
+            text: SearchText::parse(text),
+            filters: vec![],
+        }
+    }
+
+    /// Parses `input` according to the query language (see the [module](self) documentation).
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let chars = input.chars().collect::<Vec<char>>();
+        let mut words = vec![];
+        let mut filters = vec![];
+        let mut position = 0;
+        loop {
+            while position < chars.len() && chars[position].is_whitespace() {
+                position += 1;
+            }
+            if position == chars.len() {
+                break;
+            }
+            let term = Term::parse(&chars, position)?;
+            position = term.end;
+            match term.into_filter()? {
+                TermKind::Word(word) => words.push(word),
+                TermKind::Filter(filter) => filters.push(filter),
+            }
+        }
+        Ok(Self {
+            text: SearchText::parse(&words.join(" ")),
+            filters,
+        })
+    }
+
+    /// Whether there is nothing to search for.
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty() && self.filters.is_empty()
+    }
+
+    /// The fuzzy part of the query.
+    pub fn text(&self) -> &SearchText {
+        &self.text
+    }
+
+    /// The filters of the query.
+    pub fn filters(&self) -> &Vec<Filter> {
+        &self.filters
+    }
+
+    /// Whether `item` passes all filters (ignoring the fuzzy words).
+    pub fn matches_filters(&self, item: &MaterialFontMeta) -> bool {
+        self.filters.iter().all(|filter| filter.matches(item))
+    }
+}
+
+enum TermKind {
+    Word(String),
+    Filter(Filter),
+}
+
+enum TermValue {
+    Plain(String),
+    Pattern(String),
+}
+
+/// A raw `[-][field:]value` term, before interpretation.
+struct Term {
+    negated: bool,
+    field: Option<(String, std::ops::Range<usize>)>,
+    value: TermValue,
+    value_span: std::ops::Range<usize>,
+    end: usize,
+}
+
+impl Term {
+    fn parse(chars: &[char], start: usize) -> Result<Self, ParseError> {
+        let mut position = start;
+        let negated = chars[position] == '-';
+        if negated {
+            position += 1;
+        }
+
+        let mut field = None;
+        let field_end = (position..chars.len())
+            .take_while(|&i| chars[i].is_alphanumeric() || chars[i] == '_')
+            .last()
+            .map(|i| i + 1);
+        if let Some(field_end) = field_end {
+            if field_end < chars.len() && chars[field_end] == ':' {
+                let name = chars[position..field_end].iter().collect::<String>();
+                field = Some((name, position..field_end));
+                position = field_end + 1;
+            }
+        }
+
+        let value_start = position;
+        let value = match chars.get(position) {
+            Some('"') => {
+                let (text, end) = Self::read_delimited(chars, position, '"')?;
+                position = end;
+                TermValue::Plain(text)
+            }
+            Some('/') if field.is_some() => {
+                let (text, end) = Self::read_delimited(chars, position, '/')?;
+                position = end;
+                TermValue::Pattern(text)
+            }
+            _ => {
+                let end = (position..chars.len())
+                    .find(|&i| chars[i].is_whitespace())
+                    .unwrap_or(chars.len());
+                let text = chars[position..end].iter().collect::<String>();
+                position = end;
+                TermValue::Plain(text)
+            }
+        };
+        if position < chars.len() && !chars[position].is_whitespace() {
+            return Err(ParseError::new(
+                "expected a space after the closing delimiter",
+                position..position + 1,
+            ));
+        }
+
+        Ok(Self {
+            negated,
+            field,
+            value,
+            value_span: value_start..position,
+            end: position,
+        })
+    }
+
+    /// Reads a value enclosed in `delimiter`s (which can be escaped with `\`), returning it
+    /// along with the position following the closing delimiter.
+    fn read_delimited(
+        chars: &[char],
+        start: usize,
+        delimiter: char,
+    ) -> Result<(String, usize), ParseError> {
+        let mut text = String::new();
+        let mut position = start + 1;
+        while position < chars.len() {
+            match chars[position] {
+                '\\' if chars.get(position + 1) == Some(&delimiter) => {
+                    text.push(delimiter);
+                    position += 2;
+                }
+                c if c == delimiter => return Ok((text, position + 1)),
+                c => {
+                    text.push(c);
+                    position += 1;
+                }
+            }
+        }
+        Err(ParseError::new(
+            format!("missing closing `{}`", delimiter),
+            start..chars.len(),
+        ))
+    }
+
+    fn into_filter(self) -> Result<TermKind, ParseError> {
+        let (name, field_span) = match self.field {
+            None => {
+                let TermValue::Plain(word) = self.value else {
+                    unreachable!("patterns are only read after a field")
+                };
+                if !self.negated {
+                    return Ok(TermKind::Word(word));
+                }
+                if word.is_empty() {
+                    return Err(ParseError::new("missing word after `-`", self.value_span));
+                }
+                return Ok(TermKind::Filter(Filter {
+                    field: Field::Keyword,
+                    predicate: Predicate::Text(word.to_lowercase()),
+                    negated: true,
+                }));
+            }
+            Some(field) => field,
+        };
+
+        let field = Field::parse(&name).ok_or_else(|| {
+            ParseError::new(
+                format!(
+                    "unknown field `{}` (expected name, tag, cat, pop or cp)",
+                    name
+                ),
+                field_span.clone(),
+            )
+        })?;
+
+        let predicate = match self.value {
+            TermValue::Plain(value) if value.is_empty() => {
+                return Err(ParseError::new(
+                    format!("missing value after `{}:`", name),
+                    field_span.start..self.value_span.end,
+                ));
+            }
+            TermValue::Pattern(_) if field.is_numeric() => {
+                return Err(ParseError::new(
+                    format!("`{}:` expects a number, not a pattern", name),
+                    self.value_span,
+                ));
+            }
+            TermValue::Pattern(pattern) => {
+                let regex = regex::RegexBuilder::new(&pattern)
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|e| {
+                        let message = match e {
+                            regex::Error::Syntax(message) => message
+                                .lines()
+                                .last()
+                                .unwrap_or_default()
+                                .trim_start_matches("error: ")
+                                .to_string(),
+                            e => e.to_string(),
+                        };
+                        ParseError::new(
+                            format!("invalid pattern: {}", message),
+                            self.value_span.clone(),
+                        )
+                    })?;
+                Predicate::Pattern(regex)
+            }
+            TermValue::Plain(value) if field.is_numeric() => {
+                let radix = if field == Field::Codepoint { 16 } else { 10 };
+                parse_number_predicate(&value, radix).map_err(|message| {
+                    ParseError::new(format!("`{}:` {}", name, message), self.value_span.clone())
+                })?
+            }
+            TermValue::Plain(value) => Predicate::Text(value.to_lowercase()),
+        };
+
+        Ok(TermKind::Filter(Filter {
+            field,
+            predicate,
+            negated: self.negated,
+        }))
+    }
+}
+
+fn parse_number(value: &str, radix: u32) -> Result<u64, String> {
+    let digits = if radix == 16 {
+        value
+            .strip_prefix("0x")
+            .or_else(|| value.strip_prefix("U+"))
+            .or_else(|| value.strip_prefix("u+"))
+            .unwrap_or(value)
+    } else {
+        value
+    };
+    u64::from_str_radix(digits, radix).map_err(|_| {
+        let kind = if radix == 16 {
+            "hexadecimal"
+        } else {
+            "decimal"
+        };
+        format!("expects a {} number, not `{}`", kind, value)
+    })
+}
+
+fn parse_number_predicate(value: &str, radix: u32) -> Result<Predicate, String> {
+    if let Some((start, end)) = value.split_once("..") {
+        let (start, end) = (parse_number(start, radix)?, parse_number(end, radix)?);
+        if start > end {
+            return Err(format!("range `{}` is empty", value));
+        }
+        return Ok(Predicate::Range(start, end));
+    }
+
+    let operators = [
+        (">=", std::cmp::Ordering::Greater, true),
+        ("<=", std::cmp::Ordering::Less, true),
+        (">", std::cmp::Ordering::Greater, false),
+        ("<", std::cmp::Ordering::Less, false),
+        ("=", std::cmp::Ordering::Equal, false),
+    ];
+    for (operator, ordering, or_equal) in operators {
+        if let Some(number) = value.strip_prefix(operator) {
+            return Ok(Predicate::Compare(
+                ordering,
+                or_equal,
+                parse_number(number, radix)?,
+            ));
+        }
+    }
+    Ok(Predicate::Compare(
+        std::cmp::Ordering::Equal,
+        false,
+        parse_number(value, radix)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::tests::bundled;
+
+    /// Whether the icon named `name` passes the filters of `query`.
+    fn matches(query: &str, name: &str) -> bool {
+        let item = bundled().get_item_by_name(name).unwrap();
+        SearchQuery::parse(query).unwrap().matches_filters(item)
+    }
+
+    /// The message and span of the error `query` fails with.
+    fn error(query: &str) -> (String, std::ops::Range<usize>) {
+        let e = SearchQuery::parse(query).unwrap_err();
+        (e.message().clone(), e.span())
+    }
+
+    #[test]
+    fn words() {
+        let query = SearchQuery::parse("  arrow   Back ").unwrap();
+        assert!(query.filters().is_empty());
+        assert!(!query.text().is_empty());
+        assert!(SearchQuery::parse("").unwrap().is_empty());
+        assert!(SearchQuery::parse("   ").unwrap().is_empty());
+        assert!(SearchQuery::parse("10k").unwrap().filters().is_empty());
+    }
+
+    #[test]
+    fn name_filter() {
+        assert!(matches("name:arrow", "arrow_back"));
+        assert!(matches("name:ARROW_B", "arrow_back"));
+        assert!(matches("n:back", "arrow_back"));
+        assert!(!matches("name:arrow", "home"));
+    }
+
+    #[test]
+    fn tag_and_category_filters() {
+        assert!(matches("tag:Address", "home"));
+        assert!(!matches("tag:addr", "home"));
+        assert!(matches("cat:action", "home"));
+        assert!(matches("category:NAVIGATION", "arrow_back"));
+        assert!(!matches("c:nav", "arrow_back"));
+        assert_eq!(
+            SearchQuery::parse("tag:arrow cat:navigation")
+                .unwrap()
+                .filters()
+                .iter()
+                .map(Filter::field)
+                .collect::<Vec<Field>>(),
+            vec![Field::Tag, Field::Category]
+        );
+    }
+
+    #[test]
+    fn popularity_filter() {
+        // `10k` has a popularity of 1161.
+        assert!(matches("pop:1161", "10k"));
+        assert!(matches("pop:=1161", "10k"));
+        assert!(matches("pop:>1000", "10k"));
+        assert!(!matches("pop:>1161", "10k"));
+        assert!(matches("pop:>=1161", "10k"));
+        assert!(matches("pop:<1162", "10k"));
+        assert!(!matches("pop:<1161", "10k"));
+        assert!(matches("pop:<=1161", "10k"));
+        assert!(matches("pop:1000..2000", "10k"));
+        assert!(matches("pop:1161..1161", "10k"));
+        assert!(!matches("pop:0..1160", "10k"));
+    }
+
+    #[test]
+    fn codepoint_filter() {
+        // `arrow_back` is e5c4.
+        assert!(matches("cp:e5c4", "arrow_back"));
+        assert!(matches("cp:E5C4", "arrow_back"));
+        assert!(matches("cp:0xe5c4", "arrow_back"));
+        assert!(matches("cp:U+E5C4", "arrow_back"));
+        assert!(matches("cp:e500..e5ff", "arrow_back"));
+        assert!(matches("cp:>=e5c4", "arrow_back"));
+        assert!(!matches("cp:>e5c4", "arrow_back"));
+        assert!(!matches("cp:58820", "arrow_back"));
+    }
+
+    #[test]
+    fn negation() {
+        assert!(!matches("-name:arrow", "arrow_back"));
+        assert!(matches("-name:arrow", "home"));
+        assert!(!matches("-cat:navigation", "arrow_back"));
+        assert!(!matches("-pop:>1000", "10k"));
+        // Negated words exclude names containing them, and tags equal to them.
+        assert!(!matches("-arrow", "arrow_back"));
+        assert!(!matches("-address", "home"));
+        assert!(matches("-addr", "home"));
+        let query = SearchQuery::parse("-arrow").unwrap();
+        assert!(query.text().is_empty());
+        assert_eq!(query.filters()[0].field(), Field::Keyword);
+        assert!(query.filters()[0].is_negated());
+    }
+
+    #[test]
+    fn quoting() {
+        assert!(matches(r#"cat:"navigation""#, "arrow_back"));
+        assert!(matches(r#"name:"arrow_back""#, "arrow_back"));
+        assert!(!matches(r#"name:"arrow back""#, "arrow_back"));
+        assert!(matches(r#"-name:"home" name:arrow"#, "arrow_back"));
+        let query = SearchQuery::parse(r#"name:"a\"b""#).unwrap();
+        assert!(!bundled()
+            .items()
+            .iter()
+            .any(|item| query.matches_filters(item)));
+    }
+
+    #[test]
+    fn name_patterns() {
+        assert!(matches("name:/^arrow_/", "arrow_back"));
+        assert!(matches("name:/BACK$/", "arrow_back"));
+        assert!(!matches("name:/^back/", "arrow_back"));
+        assert!(matches("tag:/^addr/", "home"));
+        assert!(matches(r"name:/^\d+k$/", "10k"));
+        assert!(matches(r"name:/a\/b|home/", "home"));
+        // Without a field, slashes are part of a word.
+        assert!(SearchQuery::parse("/arrow/").unwrap().filters().is_empty());
+    }
+
+    #[test]
+    fn unclosed_delimiters() {
+        assert_eq!(
+            error(r#"name:"arrow"#),
+            ("missing closing `\"`".into(), 5..11)
+        );
+        assert_eq!(
+            error("home name:/^arrow"),
+            ("missing closing `/`".into(), 10..17)
+        );
+    }
+
+    #[test]
+    fn text_after_closing_delimiter() {
+        assert_eq!(
+            error(r#"name:"arrow"back"#),
+            (
+                "expected a space after the closing delimiter".into(),
+                12..13
+            )
+        );
+    }
+
+    #[test]
+    fn missing_word_after_minus() {
+        assert_eq!(error("home -"), ("missing word after `-`".into(), 6..6));
+    }
+
+    #[test]
+    fn unknown_field() {
+        assert_eq!(
+            error("arrow size:24"),
+            (
+                "unknown field `size` (expected name, tag, cat, pop or cp)".into(),
+                6..10
+            )
+        );
+        assert_eq!(error("-foo:bar").1, 1..4);
+    }
+
+    #[test]
+    fn missing_value() {
+        assert_eq!(error("tag:"), ("missing value after `tag:`".into(), 0..4));
+        assert_eq!(
+            error("home -cat: x"),
+            ("missing value after `cat:`".into(), 6..10)
+        );
+    }
+
+    #[test]
+    fn pattern_for_number() {
+        assert_eq!(
+            error("pop:/1/"),
+            ("`pop:` expects a number, not a pattern".into(), 4..7)
+        );
+    }
+
+    #[test]
+    fn invalid_pattern() {
+        let (message, span) = error("name:/(/");
+        assert!(message.starts_with("invalid pattern: "), "{}", message);
+        assert_eq!(span, 5..8);
+    }
+
+    #[test]
+    fn invalid_numbers() {
+        assert_eq!(
+            error("pop:many"),
+            ("`pop:` expects a decimal number, not `many`".into(), 4..8)
+        );
+        assert_eq!(
+            error("cp:>xyz"),
+            ("`cp:` expects a hexadecimal number, not `xyz`".into(), 3..7)
+        );
+        assert_eq!(
+            error("pop:10.."),
+            ("`pop:` expects a decimal number, not ``".into(), 4..8)
+        );
+        assert_eq!(
+            error("cp:e5ff..e500"),
+            ("`cp:` range `e5ff..e500` is empty".into(), 3..13)
+        );
+    }
+
+    #[test]
+    fn display_uses_columns() {
+        let e = SearchQuery::parse("home size:24").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "unknown field `size` (expected name, tag, cat, pop or cp) (at column 6)"
+        );
+    }
+}
@@ -0,0 +1,445 @@
+//! Software rendering of glyph outlines to PNG images, so that icons can be exported at any size
+//! and color (e.g. for Android and iOS asset catalogs) without a GPU or a window.
+
+use crate::glyph::{GlyphError, GlyphOutline, Segment};
+use crate::models::MaterialFontMeta;
+
+/// The icon sizes offered by default, in pixels (before density scaling).
+pub const STANDARD_SIZES: [u32; 4] = [18, 24, 36, 48];
+
+/// An 8-bit RGBA color (not premultiplied).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rgba {
+    /// The red component.
+    pub r: u8,
+    /// The green component.
+    pub g: u8,
+    /// The blue component.
+    pub b: u8,
+    /// The opacity (`255` being opaque).
+    pub a: u8,
+}
+
+impl Rgba {
+    /// Opaque black.
+    pub const BLACK: Self = Self::opaque(0, 0, 0);
+    /// Opaque white.
+    pub const WHITE: Self = Self::opaque(255, 255, 255);
+
+    /// An opaque color.
+    pub const fn opaque(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b, a: 255 }
+    }
+
+    /// Parses a `#rrggbb` or `#rrggbbaa` color (the `#` being optional).
+    ///
+    /// ```
+    /// use iced_material_icon_browser::raster::Rgba;
+    ///
+    /// assert_eq!(Rgba::parse("#ff8000"), Some(Rgba::opaque(255, 128, 0)));
+    /// assert_eq!(Rgba::parse("00000080").map(|color| color.a), Some(128));
+    /// assert_eq!(Rgba::parse("red"), None);
+    /// ```
+    pub fn parse(s: &str) -> Option<Self> {
+        let hex = s.trim().trim_start_matches('#');
+        if !(hex.len() == 6 || hex.len() == 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let component = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        Some(Self {
+            r: component(0)?,
+            g: component(2)?,
+            b: component(4)?,
+            a: if hex.len() == 8 { component(6)? } else { 255 },
+        })
+    }
+
+    /// The color as `#rrggbb`, or `#rrggbbaa` if it isn't opaque.
+    pub fn to_hex(&self) -> String {
+        if self.a == 255 {
+            format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+        }
+    }
+}
+
+/// How an icon is rendered.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RasterOptions {
+    /// The size of the icon, in pixels.
+    pub size: u32,
+    /// The color of the icon.
+    pub foreground: Rgba,
+    /// The color of the background, which is transparent when `None`.
+    pub background: Option<Rgba>,
+    /// The space around the icon, in pixels, which adds to the size of the image.
+    pub padding: u32,
+}
+
+impl Default for RasterOptions {
+    fn default() -> Self {
+        Self {
+            size: 24,
+            foreground: Rgba::BLACK,
+            background: None,
+            padding: 0,
+        }
+    }
+}
+
+impl RasterOptions {
+    /// The options scaled by the density's factor (e.g. a 24px icon is 48px at `xhdpi`). Sizes
+    /// saturate at `u32::MAX`, which [`render_png`] rejects.
+    pub fn scaled(&self, density: Density) -> Self {
+        let scale = |value: u32| {
+            let value = (f64::from(value) * f64::from(density.scale())).round();
+            value.min(f64::from(u32::MAX)) as u32
+        };
+        Self {
+            size: scale(self.size),
+            padding: scale(self.padding),
+            ..*self
+        }
+    }
+
+    /// The width (and height) of the rendered image, or `None` when it doesn't fit in a `u32`
+    /// (the size and padding being user input).
+    pub fn image_size(&self) -> Option<u32> {
+        self.padding.checked_mul(2)?.checked_add(self.size)
+    }
+}
+
+/// A screen density that assets are generated for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Density {
+    /// Android's baseline density (~160 dpi).
+    Mdpi,
+    /// Android, 1.5x.
+    Hdpi,
+    /// Android, 2x.
+    Xhdpi,
+    /// Android, 3x.
+    Xxhdpi,
+    /// Android, 4x.
+    Xxxhdpi,
+    /// iOS, `@1x`.
+    Ios1x,
+    /// iOS, `@2x`.
+    Ios2x,
+    /// iOS, `@3x`.
+    Ios3x,
+}
+
+impl Density {
+    /// The densities of Android's `drawable-*dpi` directories.
+    pub const ANDROID: [Self; 5] = [
+        Self::Mdpi,
+        Self::Hdpi,
+        Self::Xhdpi,
+        Self::Xxhdpi,
+        Self::Xxxhdpi,
+    ];
+    /// The scale factors of iOS image sets.
+    pub const IOS: [Self; 3] = [Self::Ios1x, Self::Ios2x, Self::Ios3x];
+
+    /// The factor sizes are multiplied by.
+    pub fn scale(&self) -> f32 {
+        match self {
+            Self::Mdpi | Self::Ios1x => 1.0,
+            Self::Hdpi => 1.5,
+            Self::Xhdpi | Self::Ios2x => 2.0,
+            Self::Xxhdpi | Self::Ios3x => 3.0,
+            Self::Xxxhdpi => 4.0,
+        }
+    }
+
+    /// The path, relative to the output directory, of the image of the icon named `name`:
+    /// `drawable-xhdpi/ic_home.png` on Android, `home@2x.png` on iOS.
+    pub fn file_path(&self, name: &str) -> std::path::PathBuf {
+        let android = |bucket: &str| {
+            std::path::Path::new(&format!("drawable-{}", bucket)).join(format!("ic_{}.png", name))
+        };
+        match self {
+            Self::Mdpi => android("mdpi"),
+            Self::Hdpi => android("hdpi"),
+            Self::Xhdpi => android("xhdpi"),
+            Self::Xxhdpi => android("xxhdpi"),
+            Self::Xxxhdpi => android("xxxhdpi"),
+            Self::Ios1x => format!("{}.png", name).into(),
+            Self::Ios2x => format!("{}@2x.png", name).into(),
+            Self::Ios3x => format!("{}@3x.png", name).into(),
+        }
+    }
+}
+
+/// The set of densities a batch export generates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DensitySet {
+    /// A single `<name>.png` per icon, at the chosen size.
+    #[default]
+    Single,
+    /// One image per Android density bucket (`mdpi` to `xxxhdpi`).
+    Android,
+    /// iOS' `@1x`, `@2x` and `@3x` images.
+    Ios,
+}
+
+impl DensitySet {
+    /// All sets, for pickers.
+    pub const ALL: [Self; 3] = [Self::Single, Self::Android, Self::Ios];
+
+    /// Parses `single`, `android` or `ios`.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "single" => Some(Self::Single),
+            "android" => Some(Self::Android),
+            "ios" => Some(Self::Ios),
+            _ => None,
+        }
+    }
+
+    /// The relative paths and options of the images of the icon named `name`.
+    pub fn files(
+        &self,
+        name: &str,
+        options: &RasterOptions,
+    ) -> Vec<(std::path::PathBuf, RasterOptions)> {
+        let densities: &[Density] = match self {
+            Self::Single => return vec![(format!("{}.png", name).into(), *options)],
+            Self::Android => &Density::ANDROID,
+            Self::Ios => &Density::IOS,
+        };
+        densities
+            .iter()
+            .map(|density| (density.file_path(name), options.scaled(*density)))
+            .collect()
+    }
+}
+
+impl std::fmt::Display for DensitySet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Single => write!(f, "Single image"),
+            Self::Android => write!(f, "Android (mdpi…xxxhdpi)"),
+            Self::Ios => write!(f, "iOS (@1x, @2x, @3x)"),
+        }
+    }
+}
+
+/// Why an icon could not be rendered.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RasterError {
+    /// The glyph outline could not be extracted from the font.
+    Glyph(GlyphError),
+    /// The image would be empty or too large (its size, in pixels).
+    InvalidSize(u64),
+    /// The PNG encoder failed.
+    Encoding(String),
+}
+
+impl std::fmt::Display for RasterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Glyph(e) => write!(f, "{}", e),
+            Self::InvalidSize(size) => write!(f, "Invalid image size: {}px", size),
+            Self::Encoding(message) => write!(f, "The PNG could not be encoded: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for RasterError {}
+
+impl std::convert::From<GlyphError> for RasterError {
+    fn from(value: GlyphError) -> Self {
+        Self::Glyph(value)
+    }
+}
+
+/// The largest image rendered, in pixels (per side).
+pub const MAX_IMAGE_SIZE: u32 = 4096;
+
+/// Renders `outline` to a PNG image.
+///
+/// ```
+/// use iced_material_icon_browser::raster::{render_png, RasterOptions};
+/// use iced_material_icon_browser::{resources::ICONS_FONT_BYTES, GlyphOutline};
+///
+/// let outline = GlyphOutline::from_font(ICONS_FONT_BYTES, 0xe88a).unwrap();
+/// let png = render_png(&outline, &RasterOptions { size: 48, padding: 8, ..Default::default() });
+/// assert!(png.unwrap().starts_with(b"\x89PNG"));
+/// ```
+pub fn render_png(outline: &GlyphOutline, options: &RasterOptions) -> Result<Vec<u8>, RasterError> {
+    let invalid_size =
+        || RasterError::InvalidSize(u64::from(options.size) + 2 * u64::from(options.padding));
+    let image_size = options
+        .image_size()
+        .filter(|image_size| options.size > 0 && *image_size <= MAX_IMAGE_SIZE)
+        .ok_or_else(invalid_size)?;
+    let mut pixmap = tiny_skia::Pixmap::new(image_size, image_size).ok_or_else(invalid_size)?;
+    if let Some(background) = options.background {
+        pixmap.fill(to_skia_color(background));
+    }
+
+    if let Some(path) = to_skia_path(outline) {
+        let mut paint = tiny_skia::Paint::default();
+        paint.set_color(to_skia_color(options.foreground));
+        paint.anti_alias = true;
+        let scale = options.size as f32 / GlyphOutline::GRID_SIZE;
+        let transform = tiny_skia::Transform::from_scale(scale, scale)
+            .post_translate(options.padding as f32, options.padding as f32);
+        // NOTE: TrueType contours are filled with the non-zero winding rule.
+        pixmap.fill_path(&path, &paint, tiny_skia::FillRule::Winding, transform, None);
+    }
+
+    pixmap
+        .encode_png()
+        .map_err(|e| RasterError::Encoding(e.to_string()))
+}
+
+/// Renders `item` with the glyph of its codepoint in `font_bytes`, once per density of
+/// `densities`, returning the images with their paths relative to the output directory.
+pub fn render_icon(
+    font_bytes: &[u8],
+    item: &MaterialFontMeta,
+    options: &RasterOptions,
+    densities: DensitySet,
+) -> Result<Vec<(std::path::PathBuf, Vec<u8>)>, RasterError> {
+    let outline = GlyphOutline::from_font(font_bytes, item.codepoint())?;
+    densities
+        .files(item.name(), options)
+        .into_iter()
+        .map(|(path, options)| Ok((path, render_png(&outline, &options)?)))
+        .collect()
+}
+
+fn to_skia_color(color: Rgba) -> tiny_skia::Color {
+    tiny_skia::Color::from_rgba8(color.r, color.g, color.b, color.a)
+}
+
+fn to_skia_path(outline: &GlyphOutline) -> Option<tiny_skia::Path> {
+    let mut builder = tiny_skia::PathBuilder::new();
+    for segment in outline.segments() {
+        match segment {
+            Segment::MoveTo(p) => builder.move_to(p.x, p.y),
+            Segment::LineTo(p) => builder.line_to(p.x, p.y),
+            Segment::QuadTo(c, p) => builder.quad_to(c.x, c.y, p.x, p.y),
+            Segment::CubicTo(c1, c2, p) => builder.cubic_to(c1.x, c1.y, c2.x, c2.y, p.x, p.y),
+            Segment::Close => builder.close(),
+        }
+    }
+    // NOTE: `finish` returns `None` for empty outlines, which render as blank images.
+    builder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::ICONS_FONT_BYTES;
+
+    fn options(size: u32, padding: u32) -> RasterOptions {
+        RasterOptions {
+            size,
+            padding,
+            ..Default::default()
+        }
+    }
+
+    fn home() -> GlyphOutline {
+        GlyphOutline::from_font(ICONS_FONT_BYTES, 0xe88a).unwrap()
+    }
+
+    /// The width and height of a PNG image, read from its `IHDR` chunk.
+    fn png_size(png: &[u8]) -> (u32, u32) {
+        let word = |offset: usize| u32::from_be_bytes(png[offset..offset + 4].try_into().unwrap());
+        (word(16), word(20))
+    }
+
+    #[test]
+    fn image_sizes() {
+        assert_eq!(options(24, 0).image_size(), Some(24));
+        assert_eq!(options(48, 8).image_size(), Some(64));
+        assert_eq!(options(u32::MAX, 0).image_size(), Some(u32::MAX));
+        assert_eq!(options(24, 3_000_000_000).image_size(), None);
+        assert_eq!(options(u32::MAX, 1).image_size(), None);
+    }
+
+    #[test]
+    fn scaled_options() {
+        let options = RasterOptions {
+            background: Some(Rgba::WHITE),
+            ..options(24, 3)
+        };
+        let scaled = |density| {
+            let scaled = options.scaled(density);
+            assert_eq!(scaled.background, options.background);
+            (scaled.size, scaled.padding)
+        };
+        assert_eq!(scaled(Density::Mdpi), (24, 3));
+        assert_eq!(scaled(Density::Hdpi), (36, 5));
+        assert_eq!(scaled(Density::Xhdpi), (48, 6));
+        assert_eq!(scaled(Density::Xxhdpi), (72, 9));
+        assert_eq!(scaled(Density::Xxxhdpi), (96, 12));
+        assert_eq!(scaled(Density::Ios3x), (72, 9));
+        let huge = self::options(3_000_000_000, 0).scaled(Density::Xxxhdpi);
+        assert_eq!(huge.size, u32::MAX);
+    }
+
+    #[test]
+    fn density_files() {
+        let paths = |set: DensitySet| {
+            set.files("home", &options(24, 0))
+                .into_iter()
+                .map(|(path, options)| (path.to_string_lossy().replace('\\', "/"), options.size))
+                .collect::<Vec<(String, u32)>>()
+        };
+        assert_eq!(paths(DensitySet::Single), [("home.png".into(), 24)]);
+        assert_eq!(
+            paths(DensitySet::Android),
+            [
+                ("drawable-mdpi/ic_home.png".into(), 24),
+                ("drawable-hdpi/ic_home.png".into(), 36),
+                ("drawable-xhdpi/ic_home.png".into(), 48),
+                ("drawable-xxhdpi/ic_home.png".into(), 72),
+                ("drawable-xxxhdpi/ic_home.png".into(), 96),
+            ]
+        );
+        assert_eq!(
+            paths(DensitySet::Ios),
+            [
+                ("home.png".into(), 24),
+                ("home@2x.png".into(), 48),
+                ("home@3x.png".into(), 72),
+            ]
+        );
+    }
+
+    #[test]
+    fn rendered_sizes() {
+        let png = render_png(&home(), &options(48, 8)).unwrap();
+        assert_eq!(png_size(&png), (64, 64));
+        let png = render_png(&home(), &options(MAX_IMAGE_SIZE, 0)).unwrap();
+        assert_eq!(png_size(&png), (MAX_IMAGE_SIZE, MAX_IMAGE_SIZE));
+    }
+
+    #[test]
+    fn invalid_sizes_are_rejected() {
+        let error = |size, padding| render_png(&home(), &options(size, padding)).unwrap_err();
+        assert_eq!(error(0, 8), RasterError::InvalidSize(16));
+        assert_eq!(error(MAX_IMAGE_SIZE + 1, 0), RasterError::InvalidSize(4097));
+        assert_eq!(error(4000, 100), RasterError::InvalidSize(4200));
+        assert_eq!(
+            error(24, 3_000_000_000),
+            RasterError::InvalidSize(6_000_000_024)
+        );
+        assert_eq!(
+            error(u32::MAX, u32::MAX),
+            RasterError::InvalidSize(3 * u64::from(u32::MAX))
+        );
+        let huge = options(2_000_000_000, 0).scaled(Density::Xxxhdpi);
+        assert_eq!(
+            render_png(&home(), &huge),
+            Err(RasterError::InvalidSize(u64::from(u32::MAX)))
+        );
+    }
+}
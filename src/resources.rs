@@ -0,0 +1,19 @@
+//! The Material Icons font and metadata snapshot bundled with the crate.
+
+/// The `MaterialIcons-Regular.ttf` font.
+#[cfg(windows)]
+pub const ICONS_FONT_BYTES: &[u8] = include_bytes!("..\\resources\\MaterialIcons-Regular.ttf");
+/// The `MaterialIcons-Regular.ttf` font.
+#[cfg(unix)]
+pub const ICONS_FONT_BYTES: &[u8] = include_bytes!("../resources/MaterialIcons-Regular.ttf");
+/// The `2023-09-12` snapshot of the icon metadata.
+#[cfg(windows)]
+pub const ICONS_META_BYTES: &[u8] =
+    include_bytes!("..\\resources\\2023-09-12-material-icons-meta.json");
+/// The `2023-09-12` snapshot of the icon metadata.
+#[cfg(unix)]
+pub const ICONS_META_BYTES: &[u8] =
+    include_bytes!("../resources/2023-09-12-material-icons-meta.json");
+
+/// The family name of [`ICONS_FONT_BYTES`].
+pub const ICONS_FONT_NAME: &str = "Material Icons";
@@ -0,0 +1,361 @@
+//! Fuzzy, ranked matching of a free-form search text against icon names, tags and categories.
+
+use crate::index::intersect_sorted;
+use crate::models::{MaterialFontMeta, MaterialFontMetaList};
+
+const WEIGHT_NAME: f32 = 1.0;
+const WEIGHT_TAG: f32 = 0.7;
+const WEIGHT_CATEGORY: f32 = 0.5;
+
+const MATCH_EXACT: f32 = 1.0;
+const MATCH_PREFIX: f32 = 0.8;
+const MATCH_SUBSTRING: f32 = 0.5;
+const MATCH_FUZZY: f32 = 0.4;
+const MATCH_FUZZY_PREFIX: f32 = 0.3;
+
+const BONUS_FULL_NAME: f32 = 0.5;
+const BONUS_NAME_PREFIX: f32 = 0.25;
+const BONUS_CODEPOINT: f32 = 1.5;
+const BONUS_CODEPOINT_SUFFIX: f32 = 0.2;
+
+/// How much popularity (normalized to `0.0..=1.0`) contributes to the score.
+const POPULARITY_WEIGHT: f32 = 0.25;
+
+/// Substring and hex codepoint suffix matches are ignored for shorter words, since they would
+/// match most icons.
+const MIN_PARTIAL_LENGTH: usize = 3;
+
+/// A search text, split into lowercase words (on whitespace, `_` and `-`).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchText {
+    raw: String,
+    words: Vec<String>,
+}
+
+impl SearchText {
+    /// Splits `text` into words.
+    pub fn parse(text: &str) -> Self {
+        let raw = text.trim().to_lowercase();
+        let words = raw
+            .split(|c: char| c.is_whitespace() || c == '_' || c == '-')
+            .filter(|word| !word.is_empty())
+            .map(String::from)
+            .collect();
+        Self { raw, words }
+    }
+
+    /// Whether there is nothing to search for.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// The lowercase words of the search text.
+    pub fn words(&self) -> &Vec<String> {
+        &self.words
+    }
+
+    /// How well `item` matches, or `None` when some word doesn't match the item at all. Every
+    /// word is matched against the name's words, the full name, the tags and the categories
+    /// (exactly, as a prefix, as a substring or with typos, in decreasing order of quality), and
+    /// the match qualities are averaged. Exact codepoints (`58820`, `e5c4`) also match.
+    ///
+    /// `popularity` is the item's popularity normalized to `0.0..=1.0`, which is blended in so
+    /// that popular icons come first among similar matches.
+    pub fn score(&self, item: &MaterialFontMeta, popularity: f32) -> Option<f32> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let quality = self.codepoint_score(item).max(self.words_score(item));
+        if quality <= 0.0 {
+            return None;
+        }
+
+        Some(quality + POPULARITY_WEIGHT * popularity)
+    }
+
+    /// The positions (in `list`) of the icons for which [`SearchText::score`] is not `None`,
+    /// found through the list's [index](MaterialFontMetaList::index) rather than by scoring
+    /// every icon.
+    pub fn candidates(&self, list: &MaterialFontMetaList) -> Vec<usize> {
+        let mut candidates: Option<Vec<usize>> = None;
+        for word in self.words.iter() {
+            let positions = list
+                .index()
+                .positions_matching(|token| term_score(word, token) > 0.0);
+            candidates = Some(match candidates {
+                Some(candidates) => intersect_sorted(&candidates, &positions),
+                None => positions,
+            });
+        }
+        let mut candidates = candidates.unwrap_or_default();
+
+        if self.may_be_codepoint() {
+            candidates.extend(
+                list.items()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, item)| self.codepoint_score(item) > 0.0)
+                    .map(|(position, _)| position),
+            );
+            candidates.sort_unstable();
+            candidates.dedup();
+        }
+        candidates
+    }
+
+    fn may_be_codepoint(&self) -> bool {
+        let hex = self.raw.trim_start_matches("0x");
+        !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit())
+    }
+
+    fn codepoint_score(&self, item: &MaterialFontMeta) -> f32 {
+        if item.matches_codepoint(&self.raw) {
+            return BONUS_CODEPOINT;
+        }
+        if self.may_be_codepoint() {
+            let hex = self.raw.trim_start_matches("0x");
+            if u32::from_str_radix(hex, 16).ok() == Some(item.codepoint()) {
+                return BONUS_CODEPOINT;
+            }
+            if hex.len() >= MIN_PARTIAL_LENGTH && item.matches_hex_codepoint(hex) {
+                return BONUS_CODEPOINT_SUFFIX;
+            }
+        }
+        0.0
+    }
+
+    fn words_score(&self, item: &MaterialFontMeta) -> f32 {
+        let name = item.name().to_lowercase();
+        let mut total = 0.0;
+        for word in self.words.iter() {
+            let best = name
+                .split('_')
+                .chain(std::iter::once(name.as_str()))
+                .map(|term| WEIGHT_NAME * term_score(word, term))
+                .chain(
+                    item.tags()
+                        .iter()
+                        .map(|tag| WEIGHT_TAG * term_score(word, &tag.to_lowercase())),
+                )
+                .chain(
+                    item.categories()
+                        .iter()
+                        .map(|category| WEIGHT_CATEGORY * term_score(word, category)),
+                )
+                .fold(0.0f32, f32::max);
+            if best <= 0.0 {
+                return 0.0;
+            }
+            total += best;
+        }
+
+        let joined = self.words.join("_");
+        let bonus = if name == joined {
+            BONUS_FULL_NAME
+        } else if name.starts_with(&joined) {
+            BONUS_NAME_PREFIX
+        } else {
+            0.0
+        };
+
+        total / self.words.len() as f32 + bonus
+    }
+}
+
+/// The quality of the match between a search `word` and an item `term`, from `0.0` (no match)
+/// to `1.0` (exact match).
+pub fn term_score(word: &str, term: &str) -> f32 {
+    if word == term {
+        return MATCH_EXACT;
+    }
+    if term.starts_with(word) {
+        return MATCH_PREFIX;
+    }
+    let word_length = word.chars().count();
+    if word_length >= MIN_PARTIAL_LENGTH && term.contains(word) {
+        return MATCH_SUBSTRING;
+    }
+
+    let allowed = allowed_typos(word_length);
+    if allowed == 0 {
+        return 0.0;
+    }
+    let word = word.chars().collect::<Vec<char>>();
+    let term = term.chars().collect::<Vec<char>>();
+    let quality = |distance: usize| 1.0 - distance as f32 / (word_length + 1) as f32;
+
+    let distance = edit_distance(&word, &term);
+    if distance <= allowed {
+        return MATCH_FUZZY * quality(distance);
+    }
+
+    // NOTE: Words that are still being typed ("setin") are compared to the beginning of the term
+    // ("settin"), give or take a character.
+    let prefix_distance = (word.len().saturating_sub(1)..=word.len() + 1)
+        .filter(|length| *length < term.len())
+        .map(|length| edit_distance(&word, &term[..length]))
+        .min();
+    match prefix_distance {
+        Some(distance) if distance <= allowed => MATCH_FUZZY_PREFIX * quality(distance),
+        _ => 0.0,
+    }
+}
+
+fn allowed_typos(word_length: usize) -> usize {
+    match word_length {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// The optimal string alignment distance between `a` and `b`: the number of insertions,
+/// deletions, substitutions and transpositions of adjacent characters needed to turn one into
+/// the other.
+pub fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous_previous = vec![0; b.len() + 1];
+    let mut previous = (0..=b.len()).collect::<Vec<usize>>();
+    let mut current = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        current[0] = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            current[j] = (previous[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(previous_previous[j - 2] + 1);
+            }
+        }
+        std::mem::swap(&mut previous_previous, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::tests::bundled;
+    use crate::Query;
+
+    fn distance(a: &str, b: &str) -> usize {
+        edit_distance(
+            &a.chars().collect::<Vec<char>>(),
+            &b.chars().collect::<Vec<char>>(),
+        )
+    }
+
+    fn names(text: &str, list: &MaterialFontMetaList) -> Vec<String> {
+        Query::new()
+            .search_text(text)
+            .run(list)
+            .iter()
+            .map(|item| item.name().clone())
+            .collect()
+    }
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(distance("", ""), 0);
+        assert_eq!(distance("home", "home"), 0);
+        assert_eq!(distance("", "home"), 4);
+        assert_eq!(distance("home", ""), 4);
+        assert_eq!(distance("kitten", "sitting"), 3);
+        assert_eq!(distance("setings", "settings"), 1);
+        assert_eq!(distance("hoem", "home"), 1);
+        assert_eq!(distance("seach", "search"), 1);
+        // NOTE: Optimal string alignment doesn't edit a transposed pair again, unlike the
+        // Damerau-Levenshtein distance (for which this is 2).
+        assert_eq!(distance("ca", "abc"), 3);
+    }
+
+    #[test]
+    fn term_scores() {
+        assert_eq!(term_score("home", "home"), MATCH_EXACT);
+        assert_eq!(term_score("ho", "home"), MATCH_PREFIX);
+        assert_eq!(term_score("row", "arrow"), MATCH_SUBSTRING);
+        assert_eq!(term_score("ro", "arrow"), 0.0);
+        assert_eq!(
+            term_score("setings", "settings"),
+            MATCH_FUZZY * (1.0 - 1.0 / 8.0)
+        );
+        assert_eq!(term_score("hoem", "home"), MATCH_FUZZY * (1.0 - 1.0 / 5.0));
+        assert_eq!(
+            term_score("setin", "settings"),
+            MATCH_FUZZY_PREFIX * (1.0 - 1.0 / 6.0)
+        );
+        // NOTE: Short words must match exactly (or as a prefix), and long ones allow two typos.
+        assert_eq!(term_score("hme", "home"), 0.0);
+        assert!(term_score("notifcaton", "notification") > 0.0);
+        assert_eq!(term_score("home", "search"), 0.0);
+    }
+
+    #[test]
+    fn scores() {
+        let list = bundled();
+        let item = |name| list.get_item_by_name(name).unwrap();
+        let text = SearchText::parse("arrow back");
+        assert_eq!(text.words(), &["arrow", "back"]);
+        assert!(text.score(item("arrow_back"), 0.0) > text.score(item("arrow_back_ios"), 0.0));
+        assert_eq!(text.score(item("home"), 0.0), None);
+        assert_eq!(
+            SearchText::parse("e88a").score(item("home"), 0.0),
+            Some(BONUS_CODEPOINT)
+        );
+        assert_eq!(SearchText::parse("  ").score(item("home"), 1.0), None);
+    }
+
+    #[test]
+    fn candidates_are_the_scored_icons() {
+        let list = bundled();
+        for text in [
+            "arrow back",
+            "setings",
+            "e5c4",
+            "88a",
+            "av",
+            "zzzzzz",
+            "arrow settings",
+        ] {
+            let text = SearchText::parse(text);
+            let scored = list
+                .items()
+                .iter()
+                .enumerate()
+                .filter(|(_, item)| text.score(item, 0.0).is_some())
+                .map(|(position, _)| position)
+                .collect::<Vec<usize>>();
+            assert_eq!(text.candidates(list), scored, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn typos_are_tolerated() {
+        let names = names("setings", bundled());
+        assert_eq!(names[0], "settings");
+        assert!(names.contains(&"settings_applications".to_string()));
+    }
+
+    #[test]
+    fn full_names_rank_first() {
+        assert_eq!(names("arrow back", bundled())[0], "arrow_back");
+        assert_eq!(names("arrow_back", bundled())[0], "arrow_back");
+        assert_eq!(names("e5c4", bundled())[0], "arrow_back");
+    }
+
+    #[test]
+    fn name_matches_outrank_tag_matches() {
+        // NOTE: `bolt` is more popular, and has `flash` as a tag.
+        let items = serde_json::json!([
+            {"name": "bolt", "popularity": 1000, "codepoint": 0xea0b, "categories": [],
+                "tags": ["flash"]},
+            {"name": "flash", "popularity": 0, "codepoint": 0xe3e7, "categories": [],
+                "tags": []},
+        ]);
+        let items = serde_json::from_value(items).unwrap();
+        let list = MaterialFontMetaList::from_items(Default::default(), items);
+        assert_eq!(names("flash", &list), ["flash", "bolt"]);
+    }
+}
@@ -0,0 +1,632 @@
+//! Code snippets referencing an icon from other frameworks and languages (e.g. Flutter's
+//! `Icons.arrow_back`), to be pasted into projects using the Material Icons. Besides the built-in
+//! [`Snippet`]s, users can define their own [`SnippetTemplate`]s in the configuration directory.
+
+use serde::{Deserialize, Serialize};
+
+use crate::export::{leading_digit_identifier, pascal_case};
+use crate::models::{LoadError, MaterialFontMeta};
+
+/// The version of the snippets file read by this crate.
+const FILE_VERSION: u32 = 1;
+
+/// A built-in snippet template.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Snippet {
+    /// An Iced `text` widget rendering the glyph with the icon font.
+    Iced,
+    /// A Rust constant holding the codepoint, named like the browser's own (`CODEPOINT_HOME`).
+    RustConst,
+    /// An HTML `<span>` using the ligature of the Google Fonts stylesheet.
+    Html,
+    /// A CSS `content` declaration, for pseudo-elements.
+    Css,
+    /// Flutter's `Icons` class.
+    Flutter,
+    /// A SwiftUI `Text` rendering the glyph with the icon font, which must be bundled with the
+    /// app.
+    SwiftUi,
+    /// A component of the `@mui/icons-material` package.
+    ReactMui,
+    /// Jetpack Compose's `Icons.Filled` (from `material-icons-core` or `-extended`).
+    Compose,
+}
+
+impl Snippet {
+    /// All templates, in the order they are listed in the preview.
+    pub const ALL: [Self; 8] = [
+        Self::Iced,
+        Self::RustConst,
+        Self::Html,
+        Self::Css,
+        Self::Flutter,
+        Self::SwiftUi,
+        Self::ReactMui,
+        Self::Compose,
+    ];
+
+    /// The snippet referencing `item`.
+    ///
+    /// ```
+    /// use iced_material_icon_browser::{snippets::Snippet, MaterialFontMetaList};
+    ///
+    /// let list = MaterialFontMetaList::bundled().unwrap();
+    /// let item = list.get_item_by_name("arrow_back").unwrap();
+    /// assert_eq!(Snippet::Css.render(item), r#"content: "\e5c4";"#);
+    /// assert_eq!(Snippet::Compose.render(item), "Icons.Filled.ArrowBack");
+    ///
+    /// // Flutter and MUI spell out leading numbers.
+    /// let item = list.get_item_by_name("10k").unwrap();
+    /// assert_eq!(Snippet::Flutter.render(item), "Icon(Icons.ten_k)");
+    /// ```
+    pub fn render(&self, item: &MaterialFontMeta) -> String {
+        let name = item.name();
+        let hex = item.to_hex_codepoint();
+        match self {
+            Self::Iced => format!(
+                "iced::widget::text(char::from_u32(0x{}).unwrap())\n    .font(iced::Font::with_name(\"Material Icons\"))",
+                hex
+            ),
+            Self::RustConst => format!(
+                "const CODEPOINT_{}: u32 = {};",
+                name.to_uppercase(),
+                item.codepoint()
+            ),
+            Self::Html => format!("<span class=\"material-icons\">{}</span>", name),
+            Self::Css => format!("content: \"\\{}\";", hex),
+            Self::Flutter => format!("Icon(Icons.{})", flutter_identifier(name)),
+            Self::SwiftUi => format!(
+                "Text(\"\\u{{{}}}\")\n    .font(.custom(\"MaterialIcons-Regular\", size: 24))",
+                hex
+            ),
+            Self::ReactMui => {
+                let component = mui_component(name);
+                format!(
+                    "import {component}Icon from '@mui/icons-material/{component}';\n\n<{component}Icon />"
+                )
+            }
+            Self::Compose => format!("Icons.Filled.{}", leading_digit_identifier(&pascal_case(name))),
+        }
+    }
+}
+
+impl std::fmt::Display for Snippet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Iced => write!(f, "Iced"),
+            Self::RustConst => write!(f, "Rust"),
+            Self::Html => write!(f, "HTML"),
+            Self::Css => write!(f, "CSS"),
+            Self::Flutter => write!(f, "Flutter"),
+            Self::SwiftUi => write!(f, "SwiftUI"),
+            Self::ReactMui => write!(f, "React (MUI)"),
+            Self::Compose => write!(f, "Compose"),
+        }
+    }
+}
+
+/// The icon names that are reserved words in Dart, to which Flutter appends an underscore
+/// (`Icons.class_`).
+const DART_RESERVED_WORDS: [&str; 2] = ["class", "try"];
+
+const NUMBER_WORDS: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const TENS_WORDS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+/// `name` with its leading number spelled out in words separated by `_` (e.g. `ten_k` for
+/// `10k`, `twenty_one_mp` for `21mp`), or digit by digit above 99 (`onetwothree` for `123`), as
+/// identifiers can't start with a digit. Other names are returned as is.
+fn spell_out_leading_number(name: &str) -> String {
+    let digits = name
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(name.len());
+    let Ok(number) = name[..digits].parse::<usize>() else {
+        return name.into();
+    };
+    let words = match number {
+        0..=19 => NUMBER_WORDS[number].to_string(),
+        20..=99 if number % 10 == 0 => TENS_WORDS[number / 10].to_string(),
+        20..=99 => format!("{}_{}", TENS_WORDS[number / 10], NUMBER_WORDS[number % 10]),
+        _ => name[..digits]
+            .chars()
+            .map(|digit| NUMBER_WORDS[digit as usize - '0' as usize])
+            .collect(),
+    };
+    match name[digits..].trim_start_matches('_') {
+        "" => words,
+        rest => format!("{}_{}", words, rest),
+    }
+}
+
+/// The name of `name` in Flutter's `Icons` class (e.g. `ten_k` for `10k`, `class_` for
+/// `class`).
+pub(crate) fn flutter_identifier(name: &str) -> String {
+    // NOTE: Flutter doesn't separate these words.
+    match name {
+        "360" => return "threesixty".into(),
+        "3d_rotation" => return "threed_rotation".into(),
+        _ => {}
+    }
+    if DART_RESERVED_WORDS.contains(&name) {
+        return format!("{}_", name);
+    }
+    spell_out_leading_number(name)
+}
+
+/// The name of the `@mui/icons-material` component of `name`, without the `Icon` suffix of its
+/// default import (e.g. `TenK` for `10k`).
+fn mui_component(name: &str) -> String {
+    match name {
+        "123" => "OneTwoThree".into(),
+        "360" => "ThreeSixty".into(),
+        _ => pascal_case(&spell_out_leading_number(name)),
+    }
+}
+
+/// A value a [`SnippetTemplate`] can interpolate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Placeholder {
+    Name,
+    NamePascal,
+    NameCamel,
+    NameUpper,
+    Codepoint,
+    Hex,
+    Char,
+    Categories,
+    Tags,
+}
+
+impl Placeholder {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "name" => Some(Self::Name),
+            "name_pascal" => Some(Self::NamePascal),
+            "name_camel" => Some(Self::NameCamel),
+            "name_upper" => Some(Self::NameUpper),
+            "codepoint" => Some(Self::Codepoint),
+            "hex" => Some(Self::Hex),
+            "char" => Some(Self::Char),
+            "categories" => Some(Self::Categories),
+            "tags" => Some(Self::Tags),
+            _ => None,
+        }
+    }
+
+    fn value(&self, item: &MaterialFontMeta) -> String {
+        match self {
+            Self::Name => item.name().clone(),
+            Self::NamePascal => pascal_case(item.name()),
+            Self::NameCamel => crate::export::camel_case(item.name()),
+            Self::NameUpper => item.name().to_uppercase(),
+            Self::Codepoint => item.codepoint().to_string(),
+            Self::Hex => item.to_hex_codepoint(),
+            Self::Char => item.to_char().to_string(),
+            Self::Categories => item.categories().join(", "),
+            Self::Tags => item.tags().join(", "),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum TemplatePart {
+    Text(String),
+    Placeholder(Placeholder),
+}
+
+/// Why a snippet template was rejected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TemplateError {
+    /// A `{...}` placeholder isn't one of the supported ones.
+    UnknownPlaceholder(String),
+    /// A `{` isn't closed, or a `}` wasn't opened (literal braces being written `{{` and `}}`).
+    UnmatchedBrace(usize),
+}
+
+impl std::fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownPlaceholder(name) => write!(f, "unknown placeholder {{{}}}", name),
+            Self::UnmatchedBrace(offset) => write!(
+                f,
+                "unmatched brace at offset {} (literal braces are written {{{{ and }}}})",
+                offset
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+/// A user-defined snippet, whose template interpolates the following placeholders:
+///
+/// | Placeholder | Value (for `arrow_back`) |
+/// | --- | --- |
+/// | `{name}`, `{name_pascal}`, `{name_camel}`, `{name_upper}` | `arrow_back`, `ArrowBack`, `arrowBack`, `ARROW_BACK` |
+/// | `{codepoint}`, `{hex}`, `{char}` | `58820`, `e5c4`, the character itself |
+/// | `{categories}`, `{tags}` | The comma-separated categories and tags |
+///
+/// ```
+/// use iced_material_icon_browser::{snippets::SnippetTemplate, MaterialFontMetaList};
+///
+/// let list = MaterialFontMetaList::bundled().unwrap();
+/// let item = list.get_item_by_name("arrow_back").unwrap();
+/// let template = SnippetTemplate::new("Icons enum", "Icon::{name_pascal} => {{ 0x{hex} }}").unwrap();
+/// assert_eq!(template.render(item), "Icon::ArrowBack => { 0xe5c4 }");
+/// assert!(SnippetTemplate::new("Typo", "{nmae}").is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "SnippetTemplateFields", into = "SnippetTemplateFields")]
+pub struct SnippetTemplate {
+    name: String,
+    template: String,
+    parts: Vec<TemplatePart>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct SnippetTemplateFields {
+    name: String,
+    template: String,
+}
+
+impl std::convert::TryFrom<SnippetTemplateFields> for SnippetTemplate {
+    type Error = TemplateError;
+
+    fn try_from(value: SnippetTemplateFields) -> Result<Self, Self::Error> {
+        Self::new(value.name, value.template)
+    }
+}
+
+impl std::convert::From<SnippetTemplate> for SnippetTemplateFields {
+    fn from(value: SnippetTemplate) -> Self {
+        Self {
+            name: value.name,
+            template: value.template,
+        }
+    }
+}
+
+impl SnippetTemplate {
+    /// Parses `template`, a snippet named `name` (as listed in the preview).
+    pub fn new(
+        name: impl Into<String>,
+        template: impl Into<String>,
+    ) -> Result<Self, TemplateError> {
+        let template = template.into();
+        let mut parts = vec![];
+        let mut text = String::new();
+        let mut chars = template.char_indices().peekable();
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '{' if chars.next_if(|(_, c)| *c == '{').is_some() => text.push('{'),
+                '}' if chars.next_if(|(_, c)| *c == '}').is_some() => text.push('}'),
+                '{' => {
+                    let mut placeholder = String::new();
+                    loop {
+                        match chars.next() {
+                            Some((_, '}')) => break,
+                            Some((_, c)) => placeholder.push(c),
+                            None => return Err(TemplateError::UnmatchedBrace(offset)),
+                        }
+                    }
+                    let placeholder = Placeholder::parse(placeholder.trim())
+                        .ok_or(TemplateError::UnknownPlaceholder(placeholder))?;
+                    if !text.is_empty() {
+                        parts.push(TemplatePart::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(TemplatePart::Placeholder(placeholder));
+                }
+                '}' => return Err(TemplateError::UnmatchedBrace(offset)),
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(TemplatePart::Text(text));
+        }
+        Ok(Self {
+            name: name.into(),
+            template,
+            parts,
+        })
+    }
+
+    /// The name of the snippet.
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    /// The unparsed template.
+    pub fn template(&self) -> &String {
+        &self.template
+    }
+
+    /// The snippet referencing `item`.
+    pub fn render(&self, item: &MaterialFontMeta) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                TemplatePart::Text(text) => text.clone(),
+                TemplatePart::Placeholder(placeholder) => placeholder.value(item),
+            })
+            .collect()
+    }
+}
+
+/// The snippet templates defined by the user, which are listed after the built-in ones.
+///
+/// The file isn't written by the application, but by users, and looks like:
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "snippets": [
+///     {"name": "Icon enum", "template": "Icon::{name_pascal} => '\\u{{{hex}}}',"},
+///     {"name": "Docs", "template": "![{name}](icons/{name}.svg)"}
+///   ]
+/// }
+/// ```
+///
+/// Invalid templates are skipped (see [`CustomSnippets::rejected`]), rather than rejecting the
+/// whole file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CustomSnippets {
+    snippets: Vec<SnippetTemplate>,
+    rejected: Vec<(String, TemplateError)>,
+}
+
+/// The contents of a snippets file, before the templates are parsed.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct CustomSnippetsFile {
+    version: u32,
+    snippets: Vec<SnippetTemplateFields>,
+}
+
+impl CustomSnippets {
+    /// The name of the snippets file in the [configuration directory](crate::config::config_dir).
+    pub const FILE_NAME: &'static str = "snippets.json";
+
+    /// The default location of the snippets file.
+    pub fn default_path() -> Option<std::path::PathBuf> {
+        crate::config::config_file(Self::FILE_NAME)
+    }
+
+    /// Loads the snippets from `path`, a missing file meaning no snippets.
+    pub async fn load(path: impl AsRef<std::path::Path>) -> Result<Self, LoadError> {
+        match crate::config::read_if_exists(path).await? {
+            Some(bytes) => Self::from_json(&bytes),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Parses the contents of a snippets file, skipping the invalid templates.
+    ///
+    /// ```
+    /// use iced_material_icon_browser::snippets::CustomSnippets;
+    ///
+    /// let json = br#"{"version": 1, "snippets": [
+    ///     {"name": "Typo", "template": "{nmae}"},
+    ///     {"name": "Docs", "template": "![{name}](icons/{name}.svg)"}
+    /// ]}"#;
+    /// let snippets = CustomSnippets::from_json(json).unwrap();
+    /// assert_eq!(snippets.snippets()[0].name(), "Docs");
+    /// assert_eq!(snippets.rejected()[0].0, "Typo");
+    /// ```
+    pub fn from_json(bytes: &[u8]) -> Result<Self, LoadError> {
+        let file = serde_json::from_slice::<CustomSnippetsFile>(bytes)
+            .map_err(LoadError::from_config_json)?;
+        if file.version > FILE_VERSION {
+            return Err(LoadError::Invalid(format!(
+                "unsupported snippets file version {} (expected at most {})",
+                file.version, FILE_VERSION
+            )));
+        }
+        let mut snippets = Self::default();
+        for fields in file.snippets {
+            match SnippetTemplate::new(fields.name.clone(), fields.template) {
+                Ok(template) => snippets.snippets.push(template),
+                Err(e) => snippets.rejected.push((fields.name, e)),
+            }
+        }
+        Ok(snippets)
+    }
+
+    /// The valid templates, in the order of the file.
+    pub fn snippets(&self) -> &Vec<SnippetTemplate> {
+        &self.snippets
+    }
+
+    /// The names of the templates that were skipped, and why, in the order of the file.
+    pub fn rejected(&self) -> &Vec<(String, TemplateError)> {
+        &self.rejected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MaterialFontMetaList;
+
+    #[test]
+    fn flutter_identifiers() {
+        for (name, identifier) in [
+            ("arrow_back", "arrow_back"),
+            ("class", "class_"),
+            ("try", "try_"),
+            ("10k", "ten_k"),
+            ("1k_plus", "one_k_plus"),
+            ("21mp", "twenty_one_mp"),
+            ("30fps_select", "thirty_fps_select"),
+            ("18_up_rating", "eighteen_up_rating"),
+            ("6_ft_apart", "six_ft_apart"),
+            ("4g_plus_mobiledata", "four_g_plus_mobiledata"),
+            ("123", "onetwothree"),
+            ("360", "threesixty"),
+            ("3d_rotation", "threed_rotation"),
+        ] {
+            assert_eq!(flutter_identifier(name), identifier);
+        }
+    }
+
+    #[test]
+    fn mui_components() {
+        for (name, component) in [
+            ("arrow_back", "ArrowBack"),
+            ("10k", "TenK"),
+            ("10mp", "TenMp"),
+            ("24mp", "TwentyFourMp"),
+            ("1x_mobiledata", "OneXMobiledata"),
+            ("60fps", "SixtyFps"),
+            ("18_up_rating", "EighteenUpRating"),
+            ("123", "OneTwoThree"),
+            ("360", "ThreeSixty"),
+            ("3d_rotation", "ThreeDRotation"),
+        ] {
+            assert_eq!(mui_component(name), component);
+        }
+    }
+
+    /// Whether `identifier` starts with a letter (or `_`), and only has letters, digits and `_`.
+    fn is_identifier(identifier: &str) -> bool {
+        identifier.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && identifier
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+    }
+
+    #[test]
+    fn snippets_of_all_icons_are_identifiers() {
+        let list = MaterialFontMetaList::bundled().unwrap();
+        for item in list.items() {
+            let flutter = flutter_identifier(item.name());
+            assert!(is_identifier(&flutter), "{}", flutter);
+            assert!(
+                !DART_RESERVED_WORDS.contains(&flutter.as_str()),
+                "{}",
+                flutter
+            );
+            let component = mui_component(item.name());
+            assert!(is_identifier(&component), "{}", component);
+            assert!(component.starts_with(|c: char| c.is_ascii_uppercase()));
+        }
+    }
+
+    fn render(template: &str, name: &str) -> String {
+        let list = MaterialFontMetaList::bundled().unwrap();
+        let item = list.get_item_by_name(name).unwrap();
+        SnippetTemplate::new("Test", template).unwrap().render(item)
+    }
+
+    #[test]
+    fn template_placeholders() {
+        assert_eq!(
+            render(
+                "{name} {name_pascal} {name_camel} {name_upper}",
+                "arrow_back"
+            ),
+            "arrow_back ArrowBack arrowBack ARROW_BACK"
+        );
+        assert_eq!(render("{codepoint} {hex}", "arrow_back"), "58820 e5c4");
+        assert_eq!(render("{char}", "arrow_back"), "\u{e5c4}");
+        assert_eq!(render("{ name }", "arrow_back"), "arrow_back");
+        assert_eq!(render("no placeholders", "arrow_back"), "no placeholders");
+    }
+
+    #[test]
+    fn template_brace_escapes() {
+        assert_eq!(render("{{", "home"), "{");
+        assert_eq!(render("}}", "home"), "}");
+        assert_eq!(render("{{{name}}}", "home"), "{home}");
+        assert_eq!(render("\\u{{{hex}}}", "home"), "\\u{e88a}");
+        assert_eq!(render("{{name}}", "home"), "{name}");
+    }
+
+    #[test]
+    fn template_unmatched_braces() {
+        assert_eq!(
+            SnippetTemplate::new("Test", "{name"),
+            Err(TemplateError::UnmatchedBrace(0))
+        );
+        assert_eq!(
+            SnippetTemplate::new("Test", "a {name} {hex"),
+            Err(TemplateError::UnmatchedBrace(9))
+        );
+        assert_eq!(
+            SnippetTemplate::new("Test", "a } b"),
+            Err(TemplateError::UnmatchedBrace(2))
+        );
+        assert_eq!(
+            SnippetTemplate::new("Test", "{{name}}}"),
+            Err(TemplateError::UnmatchedBrace(8))
+        );
+    }
+
+    #[test]
+    fn template_unknown_placeholders() {
+        assert_eq!(
+            SnippetTemplate::new("Test", "{nmae}"),
+            Err(TemplateError::UnknownPlaceholder("nmae".into()))
+        );
+        assert_eq!(
+            SnippetTemplate::new("Test", "{}"),
+            Err(TemplateError::UnknownPlaceholder(String::new()))
+        );
+        assert_eq!(
+            SnippetTemplate::new("Test", "{Name}"),
+            Err(TemplateError::UnknownPlaceholder("Name".into()))
+        );
+    }
+
+    #[test]
+    fn from_json_skips_invalid_templates() {
+        let json = br#"{"version": 1, "snippets": [
+            {"name": "Unclosed", "template": "{name"},
+            {"name": "Valid", "template": "{name}"},
+            {"name": "Unknown", "template": "{size}"}
+        ]}"#;
+        let snippets = CustomSnippets::from_json(json).unwrap();
+        assert_eq!(snippets.snippets().len(), 1);
+        assert_eq!(snippets.snippets()[0].name(), "Valid");
+        assert_eq!(
+            snippets.rejected(),
+            &vec![
+                ("Unclosed".into(), TemplateError::UnmatchedBrace(0)),
+                (
+                    "Unknown".into(),
+                    TemplateError::UnknownPlaceholder("size".into())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn from_json_rejects_future_versions() {
+        assert!(matches!(
+            CustomSnippets::from_json(br#"{"version": 2, "snippets": []}"#),
+            Err(LoadError::Invalid(_))
+        ));
+    }
+}
@@ -0,0 +1,198 @@
+//! The state of the browser window (its geometry and what was being browsed), saved in the
+//! configuration directory on exit and restored on the next launch.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::LoadError;
+
+/// The version of the state file written by this crate.
+const FILE_VERSION: u32 = 1;
+
+/// The upgrades of state files written by older versions (see [`crate::config::Migration`]).
+///
+/// NOTE: When the schema changes, `FILE_VERSION` gets incremented and a function is appended
+/// here (e.g. one renaming a field), so that files written by older versions keep loading.
+const MIGRATIONS: &[crate::config::Migration] = &[];
+
+// NOTE: Fails the build when `FILE_VERSION` and `MIGRATIONS` get out of sync.
+const _: () = assert!(FILE_VERSION as usize == MIGRATIONS.len() + 1);
+
+/// What the sidebar selected.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "kind", content = "name", rename_all = "snake_case")]
+pub enum BrowsingSection {
+    /// All icons.
+    #[default]
+    All,
+    /// The favorite icons.
+    Favorites,
+    /// The icons of a category.
+    Category(String),
+    /// The icons of a collection.
+    Collection(String),
+}
+
+/// The window's geometry and browsing state of the last session. The file's `version` isn't a
+/// field, since files are migrated to the current version before being deserialized.
+///
+/// The file looks like:
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "window_size": [1000, 600],
+///   "window_position": [20, 40],
+///   "grid_view": true,
+///   "collapse_aliases": true,
+///   "section": {"kind": "category", "name": "av"},
+///   "name": "add",
+///   "codepoint": 57669,
+///   "search_text": "",
+///   "scroll_offset": 0.0
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SessionState {
+    /// The inner size of the window, in logical pixels.
+    pub window_size: Option<(u32, u32)>,
+    /// The position of the window on the screen, when known.
+    pub window_position: Option<(i32, i32)>,
+    /// Whether icons were displayed as a grid (rather than a list).
+    pub grid_view: bool,
+    /// Whether icons sharing a codepoint were shown once, as their canonical icon.
+    pub collapse_aliases: bool,
+    /// The selected section of the sidebar.
+    pub section: BrowsingSection,
+    /// The name of the previewed icon.
+    pub name: Option<String>,
+    /// The codepoint of the previewed icon, which previews its canonical icon when `name` is
+    /// missing (i.e. in files saved before aliases were told apart).
+    pub codepoint: Option<u32>,
+    /// The contents of the search box.
+    pub search_text: String,
+    /// The vertical scroll offset of the grid or list, in pixels.
+    pub scroll_offset: f32,
+}
+
+impl Default for SessionState {
+    fn default() -> Self {
+        Self {
+            window_size: None,
+            window_position: None,
+            grid_view: true,
+            collapse_aliases: true,
+            section: BrowsingSection::default(),
+            name: None,
+            codepoint: None,
+            search_text: String::new(),
+            scroll_offset: 0.0,
+        }
+    }
+}
+
+impl SessionState {
+    /// The name of the state file in the [configuration directory](crate::config::config_dir).
+    pub const FILE_NAME: &'static str = "state.json";
+
+    /// The default location of the state file.
+    pub fn default_path() -> Option<std::path::PathBuf> {
+        crate::config::config_file(Self::FILE_NAME)
+    }
+
+    /// Loads the state from `path`, a missing file meaning the default state. Unlike other
+    /// configuration files, the state is read synchronously, since the window's geometry is
+    /// needed before the application starts.
+    pub fn load_blocking(path: impl AsRef<std::path::Path>) -> Result<Self, LoadError> {
+        match std::fs::read(path) {
+            Ok(bytes) => Self::from_json(&bytes),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Parses the contents of a state file, migrating it from older versions of the schema.
+    pub fn from_json(bytes: &[u8]) -> Result<Self, LoadError> {
+        crate::config::from_versioned_json(bytes, "state", MIGRATIONS)
+    }
+
+    /// The contents of a state file.
+    pub fn to_json(&self) -> String {
+        crate::config::to_versioned_json(self, FILE_VERSION)
+    }
+
+    /// Writes the state to `path`.
+    pub async fn save(self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        crate::config::write(path, self.to_json()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let state = SessionState {
+            window_size: Some((1000, 600)),
+            window_position: Some((20, 40)),
+            grid_view: false,
+            collapse_aliases: false,
+            section: BrowsingSection::Category("av".into()),
+            name: Some("house".into()),
+            codepoint: Some(0xe88a),
+            search_text: "tag:arrow".into(),
+            scroll_offset: 120.0,
+        };
+        assert_eq!(
+            SessionState::from_json(state.to_json().as_bytes()).unwrap(),
+            state
+        );
+    }
+
+    #[test]
+    fn missing_version() {
+        let state = SessionState::from_json(br#"{"grid_view": false}"#).unwrap();
+        assert!(!state.grid_view);
+        assert_eq!(
+            SessionState::from_json(b"{}").unwrap(),
+            SessionState::default()
+        );
+    }
+
+    #[test]
+    fn invalid_versions() {
+        for json in [
+            r#"{"version": 0}"#,
+            r#"{"version": -1}"#,
+            r#"{"version": "1"}"#,
+            r#"{"version": 1.5}"#,
+        ] {
+            assert!(
+                matches!(
+                    SessionState::from_json(json.as_bytes()),
+                    Err(LoadError::Invalid(_))
+                ),
+                "{}",
+                json
+            );
+        }
+    }
+
+    #[test]
+    fn future_version() {
+        let json = format!(r#"{{"version": {}}}"#, FILE_VERSION + 1);
+        assert!(matches!(
+            SessionState::from_json(json.as_bytes()),
+            Err(LoadError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn not_an_object() {
+        assert!(matches!(
+            SessionState::from_json(b"[]"),
+            Err(LoadError::Invalid(_))
+        ));
+    }
+}
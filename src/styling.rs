@@ -166,11 +166,11 @@ pub enum RowButtonStyle {
     LightlyBordered,
     Selected,
 }
-pub struct RowButton(RowButtonStyle);
+pub struct RowButton(RowButtonStyle, bool);
 
 impl Default for RowButton {
     fn default() -> Self {
-        Self(RowButtonStyle::Default)
+        Self(RowButtonStyle::Default, false)
     }
 }
 
@@ -192,11 +192,16 @@ impl RowButton {
     }
 
     pub fn selected() -> Self {
-        Self(RowButtonStyle::Selected)
+        Self(RowButtonStyle::Selected, false)
     }
 
     pub fn lightly_bordered() -> Self {
-        Self(RowButtonStyle::LightlyBordered)
+        Self(RowButtonStyle::LightlyBordered, false)
+    }
+
+    /// Draws a focus ring around the button (keyboard navigation).
+    pub fn focused(self, focused: bool) -> Self {
+        Self(self.0, focused)
     }
 }
 
@@ -231,6 +236,12 @@ impl iced::widget::button::StyleSheet for RowButton {
             RowButtonStyle::Selected => 1.0,
         };
 
+        let (border_color, border_width) = if self.1 {
+            (style.palette().text, 2.0)
+        } else {
+            (border_color, border_width)
+        };
+
         iced::widget::button::Appearance {
             text_color: style.palette().text,
             background: Some(background_color.into()),
@@ -0,0 +1,686 @@
+//! Subsetting of TrueType icon fonts, so that applications can embed only the glyphs they use
+//! (a few KB) rather than the whole font.
+//!
+//! The subset keeps the original codepoints and the font-wide tables (names, metrics, hinting
+//! programs), but not the ligatures of the `GSUB` table: icons are rendered by codepoint.
+
+use crate::font::{
+    binary_search_parameters, build_font, read_u16, read_u32, write_u16, write_u32, FontError,
+};
+use crate::models::{MaterialFontMeta, MaterialFontMetaListHeader};
+
+/// The tables copied as they are, which don't depend on the glyphs kept.
+const COPIED_TABLES: [&[u8; 4]; 5] = [b"cvt ", b"fpgm", b"gasp", b"name", b"prep"];
+
+/// Why a font could not be subset.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SubsetError {
+    /// The font could not be parsed.
+    Font(String),
+    /// The font has no glyph for the codepoint.
+    MissingGlyph(u32),
+    /// The font lacks a required table (e.g. `glyf`, as OpenType/CFF fonts aren't supported).
+    MissingTable(&'static str),
+    /// A table is truncated or inconsistent.
+    Malformed(&'static str),
+}
+
+impl std::fmt::Display for SubsetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Font(message) => write!(f, "The font was rejected: {}", message),
+            Self::MissingGlyph(codepoint) => {
+                write!(f, "The font has no glyph for codepoint {:x}", codepoint)
+            }
+            Self::MissingTable(tag) => {
+                write!(
+                    f,
+                    "The font has no `{}` table (only TrueType fonts are supported)",
+                    tag
+                )
+            }
+            Self::Malformed(tag) => write!(f, "The font's `{}` table is malformed", tag),
+        }
+    }
+}
+
+impl std::error::Error for SubsetError {}
+
+impl From<FontError> for SubsetError {
+    fn from(value: FontError) -> Self {
+        match value {
+            FontError::Font(message) => Self::Font(message),
+            FontError::Malformed(tag) => Self::Malformed(tag),
+        }
+    }
+}
+
+/// Builds a TrueType font containing only the glyphs of `codepoints` (and the `.notdef` glyph),
+/// mapped to the same codepoints as in `font_bytes`.
+///
+/// ```
+/// use iced_material_icon_browser::{resources::ICONS_FONT_BYTES, subset::subset_font};
+///
+/// let subset = subset_font(ICONS_FONT_BYTES, &[0xe88a, 0xe5c4]).unwrap();
+/// assert!(subset.len() < ICONS_FONT_BYTES.len() / 50);
+/// let face = ttf_parser::Face::parse(&subset, 0).unwrap();
+/// assert_eq!(face.number_of_glyphs(), 3);
+/// assert!(face.glyph_index('\u{e88a}').is_some());
+/// ```
+pub fn subset_font(font_bytes: &[u8], codepoints: &[u32]) -> Result<Vec<u8>, SubsetError> {
+    let face =
+        ttf_parser::Face::parse(font_bytes, 0).map_err(|e| SubsetError::Font(e.to_string()))?;
+    let table = |tag: &'static str| {
+        face.raw_face()
+            .table(ttf_parser::Tag::from_bytes_lossy(tag.as_bytes()))
+            .ok_or(SubsetError::MissingTable(tag))
+    };
+    let head = table("head")?;
+    let hhea = table("hhea")?;
+    let maxp = table("maxp")?;
+    let hmtx = table("hmtx")?;
+    let loca = table("loca")?;
+    let glyf = table("glyf")?;
+
+    let long_offsets = read_u16(head, 50, "head")? == 1;
+    let glyph_data = |glyph_id: u16| -> Result<&[u8], SubsetError> {
+        let (start, end) = if long_offsets {
+            let offset = glyph_id as usize * 4;
+            (
+                read_u32(loca, offset, "loca")?,
+                read_u32(loca, offset + 4, "loca")?,
+            )
+        } else {
+            let offset = glyph_id as usize * 2;
+            (
+                read_u16(loca, offset, "loca")? as u32 * 2,
+                read_u16(loca, offset + 2, "loca")? as u32 * 2,
+            )
+        };
+        glyf.get(start as usize..end as usize)
+            .ok_or(SubsetError::Malformed("loca"))
+    };
+
+    // NOTE: New glyph IDs follow the codepoints, so that consecutive codepoints make up a single
+    // `cmap` segment; the components of composite glyphs come last.
+    let mut codepoints = codepoints.to_vec();
+    codepoints.sort_unstable();
+    codepoints.dedup();
+    let mut glyph_ids = vec![0u16];
+    let mut new_ids = std::collections::HashMap::from([(0u16, 0u16)]);
+    let mut mapping = vec![];
+    for codepoint in codepoints {
+        let glyph_id = char::from_u32(codepoint)
+            .and_then(|c| face.glyph_index(c))
+            .ok_or(SubsetError::MissingGlyph(codepoint))?
+            .0;
+        let new_id = *new_ids.entry(glyph_id).or_insert_with(|| {
+            glyph_ids.push(glyph_id);
+            glyph_ids.len() as u16 - 1
+        });
+        mapping.push((codepoint, new_id));
+    }
+    let mut index = 0;
+    while index < glyph_ids.len() {
+        for component in composite_components(glyph_data(glyph_ids[index])?)? {
+            new_ids.entry(component).or_insert_with(|| {
+                glyph_ids.push(component);
+                glyph_ids.len() as u16 - 1
+            });
+        }
+        index += 1;
+    }
+
+    let mut new_glyf = vec![];
+    let mut new_loca = vec![];
+    let mut new_hmtx = vec![];
+    let number_of_metrics = read_u16(hhea, 34, "hhea")?.max(1);
+    for glyph_id in glyph_ids.iter().copied() {
+        new_loca.extend((new_glyf.len() as u32).to_be_bytes());
+        let mut data = glyph_data(glyph_id)?.to_vec();
+        remap_components(&mut data, &new_ids)?;
+        new_glyf.extend(data);
+        while new_glyf.len() % 4 != 0 {
+            new_glyf.push(0);
+        }
+
+        // NOTE: Glyphs past `numberOfHMetrics` share the last advance width, and only have their
+        // left side bearing listed.
+        let advance_offset = glyph_id.min(number_of_metrics - 1) as usize * 4;
+        new_hmtx.extend(read_u16(hmtx, advance_offset, "hmtx")?.to_be_bytes());
+        let lsb_offset = if glyph_id < number_of_metrics {
+            glyph_id as usize * 4 + 2
+        } else {
+            number_of_metrics as usize * 4 + (glyph_id - number_of_metrics) as usize * 2
+        };
+        new_hmtx.extend(read_u16(hmtx, lsb_offset, "hmtx")?.to_be_bytes());
+    }
+    new_loca.extend((new_glyf.len() as u32).to_be_bytes());
+
+    let number_of_glyphs = glyph_ids.len() as u16;
+    let mut new_head = head.to_vec();
+    write_u32(&mut new_head, 8, 0, "head")?;
+    write_u16(&mut new_head, 50, 1, "head")?;
+    let mut new_hhea = hhea.to_vec();
+    write_u16(&mut new_hhea, 34, number_of_glyphs, "hhea")?;
+    let mut new_maxp = maxp.to_vec();
+    write_u16(&mut new_maxp, 4, number_of_glyphs, "maxp")?;
+
+    let mut tables = vec![
+        (*b"cmap", build_cmap(&mapping)),
+        (*b"glyf", new_glyf),
+        (*b"head", new_head),
+        (*b"hhea", new_hhea),
+        (*b"hmtx", new_hmtx),
+        (*b"loca", new_loca),
+        (*b"maxp", new_maxp),
+    ];
+    if let Ok(post) = table("post") {
+        // NOTE: Version 3 of the `post` table has no glyph names.
+        let mut new_post = post
+            .get(..32)
+            .ok_or(SubsetError::Malformed("post"))?
+            .to_vec();
+        write_u32(&mut new_post, 0, 0x0003_0000, "post")?;
+        tables.push((*b"post", new_post));
+    }
+    if let Ok(os2) = table("OS/2") {
+        let mut new_os2 = os2.to_vec();
+        let first = mapping
+            .first()
+            .map(|(codepoint, _)| *codepoint)
+            .unwrap_or(0);
+        let last = mapping.last().map(|(codepoint, _)| *codepoint).unwrap_or(0);
+        write_u16(&mut new_os2, 64, first.min(0xffff) as u16, "OS/2")?;
+        write_u16(&mut new_os2, 66, last.min(0xffff) as u16, "OS/2")?;
+        tables.push((*b"OS/2", new_os2));
+    }
+    for tag in COPIED_TABLES {
+        if let Some(data) = face.raw_face().table(ttf_parser::Tag::from_bytes(tag)) {
+            tables.push((*tag, data.to_vec()));
+        }
+    }
+    Ok(build_font(0x0001_0000, tables))
+}
+
+/// Builds a subset of `font_bytes` for `items`, along with the matching metadata file (in the
+/// schema [`crate::MaterialFontMetaList::load_from_bytes`] reads), so that the pair can be
+/// browsed like the full icon set.
+pub fn subset_icons(
+    font_bytes: &[u8],
+    header: &MaterialFontMetaListHeader,
+    items: &[&MaterialFontMeta],
+) -> Result<(Vec<u8>, String), SubsetError> {
+    let codepoints = items
+        .iter()
+        .map(|item| item.codepoint())
+        .collect::<Vec<u32>>();
+    let font = subset_font(font_bytes, &codepoints)?;
+    Ok((font, crate::export::to_meta_json(header, items)))
+}
+
+/// The paths of the font and metadata files of a subset saved as `path`: `path` itself if it
+/// ends with `.ttf` (and with `.ttf` added, or replacing `.json`, otherwise), and the `.json`
+/// file of the same name, so that the metadata can't be written over the font.
+///
+/// ```
+/// use iced_material_icon_browser::subset::output_paths;
+/// use std::path::{Path, PathBuf};
+///
+/// let (font, meta) = output_paths(Path::new("icons/MaterialIcons-Subset.ttf"));
+/// assert_eq!(font, PathBuf::from("icons/MaterialIcons-Subset.ttf"));
+/// assert_eq!(meta, PathBuf::from("icons/MaterialIcons-Subset.json"));
+///
+/// let (font, meta) = output_paths(Path::new("icons.json"));
+/// assert_eq!((font, meta), (PathBuf::from("icons.ttf"), PathBuf::from("icons.json")));
+/// ```
+pub fn output_paths(path: &std::path::Path) -> (std::path::PathBuf, std::path::PathBuf) {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_lowercase());
+    let font = match extension.as_deref() {
+        Some("ttf") => path.to_path_buf(),
+        Some("json") => path.with_extension("ttf"),
+        // NOTE: Other extensions are kept, as they may be part of the name (e.g. `Icons-v4.0`).
+        _ => {
+            let mut font = path.as_os_str().to_owned();
+            font.push(".ttf");
+            font.into()
+        }
+    };
+    let meta = font.with_extension("json");
+    (font, meta)
+}
+
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+/// The offsets of the glyph IDs of the components of a composite glyph (none for simple
+/// glyphs).
+fn component_offsets(data: &[u8]) -> Result<Vec<usize>, SubsetError> {
+    if data.is_empty() || (read_u16(data, 0, "glyf")? as i16) >= 0 {
+        return Ok(vec![]);
+    }
+    let mut offsets = vec![];
+    let mut offset = 10;
+    loop {
+        let flags = read_u16(data, offset, "glyf")?;
+        offsets.push(offset + 2);
+        offset += 4;
+        offset += if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+            4
+        } else {
+            2
+        };
+        offset += if flags & WE_HAVE_A_SCALE != 0 {
+            2
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            4
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            8
+        } else {
+            0
+        };
+        if flags & MORE_COMPONENTS == 0 {
+            return Ok(offsets);
+        }
+    }
+}
+
+fn composite_components(data: &[u8]) -> Result<Vec<u16>, SubsetError> {
+    component_offsets(data)?
+        .into_iter()
+        .map(|offset| Ok(read_u16(data, offset, "glyf")?))
+        .collect()
+}
+
+fn remap_components(
+    data: &mut [u8],
+    new_ids: &std::collections::HashMap<u16, u16>,
+) -> Result<(), SubsetError> {
+    for offset in component_offsets(data)? {
+        let glyph_id = read_u16(data, offset, "glyf")?;
+        let new_id = *new_ids
+            .get(&glyph_id)
+            .ok_or(SubsetError::Malformed("glyf"))?;
+        write_u16(data, offset, new_id, "glyf")?;
+    }
+    Ok(())
+}
+
+/// A `cmap` table with a format 4 subtable (for the Basic Multilingual Plane, where the Material
+/// Icons are), and a format 12 one if any codepoint is beyond it.
+fn build_cmap(mapping: &[(u32, u16)]) -> Vec<u8> {
+    // NOTE: Runs of consecutive codepoints mapped to consecutive glyphs, as (start, end, glyph).
+    let mut runs: Vec<(u32, u32, u16)> = vec![];
+    for (codepoint, glyph_id) in mapping {
+        match runs.last_mut() {
+            Some((start, end, first))
+                if *end + 1 == *codepoint
+                    && *first as u32 + (*codepoint - *start) == *glyph_id as u32 =>
+            {
+                *end = *codepoint
+            }
+            _ => runs.push((*codepoint, *codepoint, *glyph_id)),
+        }
+    }
+
+    let bmp_runs = runs
+        .iter()
+        .filter(|(_, end, _)| *end < 0xffff)
+        .copied()
+        .collect::<Vec<(u32, u32, u16)>>();
+    let segment_count = bmp_runs.len() as u16 + 1;
+    let (search_range, entry_selector) = binary_search_parameters(segment_count, 2);
+    let mut format4 = vec![];
+    for value in [
+        4,
+        16 + 8 * segment_count,
+        0,
+        segment_count * 2,
+        search_range,
+        entry_selector,
+        segment_count * 2 - search_range,
+    ] {
+        format4.extend(value.to_be_bytes());
+    }
+    for (_, end, _) in &bmp_runs {
+        format4.extend((*end as u16).to_be_bytes());
+    }
+    format4.extend([0xff, 0xff, 0, 0]);
+    for (start, _, _) in &bmp_runs {
+        format4.extend((*start as u16).to_be_bytes());
+    }
+    format4.extend([0xff, 0xff]);
+    for (start, _, glyph_id) in &bmp_runs {
+        format4.extend(glyph_id.wrapping_sub(*start as u16).to_be_bytes());
+    }
+    format4.extend(1u16.to_be_bytes());
+    format4.extend(vec![0; segment_count as usize * 2]);
+
+    let mut subtables = vec![((3u16, 1u16), format4)];
+    if runs.iter().any(|(_, end, _)| *end >= 0xffff) {
+        let mut format12 = vec![];
+        format12.extend(12u16.to_be_bytes());
+        format12.extend(0u16.to_be_bytes());
+        format12.extend((16 + 12 * runs.len() as u32).to_be_bytes());
+        format12.extend(0u32.to_be_bytes());
+        format12.extend((runs.len() as u32).to_be_bytes());
+        for (start, end, glyph_id) in &runs {
+            format12.extend(start.to_be_bytes());
+            format12.extend(end.to_be_bytes());
+            format12.extend((*glyph_id as u32).to_be_bytes());
+        }
+        subtables.push(((3, 10), format12));
+    }
+
+    let mut cmap = vec![];
+    cmap.extend(0u16.to_be_bytes());
+    cmap.extend((subtables.len() as u16).to_be_bytes());
+    let mut offset = 4 + 8 * subtables.len() as u32;
+    for ((platform, encoding), subtable) in &subtables {
+        cmap.extend(platform.to_be_bytes());
+        cmap.extend(encoding.to_be_bytes());
+        cmap.extend(offset.to_be_bytes());
+        offset += subtable.len() as u32;
+    }
+    for (_, subtable) in subtables {
+        cmap.extend(subtable);
+    }
+    cmap
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::glyph::GlyphOutline;
+    use crate::resources::ICONS_FONT_BYTES;
+
+    const ARGS_ARE_XY_VALUES: u16 = 0x0002;
+
+    /// A simple glyph, with one contour through `points` (all on the curve).
+    fn simple_glyph(points: &[(i16, i16)]) -> Vec<u8> {
+        let xs = points.iter().map(|(x, _)| *x);
+        let ys = points.iter().map(|(_, y)| *y);
+        let mut data = vec![];
+        for value in [
+            1,
+            xs.clone().min().unwrap(),
+            ys.clone().min().unwrap(),
+            xs.clone().max().unwrap(),
+            ys.clone().max().unwrap(),
+            points.len() as i16 - 1,
+            0,
+        ] {
+            data.extend(value.to_be_bytes());
+        }
+        data.extend(vec![0x01; points.len()]);
+        let mut previous = 0;
+        for x in xs {
+            data.extend((x - previous).to_be_bytes());
+            previous = x;
+        }
+        let mut previous = 0;
+        for y in ys {
+            data.extend((y - previous).to_be_bytes());
+            previous = y;
+        }
+        data
+    }
+
+    /// A composite glyph, made of `components` as (flags, glyph ID, arguments and transform).
+    fn composite_glyph(components: &[(u16, u16, &[u8])]) -> Vec<u8> {
+        let mut data = vec![];
+        for value in [-1i16, 0, 0, 1000, 1000] {
+            data.extend(value.to_be_bytes());
+        }
+        for (index, (flags, glyph_id, arguments)) in components.iter().enumerate() {
+            let more = if index + 1 < components.len() {
+                MORE_COMPONENTS
+            } else {
+                0
+            };
+            data.extend((flags | more | ARGS_ARE_XY_VALUES).to_be_bytes());
+            data.extend(glyph_id.to_be_bytes());
+            data.extend(*arguments);
+        }
+        data
+    }
+
+    /// A TrueType font with `glyphs`, the first `number_of_metrics` of which have an advance width
+    /// of their own (`500 + 100 * glyph_id`), each with a left side bearing of `glyph_id`.
+    fn font(
+        glyphs: &[Vec<u8>],
+        mapping: &[(u32, u16)],
+        number_of_metrics: u16,
+        long_offsets: bool,
+    ) -> Vec<u8> {
+        let mut glyf = vec![];
+        let mut loca = vec![];
+        let mut hmtx = vec![];
+        for (glyph_id, data) in glyphs.iter().enumerate() {
+            if long_offsets {
+                loca.extend((glyf.len() as u32).to_be_bytes());
+            } else {
+                loca.extend((glyf.len() as u16 / 2).to_be_bytes());
+            }
+            glyf.extend(data);
+            while glyf.len() % 4 != 0 {
+                glyf.push(0);
+            }
+            if glyph_id < number_of_metrics as usize {
+                hmtx.extend((500 + 100 * glyph_id as u16).to_be_bytes());
+            }
+            hmtx.extend((glyph_id as i16).to_be_bytes());
+        }
+        if long_offsets {
+            loca.extend((glyf.len() as u32).to_be_bytes());
+        } else {
+            loca.extend((glyf.len() as u16 / 2).to_be_bytes());
+        }
+
+        let mut head = vec![0; 54];
+        write_u32(&mut head, 0, 0x0001_0000, "head").unwrap();
+        write_u32(&mut head, 12, 0x5f0f_3cf5, "head").unwrap();
+        write_u16(&mut head, 18, 1000, "head").unwrap();
+        write_u16(&mut head, 50, long_offsets as u16, "head").unwrap();
+        let mut hhea = vec![0; 36];
+        write_u32(&mut hhea, 0, 0x0001_0000, "hhea").unwrap();
+        write_u16(&mut hhea, 34, number_of_metrics, "hhea").unwrap();
+        let mut maxp = vec![0; 32];
+        write_u32(&mut maxp, 0, 0x0001_0000, "maxp").unwrap();
+        write_u16(&mut maxp, 4, glyphs.len() as u16, "maxp").unwrap();
+        build_font(
+            0x0001_0000,
+            vec![
+                (*b"cmap", build_cmap(mapping)),
+                (*b"glyf", glyf),
+                (*b"head", head),
+                (*b"hhea", hhea),
+                (*b"hmtx", hmtx),
+                (*b"loca", loca),
+                (*b"maxp", maxp),
+            ],
+        )
+    }
+
+    /// `.notdef`, a square (`a`), a triangle (`b`), a composite of both (`c`), and a composite of
+    /// that composite (U+1F600, beyond the Basic Multilingual Plane), only the first two glyphs
+    /// having advance widths of their own.
+    fn test_font(long_offsets: bool) -> Vec<u8> {
+        let glyphs = [
+            vec![],
+            simple_glyph(&[(0, 0), (100, 0), (100, 100), (0, 100)]),
+            simple_glyph(&[(0, 0), (200, 0), (100, 300)]),
+            composite_glyph(&[
+                (ARG_1_AND_2_ARE_WORDS, 1, &[0x01, 0x2c, 0xff, 0x9c]),
+                (WE_HAVE_A_SCALE, 2, &[10, 20, 0x20, 0x00]),
+            ]),
+            composite_glyph(&[
+                (WE_HAVE_AN_X_AND_Y_SCALE, 3, &[0, 0, 0x40, 0x00, 0x20, 0x00]),
+                (
+                    WE_HAVE_A_TWO_BY_TWO,
+                    1,
+                    &[5, 5, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x00],
+                ),
+            ]),
+        ];
+        let mapping = [(0x61, 1), (0x62, 2), (0x63, 3), (0x1f600, 4)];
+        font(&glyphs, &mapping, 2, long_offsets)
+    }
+
+    /// Checks that the glyphs of `codepoints` have the same outlines, advance widths and side
+    /// bearings in `subset` as in `original`.
+    fn assert_same_glyphs(original: &[u8], subset: &[u8], codepoints: &[u32]) {
+        let original_face = ttf_parser::Face::parse(original, 0).unwrap();
+        let face = ttf_parser::Face::parse(subset, 0).unwrap();
+        for codepoint in codepoints.iter().copied() {
+            let c = char::from_u32(codepoint).unwrap();
+            let original_id = original_face.glyph_index(c).unwrap();
+            let glyph_id = face.glyph_index(c).unwrap();
+            let outline = GlyphOutline::from_font(original, codepoint).unwrap();
+            assert!(!outline.segments().is_empty());
+            assert_eq!(
+                GlyphOutline::from_font(subset, codepoint).unwrap(),
+                outline,
+                "outline of {:x}",
+                codepoint
+            );
+            assert_eq!(
+                face.glyph_hor_advance(glyph_id),
+                original_face.glyph_hor_advance(original_id),
+                "advance of {:x}",
+                codepoint
+            );
+            assert_eq!(
+                face.glyph_hor_side_bearing(glyph_id),
+                original_face.glyph_hor_side_bearing(original_id),
+                "side bearing of {:x}",
+                codepoint
+            );
+        }
+    }
+
+    #[test]
+    fn bundled_glyphs_are_kept() {
+        let codepoints = [0xe88a, 0xe5c4, 0xe8b6, 0xe5c5, 0xf06c];
+        let subset = subset_font(ICONS_FONT_BYTES, &codepoints).unwrap();
+        assert_same_glyphs(ICONS_FONT_BYTES, &subset, &codepoints);
+        let face = ttf_parser::Face::parse(&subset, 0).unwrap();
+        assert_eq!(face.number_of_glyphs(), codepoints.len() as u16 + 1);
+        assert!(face.glyph_index('\u{e88b}').is_none());
+    }
+
+    #[test]
+    fn short_and_long_offsets() {
+        for long_offsets in [false, true] {
+            let original = test_font(long_offsets);
+            let subset = subset_font(&original, &[0x61, 0x62]).unwrap();
+            assert_same_glyphs(&original, &subset, &[0x61, 0x62]);
+        }
+    }
+
+    #[test]
+    fn glyphs_past_the_metrics_share_the_last_advance() {
+        let original = test_font(true);
+        let face = ttf_parser::Face::parse(&original, 0).unwrap();
+        assert_eq!(face.glyph_hor_advance(ttf_parser::GlyphId(2)), Some(600));
+        assert_eq!(face.glyph_hor_side_bearing(ttf_parser::GlyphId(2)), Some(2));
+
+        // NOTE: The triangle is the first glyph of the subset, so its advance is listed.
+        let subset = subset_font(&original, &[0x62]).unwrap();
+        assert_same_glyphs(&original, &subset, &[0x62]);
+    }
+
+    #[test]
+    fn composite_glyphs_keep_their_components() {
+        let original = test_font(false);
+        let subset = subset_font(&original, &[0x63]).unwrap();
+        assert_same_glyphs(&original, &subset, &[0x63]);
+        let face = ttf_parser::Face::parse(&subset, 0).unwrap();
+        assert_eq!(face.number_of_glyphs(), 4);
+        assert!(face.glyph_index('a').is_none());
+        assert!(face.glyph_index('b').is_none());
+
+        // NOTE: Components of components are kept too, and the square, used twice, only once.
+        let subset = subset_font(&original, &[0x1f600]).unwrap();
+        assert_same_glyphs(&original, &subset, &[0x1f600]);
+        assert_eq!(
+            ttf_parser::Face::parse(&subset, 0)
+                .unwrap()
+                .number_of_glyphs(),
+            5
+        );
+    }
+
+    #[test]
+    fn codepoints_beyond_the_basic_multilingual_plane() {
+        let original = test_font(true);
+        let codepoints = [0x61, 0x62, 0x63, 0x1f600];
+        let subset = subset_font(&original, &codepoints).unwrap();
+        assert_same_glyphs(&original, &subset, &codepoints);
+
+        let face = ttf_parser::Face::parse(&subset, 0).unwrap();
+        let formats = face
+            .tables()
+            .cmap
+            .unwrap()
+            .subtables
+            .into_iter()
+            .map(|subtable| match subtable.format {
+                ttf_parser::cmap::Format::SegmentMappingToDeltaValues(_) => 4,
+                ttf_parser::cmap::Format::SegmentedCoverage(_) => 12,
+                _ => 0,
+            })
+            .collect::<Vec<u8>>();
+        assert_eq!(formats, [4, 12]);
+
+        let subset = subset_font(&original, &[0x61]).unwrap();
+        let face = ttf_parser::Face::parse(&subset, 0).unwrap();
+        assert_eq!(face.tables().cmap.unwrap().subtables.len(), 1);
+    }
+
+    #[test]
+    fn missing_glyphs_are_rejected() {
+        let original = test_font(true);
+        assert_eq!(
+            subset_font(&original, &[0x61, 0x64]),
+            Err(SubsetError::MissingGlyph(0x64))
+        );
+        assert_eq!(
+            subset_font(&original, &[0xd800]),
+            Err(SubsetError::MissingGlyph(0xd800))
+        );
+    }
+
+    #[test]
+    fn malformed_fonts_are_rejected() {
+        assert!(matches!(
+            subset_font(b"not a font", &[0x61]),
+            Err(SubsetError::Font(_))
+        ));
+
+        // NOTE: The composite announces a second component, which is cut off (after the header
+        // and the first component).
+        let mut composite = composite_glyph(&[(0, 1, &[0, 0]), (0, 1, &[0, 0])]);
+        composite.truncate(10 + 6);
+        let square = simple_glyph(&[(0, 0), (100, 0), (100, 100)]);
+        let original = font(&[vec![], square, composite], &[(0x61, 2)], 1, true);
+        assert_eq!(
+            subset_font(&original, &[0x61]),
+            Err(SubsetError::Malformed("glyf"))
+        );
+
+        // NOTE: A component missing from the font.
+        let composite = composite_glyph(&[(0, 7, &[0, 0])]);
+        let original = font(&[vec![], composite], &[(0x61, 1)], 1, true);
+        assert_eq!(
+            subset_font(&original, &[0x61]),
+            Err(SubsetError::Malformed("loca"))
+        );
+    }
+}
@@ -0,0 +1,690 @@
+//! Scanning of a project's source tree for references to icons (e.g. `char::from_u32(0xe14d)`
+//! in Rust, or `Icons.home` in Flutter), to find out which icons it uses.
+
+use crate::models::{is_private_use, MaterialFontMeta, MaterialFontMetaList};
+
+/// The extensions of the files scanned.
+const SOURCE_EXTENSIONS: [&str; 16] = [
+    "rs", "dart", "html", "htm", "css", "scss", "sass", "less", "js", "jsx", "ts", "tsx", "vue",
+    "svelte", "kt", "swift",
+];
+
+/// The extensions of the files in Rust, which alone call `char::from_u32`.
+const RUST_EXTENSIONS: [&str; 1] = ["rs"];
+
+/// The extensions of the files in Dart, which alone has Flutter's `Icons` class.
+const DART_EXTENSIONS: [&str; 1] = ["dart"];
+
+/// The extensions of the files holding code, with hex literals and `\u` escapes.
+const CODE_EXTENSIONS: [&str; 10] = [
+    "rs", "dart", "js", "jsx", "ts", "tsx", "vue", "svelte", "kt", "swift",
+];
+
+/// The extensions of the files holding HTML markup (or templates of it).
+const MARKUP_EXTENSIONS: [&str; 8] = ["html", "htm", "js", "jsx", "ts", "tsx", "vue", "svelte"];
+
+/// The extensions of the files holding CSS (or `<style>` elements).
+const STYLE_EXTENSIONS: [&str; 8] = [
+    "css", "scss", "sass", "less", "html", "htm", "vue", "svelte",
+];
+
+/// The directories skipped (besides hidden ones), which hold dependencies or build artifacts.
+const SKIPPED_DIRECTORIES: [&str; 4] = ["target", "node_modules", "build", "dist"];
+
+/// Files larger than this (e.g. minified bundles) are skipped.
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// The suffixes of the outlined, rounded and sharp variants of Flutter's icons (e.g.
+/// `Icons.home_outlined`), which are listed under the name of the filled one.
+const FLUTTER_STYLE_SUFFIXES: [&str; 3] = ["_outlined", "_rounded", "_sharp"];
+
+/// How an icon is referenced.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ReferenceKind {
+    /// `char::from_u32(57677)` or `char::from_u32(0xe14d)` in Rust.
+    FromU32,
+    /// A `CODEPOINT_*: u32 = 57677` constant, like the browser's own.
+    CodepointConstant,
+    /// A `0xe14d` literal.
+    HexLiteral,
+    /// A `\u{e14d}` (Rust, Swift, ES6) or `\ue14d` (Dart, JavaScript, Kotlin) escape.
+    UnicodeEscape,
+    /// An HTML element with the `material-icons` class (e.g. `<span class="material-icons">`).
+    HtmlLigature,
+    /// A CSS `content: "\e14d"` declaration.
+    CssContent,
+    /// Flutter's `Icons.content_copy` (or `Icons.content_copy_outlined`, etc.).
+    Flutter,
+}
+
+impl std::fmt::Display for ReferenceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FromU32 => write!(f, "char::from_u32"),
+            Self::CodepointConstant => write!(f, "CODEPOINT_* constant"),
+            Self::HexLiteral => write!(f, "hex literal"),
+            Self::UnicodeEscape => write!(f, "unicode escape"),
+            Self::HtmlLigature => write!(f, "HTML ligature"),
+            Self::CssContent => write!(f, "CSS content"),
+            Self::Flutter => write!(f, "Flutter Icons"),
+        }
+    }
+}
+
+/// What a reference designates an icon by.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ReferenceKey {
+    /// A codepoint.
+    Codepoint(u32),
+    /// A name (a ligature, or a Flutter identifier).
+    Name(String),
+}
+
+impl std::fmt::Display for ReferenceKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Codepoint(codepoint) => write!(f, "{:04x}", codepoint),
+            Self::Name(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// A reference to an icon in a source file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IconReference {
+    /// The path of the file, relative to the scanned directory.
+    pub path: std::path::PathBuf,
+    /// The line of the reference, starting at 1.
+    pub line: usize,
+    /// How the icon is referenced.
+    pub kind: ReferenceKind,
+    /// What the icon is designated by.
+    pub key: ReferenceKey,
+}
+
+impl IconReference {
+    /// The icon referenced, if `meta_list` has it.
+    ///
+    /// ```
+    /// use iced_material_icon_browser::usage::scan_source;
+    /// use iced_material_icon_browser::MaterialFontMetaList;
+    ///
+    /// let list = MaterialFontMetaList::bundled().unwrap();
+    /// let references = scan_source("main.dart", "Icons.home_outlined, Icons.ten_k");
+    /// assert_eq!(references[0].resolve(&list).unwrap().name(), "home");
+    /// assert_eq!(references[1].resolve(&list).unwrap().name(), "10k");
+    /// ```
+    pub fn resolve<'a>(&self, meta_list: &'a MaterialFontMetaList) -> Option<&'a MaterialFontMeta> {
+        match &self.key {
+            ReferenceKey::Codepoint(codepoint) => meta_list.get_item(*codepoint),
+            ReferenceKey::Name(name) if self.kind == ReferenceKind::Flutter => {
+                resolve_flutter_name(name, meta_list)
+            }
+            ReferenceKey::Name(name) => meta_list.get_item_by_name(name),
+        }
+    }
+
+    /// The location of the reference, as `path:line`.
+    pub fn location(&self) -> String {
+        format!("{}:{}", self.path.display(), self.line)
+    }
+}
+
+/// The icon of Flutter's `Icons.<name>`, `name` being the identifier without the underscore
+/// appended to reserved words.
+fn resolve_flutter_name<'a>(
+    name: &str,
+    meta_list: &'a MaterialFontMetaList,
+) -> Option<&'a MaterialFontMeta> {
+    // NOTE: The name is tried as is first, since some icons end with a style suffix themselves
+    // (e.g. `insert_chart_outlined`).
+    let base_name = FLUTTER_STYLE_SUFFIXES
+        .iter()
+        .find_map(|suffix| name.strip_suffix(suffix))
+        .map(|base_name| base_name.trim_end_matches('_'));
+    std::iter::once(name).chain(base_name).find_map(|name| {
+        meta_list.get_item_by_name(name).or_else(|| {
+            // NOTE: Flutter spells out leading numbers (`Icons.ten_k` for `10k`).
+            meta_list
+                .items()
+                .iter()
+                .filter(|item| item.name().starts_with(|c: char| c.is_ascii_digit()))
+                .find(|item| crate::snippets::flutter_identifier(item.name()) == name)
+        })
+    })
+}
+
+struct Pattern {
+    kind: ReferenceKind,
+    regex: regex::Regex,
+    /// Whether the first group is a name (rather than a codepoint).
+    name: bool,
+    /// The extensions (in lowercase) of the files the pattern applies to, so that e.g. Flutter's
+    /// `Icons.home` isn't looked for in Kotlin files.
+    extensions: &'static [&'static str],
+}
+
+fn patterns() -> &'static [Pattern] {
+    static PATTERNS: std::sync::OnceLock<Vec<Pattern>> = std::sync::OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let pattern = |kind, regex: &str, name, extensions| Pattern {
+            kind,
+            regex: regex::Regex::new(regex).expect("the pattern should be valid"),
+            name,
+            extensions,
+        };
+        vec![
+            pattern(
+                ReferenceKind::FromU32,
+                r"char::from_u32\(\s*(0x[0-9a-fA-F]+|[0-9]+)\s*\)",
+                false,
+                &RUST_EXTENSIONS,
+            ),
+            pattern(
+                ReferenceKind::CodepointConstant,
+                r"CODEPOINT_\w*\s*:\s*u32\s*=\s*(0x[0-9a-fA-F]+|[0-9]+)",
+                false,
+                &RUST_EXTENSIONS,
+            ),
+            pattern(
+                ReferenceKind::HexLiteral,
+                r"\b(0x[eEfF][0-9a-fA-F]{3})\b",
+                false,
+                &CODE_EXTENSIONS,
+            ),
+            pattern(
+                ReferenceKind::UnicodeEscape,
+                r"\\u\{([0-9a-fA-F]{4,6})\}|\\u([eEfF][0-9a-fA-F]{3})",
+                false,
+                &CODE_EXTENSIONS,
+            ),
+            pattern(
+                ReferenceKind::HtmlLigature,
+                r#"class\s*=\s*["'][^"']*\bmaterial-icons(?:-[a-z]+)?\b[^"']*["'][^>]*>\s*([a-z0-9_]+)\s*<"#,
+                true,
+                &MARKUP_EXTENSIONS,
+            ),
+            pattern(
+                ReferenceKind::CssContent,
+                r#"content\s*:\s*["']\\([0-9a-fA-F]{4,6})["']"#,
+                false,
+                &STYLE_EXTENSIONS,
+            ),
+            pattern(
+                ReferenceKind::Flutter,
+                r"\bIcons\.([a-z_][a-z0-9_]*)\b",
+                true,
+                &DART_EXTENSIONS,
+            ),
+        ]
+    })
+}
+
+/// The icon references of `source`, the contents of the file at `path`. Only the references
+/// of the file's language (told by its extension) are looked for: Flutter's `Icons.home` in a Dart
+/// file, but not in a Kotlin one.
+///
+/// Codepoints of hex literals and escapes are only reported in the Private Use Areas (e.g.
+/// `0xe14d` but not `0xff00`), since other values are unlikely to be icons.
+///
+/// ```
+/// use iced_material_icon_browser::usage::{scan_source, ReferenceKey};
+///
+/// let source = "const CODEPOINT_COPY: u32 = 57677;\nlet c = '\\u{e5c4}'; let mask = 0xff00;";
+/// let keys = scan_source("main.rs", source)
+///     .into_iter()
+///     .map(|reference| (reference.line, reference.key))
+///     .collect::<Vec<_>>();
+/// assert_eq!(keys, [(1, ReferenceKey::Codepoint(57677)), (2, ReferenceKey::Codepoint(0xe5c4))]);
+///
+/// let source = r#"<span class="material-icons md-18">home</span> Icons.class_"#;
+/// let keys = scan_source("index.html", source)
+///     .into_iter()
+///     .map(|reference| reference.key)
+///     .collect::<Vec<_>>();
+/// assert_eq!(keys, [ReferenceKey::Name("home".into())]);
+/// ```
+pub fn scan_source(path: impl AsRef<std::path::Path>, source: &str) -> Vec<IconReference> {
+    let extension = source_extension(path.as_ref());
+    let patterns = patterns()
+        .iter()
+        .filter(|pattern| {
+            extension
+                .as_deref()
+                .is_some_and(|extension| pattern.extensions.contains(&extension))
+        })
+        .collect::<Vec<&Pattern>>();
+    let mut references = vec![];
+    for (index, line) in source.lines().enumerate() {
+        for pattern in &patterns {
+            for captures in pattern.regex.captures_iter(line) {
+                let Some(value) = captures.iter().skip(1).flatten().next() else {
+                    continue;
+                };
+                let value = value.as_str();
+                let key = if pattern.name {
+                    // NOTE: Flutter appends `_` to names that are reserved words (`Icons.class_`).
+                    ReferenceKey::Name(value.trim_end_matches('_').into())
+                } else {
+                    // NOTE: Codepoints passed to `char::from_u32` or assigned to `CODEPOINT_*`
+                    // constants are icons, whatever their value (and may be decimal).
+                    let explicit = matches!(
+                        pattern.kind,
+                        ReferenceKind::FromU32 | ReferenceKind::CodepointConstant
+                    );
+                    let codepoint = match value.strip_prefix("0x") {
+                        Some(hex) => u32::from_str_radix(hex, 16),
+                        None if explicit => value.parse(),
+                        None => u32::from_str_radix(value, 16),
+                    };
+                    match codepoint {
+                        Ok(codepoint) if explicit || is_private_use(codepoint) => {
+                            ReferenceKey::Codepoint(codepoint)
+                        }
+                        _ => continue,
+                    }
+                };
+                // NOTE: `char::from_u32(0xe14d)` also matches the hex literal pattern, which comes
+                // later, so the more specific kind is kept.
+                let duplicate = references.iter().any(|reference: &IconReference| {
+                    reference.line == index + 1 && reference.key == key
+                });
+                if !duplicate {
+                    references.push(IconReference {
+                        path: path.as_ref().into(),
+                        line: index + 1,
+                        kind: pattern.kind,
+                        key,
+                    });
+                }
+            }
+        }
+    }
+    references
+}
+
+/// The extension of the file at `path`, in lowercase.
+fn source_extension(path: &std::path::Path) -> Option<String> {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_lowercase())
+}
+
+/// The icon references found in a project's source tree.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProjectUsage {
+    root: std::path::PathBuf,
+    references: Vec<IconReference>,
+    skipped: Vec<(std::path::PathBuf, String)>,
+}
+
+impl ProjectUsage {
+    /// Scans the source files (`.rs`, `.dart`, `.html`, `.css`, `.js`, ...) under `root`, skipping
+    /// hidden directories and those of dependencies and build artifacts (e.g. `target`).
+    ///
+    /// Only an unreadable `root` fails the scan: other entries that can't be read are skipped,
+    /// and listed by [`Self::skipped`].
+    pub fn scan(root: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let root = root.as_ref();
+        let mut references = vec![];
+        let mut skipped = vec![];
+        let mut skip = |path: &std::path::Path, e: std::io::Error| {
+            skipped.push((
+                path.strip_prefix(root).unwrap_or(path).into(),
+                e.to_string(),
+            ));
+        };
+        let mut directories = vec![root.to_path_buf()];
+        while let Some(directory) = directories.pop() {
+            let read_dir = match std::fs::read_dir(&directory) {
+                Ok(read_dir) => read_dir,
+                Err(e) if directory == root => return Err(e),
+                Err(e) => {
+                    skip(&directory, e);
+                    continue;
+                }
+            };
+            let mut entries = vec![];
+            for entry in read_dir {
+                match entry {
+                    Ok(entry) => entries.push(entry),
+                    Err(e) => skip(&directory, e),
+                }
+            }
+            entries.sort_by_key(|entry| entry.file_name());
+            for entry in entries {
+                let path = entry.path();
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                let file_type = match entry.file_type() {
+                    Ok(file_type) => file_type,
+                    Err(e) => {
+                        skip(&path, e);
+                        continue;
+                    }
+                };
+                if file_type.is_dir() {
+                    if !name.starts_with('.') && !SKIPPED_DIRECTORIES.contains(&name.as_ref()) {
+                        directories.push(path);
+                    }
+                    continue;
+                }
+                let is_source = source_extension(&path)
+                    .map(|extension| SOURCE_EXTENSIONS.contains(&extension.as_str()))
+                    .unwrap_or(false);
+                if !file_type.is_file() || !is_source {
+                    continue;
+                }
+                match entry.metadata() {
+                    Ok(metadata) if metadata.len() > MAX_FILE_SIZE => continue,
+                    Ok(_) => {}
+                    Err(e) => {
+                        skip(&path, e);
+                        continue;
+                    }
+                }
+                let source = match std::fs::read_to_string(&path) {
+                    Ok(source) => source,
+                    // NOTE: Files that aren't UTF-8 aren't source files of interest.
+                    Err(e) if e.kind() == std::io::ErrorKind::InvalidData => continue,
+                    Err(e) => {
+                        skip(&path, e);
+                        continue;
+                    }
+                };
+                let relative_path = path.strip_prefix(root).unwrap_or(&path);
+                references.extend(scan_source(relative_path, &source));
+            }
+        }
+        references.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+        Ok(Self {
+            root: root.into(),
+            references,
+            skipped,
+        })
+    }
+
+    /// The scanned directory.
+    pub fn root(&self) -> &std::path::PathBuf {
+        &self.root
+    }
+
+    /// All references, by file and line.
+    pub fn references(&self) -> &Vec<IconReference> {
+        &self.references
+    }
+
+    /// The files and directories that couldn't be read (relative to the scanned directory), with
+    /// the error, in scan order.
+    pub fn skipped(&self) -> &Vec<(std::path::PathBuf, String)> {
+        &self.skipped
+    }
+
+    /// The icons used, by name, with their references.
+    pub fn used_icons<'a>(
+        &'a self,
+        meta_list: &'a MaterialFontMetaList,
+    ) -> Vec<(&'a MaterialFontMeta, Vec<&'a IconReference>)> {
+        let mut icons =
+            std::collections::BTreeMap::<&String, (&MaterialFontMeta, Vec<&IconReference>)>::new();
+        for reference in &self.references {
+            if let Some(item) = reference.resolve(meta_list) {
+                icons
+                    .entry(item.name())
+                    .or_insert_with(|| (item, vec![]))
+                    .1
+                    .push(reference);
+            }
+        }
+        icons.into_values().collect()
+    }
+
+    /// The references to icons that `meta_list` doesn't have (e.g. typos, or icons removed from
+    /// the font).
+    pub fn unknown_references(&self, meta_list: &MaterialFontMetaList) -> Vec<&IconReference> {
+        self.references
+            .iter()
+            .filter(|reference| reference.resolve(meta_list).is_none())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The names of the icons the references of `source` resolve to (`None` if unknown).
+    fn resolve(path: &str, source: &str) -> Vec<Option<String>> {
+        let list = MaterialFontMetaList::bundled().unwrap();
+        scan_source(path, source)
+            .iter()
+            .map(|reference| reference.resolve(&list).map(|item| item.name().clone()))
+            .collect()
+    }
+
+    #[test]
+    fn flutter_style_suffixes() {
+        assert_eq!(
+            resolve(
+                "main.dart",
+                "Icons.home Icons.home_outlined Icons.home_rounded Icons.home_sharp"
+            ),
+            vec![Some("home".to_string()); 4]
+        );
+        assert_eq!(
+            resolve(
+                "main.dart",
+                "Icons.insert_chart_outlined Icons.insert_chart_outlined_sharp"
+            ),
+            vec![Some("insert_chart_outlined".to_string()); 2]
+        );
+    }
+
+    #[test]
+    fn flutter_reserved_words() {
+        assert_eq!(
+            resolve(
+                "main.dart",
+                "Icons.class_ Icons.class_outlined Icons.try_sharp"
+            ),
+            vec![
+                Some("class".to_string()),
+                Some("class".to_string()),
+                Some("try".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn flutter_leading_numbers() {
+        assert_eq!(
+            resolve(
+                "main.dart",
+                "Icons.ten_k Icons.ten_k_rounded Icons.twenty_one_mp Icons.eighteen_up_rating"
+            ),
+            vec![
+                Some("10k".to_string()),
+                Some("10k".to_string()),
+                Some("21mp".to_string()),
+                Some("18_up_rating".to_string())
+            ]
+        );
+        assert_eq!(
+            resolve(
+                "main.dart",
+                "Icons.onetwothree Icons.threesixty Icons.threed_rotation_outlined"
+            ),
+            vec![
+                Some("123".to_string()),
+                Some("360".to_string()),
+                Some("3d_rotation".to_string())
+            ]
+        );
+    }
+
+    /// A new empty directory, named after the test.
+    fn temporary_directory(test: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "iced-material-icon-browser-{}-{}",
+            test,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        path
+    }
+
+    #[test]
+    fn scan_project() {
+        let root = temporary_directory("scan");
+        std::fs::create_dir_all(root.join("lib")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules")).unwrap();
+        std::fs::write(root.join("lib/main.dart"), "Icon(Icons.home_outlined)").unwrap();
+        std::fs::write(root.join("lib/binary.rs"), [0xff, 0xfe, 0x00]).unwrap();
+        std::fs::write(root.join("node_modules/index.js"), "'\\ue88a'").unwrap();
+        std::fs::write(root.join("notes.txt"), "Icons.search").unwrap();
+
+        let usage = ProjectUsage::scan(&root).unwrap();
+        let paths = usage
+            .references()
+            .iter()
+            .map(|reference| reference.location())
+            .collect::<Vec<String>>();
+        assert_eq!(
+            paths,
+            [std::path::Path::new("lib")
+                .join("main.dart:1")
+                .display()
+                .to_string()]
+        );
+        assert!(usage.skipped().is_empty());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn scan_missing_root() {
+        let root = temporary_directory("missing").join("missing");
+        assert!(ProjectUsage::scan(root).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn scan_skips_unreadable_directories() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = temporary_directory("unreadable");
+        std::fs::create_dir_all(root.join("private")).unwrap();
+        std::fs::write(root.join("main.dart"), "Icons.home").unwrap();
+        std::fs::set_permissions(root.join("private"), std::fs::Permissions::from_mode(0o000))
+            .unwrap();
+        let usage = ProjectUsage::scan(&root);
+        std::fs::set_permissions(root.join("private"), std::fs::Permissions::from_mode(0o755))
+            .unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        let usage = usage.unwrap();
+        assert_eq!(usage.references().len(), 1);
+        // NOTE: Permissions don't apply to root, which can read the directory anyway.
+        if !usage.skipped().is_empty() {
+            assert_eq!(usage.skipped()[0].0, std::path::Path::new("private"));
+        }
+    }
+
+    #[test]
+    fn unknown_names() {
+        // NOTE: `Icons.10k` isn't a Dart identifier, so it isn't a reference at all.
+        assert_eq!(
+            resolve(
+                "main.dart",
+                "Icons.not_an_icon Icons.not_an_icon_outlined Icons.10k"
+            ),
+            vec![None, None]
+        );
+        // NOTE: Ligatures name the icon exactly, without Flutter's conventions.
+        assert_eq!(
+            resolve(
+                "index.html",
+                r#"<span class="material-icons">home_outlined</span>"#
+            ),
+            vec![None]
+        );
+    }
+
+    /// The kinds of the references of `source`, in a file named `path`.
+    fn kinds(path: &str, source: &str) -> Vec<ReferenceKind> {
+        scan_source(path, source)
+            .into_iter()
+            .map(|reference| reference.kind)
+            .collect()
+    }
+
+    #[test]
+    fn patterns_apply_to_their_languages() {
+        let source = "Icons.home char::from_u32(0xe88a) '\\ue8b6'";
+        assert_eq!(
+            kinds("main.rs", source),
+            [ReferenceKind::FromU32, ReferenceKind::UnicodeEscape]
+        );
+        assert_eq!(
+            kinds("main.dart", source),
+            [
+                ReferenceKind::HexLiteral,
+                ReferenceKind::UnicodeEscape,
+                ReferenceKind::Flutter
+            ]
+        );
+        // NOTE: Compose's `Icons.Filled.Home` (or Kotlin's `Icons.home` properties) aren't
+        // Flutter's icons.
+        assert_eq!(
+            kinds("MainActivity.kt", source),
+            [ReferenceKind::HexLiteral, ReferenceKind::UnicodeEscape]
+        );
+        assert_eq!(kinds("styles.css", source), []);
+        assert_eq!(kinds("README.md", source), []);
+
+        let source = r#"<i class="material-icons">home</i> .icon::before { content: "\e88a"; }"#;
+        assert_eq!(
+            kinds("index.HTML", source),
+            [ReferenceKind::HtmlLigature, ReferenceKind::CssContent]
+        );
+        assert_eq!(kinds("styles.scss", source), [ReferenceKind::CssContent]);
+        assert_eq!(kinds("App.tsx", source), [ReferenceKind::HtmlLigature]);
+        assert_eq!(kinds("main.rs", source), []);
+    }
+
+    #[test]
+    fn every_source_extension_has_patterns() {
+        let extensions = patterns()
+            .iter()
+            .flat_map(|pattern| pattern.extensions.iter().copied())
+            .collect::<std::collections::BTreeSet<&str>>();
+        assert_eq!(
+            extensions,
+            SOURCE_EXTENSIONS
+                .into_iter()
+                .collect::<std::collections::BTreeSet<&str>>()
+        );
+    }
+
+    #[test]
+    fn other_languages_have_no_unknown_references() {
+        let root = temporary_directory("languages");
+        std::fs::write(
+            root.join("Icons.kt"),
+            "val icon = Icons.Filled.Home; Icons.home",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("icons.ts"),
+            "import { Icons } from './icons'; Icons.add",
+        )
+        .unwrap();
+        std::fs::write(root.join("main.dart"), "Icons.home").unwrap();
+
+        let usage = ProjectUsage::scan(&root).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+        let list = MaterialFontMetaList::bundled().unwrap();
+        assert_eq!(usage.references().len(), 1);
+        assert!(usage.unknown_references(&list).is_empty());
+    }
+}
@@ -0,0 +1,334 @@
+//! Consistency checks between a metadata file and the icon font it describes, to catch bad
+//! snapshot updates (e.g. a metadata file from a newer release than the font).
+
+use crate::glyph::GlyphError;
+use crate::models::MaterialFontMetaList;
+
+/// How serious a problem is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Something worth knowing about that doesn't break the browser (e.g. the font keeps the
+    /// glyphs of deprecated icons, which the metadata no longer lists, or icons are aliases of
+    /// others).
+    Warning,
+    /// Icons that can't be rendered, or can't be looked up reliably.
+    Error,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// An inconsistency between the metadata and the font.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Problem {
+    /// The font has no glyph for the codepoint of the icon.
+    MissingGlyph {
+        /// The name of the icon.
+        name: String,
+        /// Its codepoint.
+        codepoint: u32,
+    },
+    /// The font has a glyph (in a Private Use Area) that no icon has the codepoint of.
+    UnlistedGlyph {
+        /// The codepoint of the glyph.
+        codepoint: u32,
+    },
+    /// Several icons have the same codepoint, the first one being canonical and the others its
+    /// aliases (see [`MaterialFontMetaList::aliases`]).
+    DuplicateCodepoint {
+        /// The codepoint.
+        codepoint: u32,
+        /// The names of the icons, in file order.
+        names: Vec<String>,
+    },
+    /// Several icons have the same name, so only the first one can be looked up by name.
+    DuplicateName {
+        /// The name.
+        name: String,
+        /// The codepoints of the icons, in file order.
+        codepoints: Vec<u32>,
+    },
+}
+
+impl Problem {
+    /// How serious the problem is.
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::UnlistedGlyph { .. } | Self::DuplicateCodepoint { .. } => Severity::Warning,
+            Self::MissingGlyph { .. } | Self::DuplicateName { .. } => Severity::Error,
+        }
+    }
+}
+
+impl std::fmt::Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let hex_list = |codepoints: &[u32]| {
+            codepoints
+                .iter()
+                .map(|codepoint| format!("{:04x}", codepoint))
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        match self {
+            Self::MissingGlyph { name, codepoint } => {
+                write!(f, "`{}` ({:04x}) has no glyph in the font", name, codepoint)
+            }
+            Self::UnlistedGlyph { codepoint } => {
+                write!(f, "The glyph of {:04x} has no metadata", codepoint)
+            }
+            Self::DuplicateCodepoint { codepoint, names } => write!(
+                f,
+                "{:04x} is the codepoint of several icons: {}",
+                codepoint,
+                names.join(", ")
+            ),
+            Self::DuplicateName { name, codepoints } => write!(
+                f,
+                "`{}` is the name of several icons: {}",
+                name,
+                hex_list(codepoints)
+            ),
+        }
+    }
+}
+
+/// The problems found by [`validate`], errors first.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    problems: Vec<Problem>,
+}
+
+impl ValidationReport {
+    /// All problems, errors first.
+    pub fn problems(&self) -> &Vec<Problem> {
+        &self.problems
+    }
+
+    /// The number of problems of `severity`.
+    pub fn count(&self, severity: Severity) -> usize {
+        self.problems
+            .iter()
+            .filter(|problem| problem.severity() == severity)
+            .count()
+    }
+
+    /// Whether no errors were found (warnings aside).
+    pub fn is_valid(&self) -> bool {
+        self.count(Severity::Error) == 0
+    }
+}
+
+/// Checks `meta_list` against the `cmap` table of `font_bytes`: icons without glyphs, glyphs
+/// without icons, and icons sharing a codepoint or a name.
+///
+/// Only glyphs in the Private Use Areas are expected to be icons, since Material Icons also maps
+/// the letters, digits and `_` its ligatures are spelled with.
+///
+/// ```
+/// use iced_material_icon_browser::validation::validate;
+/// use iced_material_icon_browser::{resources::ICONS_FONT_BYTES, MaterialFontMetaList};
+///
+/// let report = validate(&MaterialFontMetaList::bundled().unwrap(), ICONS_FONT_BYTES).unwrap();
+/// assert!(report.is_valid());
+/// ```
+pub fn validate(
+    meta_list: &MaterialFontMetaList,
+    font_bytes: &[u8],
+) -> Result<ValidationReport, GlyphError> {
+    let face =
+        ttf_parser::Face::parse(font_bytes, 0).map_err(|e| GlyphError::Font(e.to_string()))?;
+    let mut glyphs = std::collections::BTreeSet::new();
+    if let Some(cmap) = face.tables().cmap {
+        for subtable in cmap.subtables.into_iter().filter(|s| s.is_unicode()) {
+            subtable.codepoints(|codepoint| {
+                // NOTE: Unused codepoints of a segment may map to `.notdef` (glyph 0).
+                if subtable.glyph_index(codepoint).map(|id| id.0 != 0) == Some(true) {
+                    glyphs.insert(codepoint);
+                }
+            });
+        }
+    }
+
+    let mut names_by_codepoint = std::collections::BTreeMap::<u32, Vec<String>>::new();
+    let mut codepoints_by_name = std::collections::BTreeMap::<&String, Vec<u32>>::new();
+    for item in meta_list.items() {
+        names_by_codepoint
+            .entry(item.codepoint())
+            .or_default()
+            .push(item.name().clone());
+        codepoints_by_name
+            .entry(item.name())
+            .or_default()
+            .push(item.codepoint());
+    }
+
+    let mut problems = vec![];
+    for item in meta_list.items() {
+        if !glyphs.contains(&item.codepoint()) {
+            problems.push(Problem::MissingGlyph {
+                name: item.name().clone(),
+                codepoint: item.codepoint(),
+            });
+        }
+    }
+    for (codepoint, names) in &names_by_codepoint {
+        if names.len() > 1 {
+            problems.push(Problem::DuplicateCodepoint {
+                codepoint: *codepoint,
+                names: names.clone(),
+            });
+        }
+    }
+    for (name, codepoints) in codepoints_by_name {
+        if codepoints.len() > 1 {
+            problems.push(Problem::DuplicateName {
+                name: name.clone(),
+                codepoints,
+            });
+        }
+    }
+    problems.extend(
+        glyphs
+            .into_iter()
+            .filter(|codepoint| {
+                crate::models::is_private_use(*codepoint)
+                    && !names_by_codepoint.contains_key(codepoint)
+            })
+            .map(|codepoint| Problem::UnlistedGlyph { codepoint }),
+    );
+    // NOTE: The sort is stable, so problems of the same severity keep the order above.
+    problems.sort_by_key(|problem| std::cmp::Reverse(problem.severity()));
+    Ok(ValidationReport { problems })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::ICONS_FONT_BYTES;
+
+    const HOME: u32 = 0xe88a;
+    const SEARCH: u32 = 0xe8b6;
+    const ADD: u32 = 0xe145;
+
+    /// A metadata file listing `icons` (names and codepoints).
+    fn meta_list(icons: &[(&str, u32)]) -> MaterialFontMetaList {
+        let icons = icons
+            .iter()
+            .map(|(name, codepoint)| {
+                serde_json::json!({
+                    "name": name,
+                    "popularity": 0,
+                    "codepoint": codepoint,
+                    "categories": [],
+                    "tags": [],
+                })
+            })
+            .collect::<Vec<serde_json::Value>>();
+        let json = serde_json::json!({ "icons": icons }).to_string();
+        MaterialFontMetaList::load_from_bytes(json.into_bytes()).unwrap()
+    }
+
+    /// A font with only the glyphs of `codepoints`.
+    fn font(codepoints: &[u32]) -> Vec<u8> {
+        crate::subset::subset_font(ICONS_FONT_BYTES, codepoints).unwrap()
+    }
+
+    #[test]
+    fn matching_pair() {
+        let report = validate(&meta_list(&[("home", HOME)]), &font(&[HOME])).unwrap();
+        assert!(report.problems().is_empty());
+        assert!(report.is_valid());
+    }
+
+    #[test]
+    fn missing_glyph() {
+        let report = validate(&meta_list(&[("home", HOME), ("add", ADD)]), &font(&[HOME])).unwrap();
+        assert_eq!(
+            report.problems(),
+            &vec![Problem::MissingGlyph {
+                name: "add".into(),
+                codepoint: ADD,
+            }]
+        );
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn unlisted_glyph() {
+        let report = validate(&meta_list(&[("home", HOME)]), &font(&[HOME, SEARCH])).unwrap();
+        assert_eq!(
+            report.problems(),
+            &vec![Problem::UnlistedGlyph { codepoint: SEARCH }]
+        );
+        assert_eq!(report.count(Severity::Warning), 1);
+        assert!(report.is_valid());
+    }
+
+    #[test]
+    fn duplicate_codepoint() {
+        let report = validate(
+            &meta_list(&[("home", HOME), ("house_alias", HOME)]),
+            &font(&[HOME]),
+        )
+        .unwrap();
+        assert_eq!(
+            report.problems(),
+            &vec![Problem::DuplicateCodepoint {
+                codepoint: HOME,
+                names: vec!["home".into(), "house_alias".into()],
+            }]
+        );
+        assert!(report.is_valid());
+    }
+
+    #[test]
+    fn duplicate_name() {
+        let report = validate(
+            &meta_list(&[("home", HOME), ("home", SEARCH)]),
+            &font(&[HOME, SEARCH]),
+        )
+        .unwrap();
+        assert_eq!(
+            report.problems(),
+            &vec![Problem::DuplicateName {
+                name: "home".into(),
+                codepoints: vec![HOME, SEARCH],
+            }]
+        );
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn errors_come_first() {
+        let report = validate(
+            &meta_list(&[("home", HOME), ("house_alias", HOME), ("add", ADD)]),
+            &font(&[HOME, SEARCH]),
+        )
+        .unwrap();
+        let severities = report
+            .problems()
+            .iter()
+            .map(Problem::severity)
+            .collect::<Vec<Severity>>();
+        assert_eq!(
+            severities,
+            vec![Severity::Error, Severity::Warning, Severity::Warning]
+        );
+        assert_eq!(report.count(Severity::Error), 1);
+    }
+
+    #[test]
+    fn invalid_font() {
+        assert!(matches!(
+            validate(&meta_list(&[("home", HOME)]), b"not a font"),
+            Err(GlyphError::Font(_))
+        ));
+    }
+}